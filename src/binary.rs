@@ -0,0 +1,316 @@
+//! Compact binary log format and a decoder to read it back
+//!
+//! A binary log file starts with the 8 bytes `ULOGBIN1`, followed by entries.
+//! Every entry is its length as a little-endian `u32`, followed by that many bytes:
+//!
+//! - A name definition: the byte `0`, the index of the name as a little-endian `u32`, and the name in UTF-8.
+//! - A record: the byte `1`, the time in nanoseconds since the Unix epoch as a little-endian `u64`,
+//!   the log level as a byte (`0` for "Trace" up to `5` for "Critical"), the index of the name of the logger
+//!   as a little-endian `u32`, the length of the trace id as a little-endian `u16` (`0` if there is none),
+//!   the trace id, and the message in UTF-8.
+//!
+//! A record refers to the last definition of its name index before it.
+
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::log_level::LogLevel;
+use crate::log_record::LogRecord;
+
+pub(crate) const MAGIC: &[u8; 8] = b"ULOGBIN1";
+
+const NAME_TAG: u8 = 0;
+const RECORD_TAG: u8 = 1;
+
+/// Returns the number of nanoseconds since the Unix epoch, or `0` for times before it.
+pub(crate) fn timestamp_nanos<Tz: TimeZone>(date_time: &DateTime<Tz>) -> u64 {
+    let seconds = date_time.timestamp().max(0) as u64;
+
+    seconds
+        .saturating_mul(1_000_000_000)
+        .saturating_add(u64::from(date_time.timestamp_subsec_nanos()))
+}
+
+fn push_entry(to_write: &mut Vec<u8>, entry: &[u8]) {
+    to_write.extend_from_slice(&(entry.len() as u32).to_le_bytes());
+    to_write.extend_from_slice(entry);
+}
+
+pub(crate) fn encode_name(to_write: &mut Vec<u8>, index: u32, name: &str) {
+    let mut entry = Vec::with_capacity(5 + name.len());
+    entry.push(NAME_TAG);
+    entry.extend_from_slice(&index.to_le_bytes());
+    entry.extend_from_slice(name.as_bytes());
+
+    push_entry(to_write, &entry);
+}
+
+pub(crate) fn encode_record(
+    to_write: &mut Vec<u8>,
+    nanos: u64,
+    level: LogLevel,
+    name_index: u32,
+    trace_id: Option<&str>,
+    message: &str,
+) {
+    let trace_id = trace_id.unwrap_or("").as_bytes();
+    let trace_id = &trace_id[..trace_id.len().min(u16::MAX as usize)];
+
+    let mut entry = Vec::with_capacity(16 + trace_id.len() + message.len());
+    entry.push(RECORD_TAG);
+    entry.extend_from_slice(&nanos.to_le_bytes());
+    entry.push(level as u8);
+    entry.extend_from_slice(&name_index.to_le_bytes());
+    entry.extend_from_slice(&(trace_id.len() as u16).to_le_bytes());
+    entry.extend_from_slice(trace_id);
+    entry.extend_from_slice(message.as_bytes());
+
+    push_entry(to_write, &entry);
+}
+
+/// Reads the records of a binary log, as written by a logger with `OutputFormat::Binary`.
+/// Created by [`decode_binary_log`], or by [`BinaryLogReader::new`] for other readers than files.
+///
+/// The iterator ends at the end of the log, or at the first entry that is truncated or corrupt,
+/// for example because the process was killed while writing it.
+pub struct BinaryLogReader<R: Read> {
+    reader: R,
+    names: Vec<(u32, String)>,
+}
+
+impl<R: Read> BinaryLogReader<R> {
+    /// Creates a reader of a binary log, and checks that it starts with the header of the format.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader of the binary log, positioned at its start.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of the kind `InvalidData` if the log doesn't start with the header,
+    /// or the error of the reader if the header can't be read.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Not a binary log: the header is missing",
+            ));
+        }
+
+        Ok(Self {
+            reader,
+            names: Vec::new(),
+        })
+    }
+
+    fn read_entry(&mut self) -> Option<Vec<u8>> {
+        let mut len = [0; 4];
+        self.reader.read_exact(&mut len).ok()?;
+        let len = u32::from_le_bytes(len) as usize;
+
+        // Reading through `take` grows the buffer as data arrives, so a corrupt length can't allocate gigabytes.
+        let mut entry = Vec::new();
+        (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut entry)
+            .ok()?;
+
+        if entry.len() == len {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    fn name(&self, index: u32) -> Option<&str> {
+        self.names
+            .iter()
+            .find(|(name_index, _)| *name_index == index)
+            .map(|(_, name)| name.as_str())
+    }
+
+    fn decode_record(&self, entry: &[u8]) -> Option<LogRecord> {
+        let nanos = u64::from_le_bytes(entry.get(1..9)?.try_into().ok()?);
+        let level = *LogLevel::ALL.get(usize::from(*entry.get(9)?))?;
+        let name_index = u32::from_le_bytes(entry.get(10..14)?.try_into().ok()?);
+        let trace_id_len = usize::from(u16::from_le_bytes(entry.get(14..16)?.try_into().ok()?));
+        let trace_id = entry.get(16..16 + trace_id_len)?;
+        let message = &entry[16 + trace_id_len..];
+
+        let timestamp = Utc
+            .timestamp_opt(
+                (nanos / 1_000_000_000) as i64,
+                (nanos % 1_000_000_000) as u32,
+            )
+            .single()?;
+
+        Some(LogRecord {
+            timestamp: timestamp.format("%Y-%m-%dT%H:%M:%S%.9fZ").to_string(),
+            logger: self.name(name_index)?.to_string(),
+            level,
+            trace_id: if trace_id.is_empty() {
+                None
+            } else {
+                Some(String::from_utf8_lossy(trace_id).into_owned())
+            },
+            message: String::from_utf8_lossy(message).into_owned(),
+        })
+    }
+}
+
+impl<R: Read> Iterator for BinaryLogReader<R> {
+    type Item = LogRecord;
+
+    fn next(&mut self) -> Option<LogRecord> {
+        loop {
+            let entry = self.read_entry()?;
+
+            match *entry.first()? {
+                NAME_TAG => {
+                    let index = u32::from_le_bytes(entry.get(1..5)?.try_into().ok()?);
+                    let name = String::from_utf8_lossy(&entry[5..]).into_owned();
+
+                    self.names.retain(|(name_index, _)| *name_index != index);
+                    self.names.push((index, name));
+                }
+                RECORD_TAG => return self.decode_record(&entry),
+                _ => return None,
+            }
+        }
+    }
+}
+
+/// Opens a binary log file, as written by a logger with `OutputFormat::Binary`, to read its records.
+/// The timestamps of the records are in UTC, in the RFC 3339 format with nanoseconds, like `2020-05-01T12:00:00.000000000Z`.
+///
+/// # Arguments
+///
+/// * `path` - The path to the binary log file.
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::binary;
+/// use ultimate_logger::log_level::LogLevel;
+/// use ultimate_logger::sink::OutputFormat;
+///
+/// let path = std::env::temp_dir().join("log.bin");
+/// let mut logger = Logger::new_default(String::from("example"));
+/// logger.set_output_format(OutputFormat::Binary);
+/// logger.set_append(false);
+/// logger.add_file_sink(path.to_str().unwrap(), LogLevel::Trace).unwrap();
+///
+/// logger.info("This is an info message");
+/// drop(logger);
+///
+/// for record in binary::decode_binary_log(&path).unwrap() {
+///     println!("[{}] [{}] [{}] {}", record.timestamp, record.logger, record.level, record.message);
+/// }
+/// ```
+///
+/// This will print the following message:
+///
+/// ```text
+/// [2020-12-31T23:59:59.999999999Z] [example] [info] This is an info message
+/// ```
+///
+/// # Errors
+///
+/// This function will return an error if the file can't be opened,
+/// or an error of the kind `InvalidData` if it isn't a binary log.
+pub fn decode_binary_log<P: AsRef<Path>>(path: P) -> io::Result<BinaryLogReader<BufReader<File>>> {
+    BinaryLogReader::new(BufReader::new(File::open(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_log(records: &[(LogLevel, Option<&str>, &str)]) -> Vec<u8> {
+        let mut log = MAGIC.to_vec();
+        encode_name(&mut log, 0, "test");
+
+        for (i, (level, trace_id, message)) in records.iter().enumerate() {
+            encode_record(&mut log, i as u64, *level, 0, *trace_id, message);
+        }
+
+        log
+    }
+
+    #[test]
+    fn reader_should_decode_records() {
+        let log = encode_log(&[
+            (LogLevel::Info, None, "first"),
+            (LogLevel::Critical, Some("abc"), "second\nline"),
+        ]);
+        let records: Vec<LogRecord> = BinaryLogReader::new(&log[..]).unwrap().collect();
+
+        assert_eq!(
+            records,
+            vec![
+                LogRecord {
+                    timestamp: String::from("1970-01-01T00:00:00.000000000Z"),
+                    logger: String::from("test"),
+                    level: LogLevel::Info,
+                    trace_id: None,
+                    message: String::from("first"),
+                },
+                LogRecord {
+                    timestamp: String::from("1970-01-01T00:00:00.000000001Z"),
+                    logger: String::from("test"),
+                    level: LogLevel::Critical,
+                    trace_id: Some(String::from("abc")),
+                    message: String::from("second\nline"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reader_should_use_last_name_definition() {
+        let mut log = encode_log(&[(LogLevel::Info, None, "first")]);
+        encode_name(&mut log, 0, "renamed");
+        encode_record(&mut log, 0, LogLevel::Info, 0, None, "second");
+
+        let loggers: Vec<String> = BinaryLogReader::new(&log[..])
+            .unwrap()
+            .map(|record| record.logger)
+            .collect();
+
+        assert_eq!(loggers, vec!["test", "renamed"]);
+    }
+
+    #[test]
+    fn reader_should_stop_at_truncated_or_corrupt_entry() {
+        let log = encode_log(&[
+            (LogLevel::Info, None, "first"),
+            (LogLevel::Info, None, "second"),
+        ]);
+
+        for len in MAGIC.len()..log.len() {
+            let records = BinaryLogReader::new(&log[..len]).unwrap().count();
+            assert!(records <= 1, "{}", len);
+        }
+
+        let mut corrupt = log.clone();
+        corrupt.extend_from_slice(&u32::MAX.to_le_bytes());
+        corrupt.push(RECORD_TAG);
+        assert_eq!(BinaryLogReader::new(&corrupt[..]).unwrap().count(), 2);
+
+        assert_eq!(
+            BinaryLogReader::new(&b"not a log"[..])
+                .err()
+                .unwrap()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -0,0 +1,531 @@
+//! Builder to create a logger step by step
+
+use std::path::{Path, PathBuf};
+
+use crate::config::{LoggerConfig, SinkConfig};
+use crate::error::LoggerError;
+use crate::log_file::DATE_PLACEHOLDER;
+use crate::log_level::LogLevel;
+use crate::sink::OutputFormat;
+use crate::Logger;
+
+/// Builds a [`Logger`] with chainable methods.
+///
+/// The defaults are those of `Logger::new_default`, except for the minimum log level:
+/// the logger writes to the console, not to a file, and the minimum log level is "Info".
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::builder::LoggerBuilder;
+/// use ultimate_logger::log_level::LogLevel;
+///
+/// let mut logger = LoggerBuilder::new()
+///     .name(String::from("example"))
+///     .min_level(LogLevel::Debug)
+///     .file("log.txt")
+///     .build()
+///     .unwrap();
+///
+/// logger.debug("This is a debug message");
+/// ```
+///
+/// This will log the following message to the file `log.txt`, and to the console:
+///
+/// ```text
+/// [2020-12-31 23:59:59.999] [example] [debug] This is a debug message
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LoggerBuilder {
+    name: String,
+    min_level: LogLevel,
+    file: Option<PathBuf>,
+    console: bool,
+    size_rotation: Option<(u64, usize)>,
+    rotate_daily: bool,
+    #[cfg(feature = "gzip")]
+    compress_rotated: bool,
+    truncate_on_open: bool,
+    start_banner: bool,
+    output_format: OutputFormat,
+    json_schema: bool,
+}
+
+impl Default for LoggerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LoggerBuilder {
+    /// Creates a builder with the default settings. A name has to be set before building.
+    pub fn new() -> Self {
+        Self {
+            name: String::new(),
+            min_level: LogLevel::Info,
+            file: None,
+            console: true,
+            size_rotation: None,
+            rotate_daily: false,
+            #[cfg(feature = "gzip")]
+            compress_rotated: false,
+            truncate_on_open: false,
+            start_banner: false,
+            output_format: OutputFormat::Text,
+            json_schema: false,
+        }
+    }
+
+    /// Sets the name of the logger.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the logger.
+    pub fn name(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
+
+    /// Sets the minimum log level. The default is "Info".
+    ///
+    /// # Arguments
+    ///
+    /// * `min_level` - The minimum log level.
+    pub fn min_level(mut self, min_level: LogLevel) -> Self {
+        self.min_level = min_level;
+        self
+    }
+
+    /// Sets the file to log to. If the file doesn't exist, it will be created,
+    /// otherwise it's appended to, unless [`truncate_on_open`](#method.truncate_on_open) is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the file.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.file = Some(path.into());
+        self
+    }
+
+    /// Sets whether the log file is emptied when it's opened, so it only contains the lines of this run.
+    /// The default is `false`, so the lines are appended to those of earlier runs. See `Logger::set_append`.
+    ///
+    /// # Arguments
+    ///
+    /// * `truncate_on_open` - Whether to empty the log file when it's opened.
+    pub fn truncate_on_open(mut self, truncate_on_open: bool) -> Self {
+        self.truncate_on_open = truncate_on_open;
+        self
+    }
+
+    /// Sets whether a banner line like `===== example started at 2020-12-31 23:59:59.999 pid=1234 =====`
+    /// is written to the log file when it's opened, also when it's appended to, so every run stands out.
+    /// The default is `false`. See `Logger::set_start_banner`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_banner` - Whether to write the banner.
+    pub fn with_start_banner(mut self, start_banner: bool) -> Self {
+        self.start_banner = start_banner;
+        self
+    }
+
+    /// Sets the format the logger writes its file in. The default is `OutputFormat::Text`.
+    /// See `Logger::set_output_format`.
+    ///
+    /// # Arguments
+    ///
+    /// * `output_format` - The format.
+    pub fn output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    /// Sets whether a JSON Schema describing the records is written next to the log file in the JSON format,
+    /// so `app.log` gets an `app.log.schema.json`. The default is `false`. See `Logger::set_json_schema`.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_schema` - Whether to write the schema file.
+    pub fn with_json_schema(mut self, json_schema: bool) -> Self {
+        self.json_schema = json_schema;
+        self
+    }
+
+    /// Sets whether the logger writes to the console. The default is `true`.
+    ///
+    /// # Arguments
+    ///
+    /// * `console` - Whether to write to the console.
+    pub fn console(mut self, console: bool) -> Self {
+        self.console = console;
+        self
+    }
+
+    /// Rotates the log file when it would grow larger than `max_bytes`, keeping at most `max_files` rotated files.
+    /// See `Logger::set_size_rotation` for more information.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes` - The maximum size of the log file in bytes.
+    /// * `max_files` - The number of rotated files to keep.
+    pub fn rotate_size(mut self, max_bytes: u64, max_files: usize) -> Self {
+        self.size_rotation = Some((max_bytes, max_files));
+        self
+    }
+
+    /// Starts a new log file every day. The local date replaces `{date}` in the path of the file,
+    /// or is added before the extension if the path has no `{date}`, so `app.log` becomes `app-2024-01-15.log`.
+    /// The date is checked on every write, so the first message after midnight goes to the file of the new day.
+    pub fn rotate_daily(mut self) -> Self {
+        self.rotate_daily = true;
+        self
+    }
+
+    /// Compresses rotated log files with gzip, see `Logger::set_compress_rotated`.
+    /// Only available with the `gzip` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `compress_rotated` - Whether rotated files are compressed.
+    #[cfg(feature = "gzip")]
+    pub fn compress_rotated(mut self, compress_rotated: bool) -> Self {
+        self.compress_rotated = compress_rotated;
+        self
+    }
+
+    /// Returns the path of the log file, with the date placeholder if a new file is started every day.
+    fn file_path(&self, file: &Path) -> String {
+        let path = file.to_string_lossy().into_owned();
+
+        if !self.rotate_daily || path.contains(DATE_PLACEHOLDER) {
+            return path;
+        }
+
+        let mut file_name = file.file_stem().unwrap_or_default().to_os_string();
+        file_name.push(format!("-{}", DATE_PLACEHOLDER));
+
+        if let Some(extension) = file.extension() {
+            file_name.push(".");
+            file_name.push(extension);
+        }
+
+        file.with_file_name(file_name)
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Returns the configuration of the logger that would be built, to adjust other settings before applying it.
+    pub fn to_config(&self) -> LoggerConfig {
+        let mut config = LoggerConfig::new(self.name.clone(), self.min_level);
+        config.sinks.clear();
+        config.size_rotation = self.size_rotation;
+        #[cfg(feature = "gzip")]
+        {
+            config.compress_rotated = self.compress_rotated;
+        }
+        config.append = !self.truncate_on_open;
+        config.start_banner = self.start_banner;
+        config.output_format = self.output_format;
+        config.json_schema = self.json_schema;
+
+        if let Some(file) = &self.file {
+            config.sinks.push(SinkConfig::File {
+                path: self.file_path(file),
+                min_level: None,
+            });
+        }
+
+        if self.console {
+            config.sinks.push(SinkConfig::Console { min_level: None });
+        }
+
+        config
+    }
+
+    /// Creates the logger.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `LoggerError::InvalidConfig` if no name was set,
+    /// or if the logger would write neither to the console nor to a file.
+    /// It will return an error that tells why if the log file can't be created or opened,
+    /// like `LoggerError::DirectoryNotFound`.
+    pub fn build(self) -> Result<Logger, LoggerError> {
+        let config = self.to_config();
+        let mut logger = Logger::new(self.name, self.min_level);
+        logger.try_reconfigure(config)?;
+
+        Ok(logger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigError;
+
+    #[test]
+    fn build_should_use_defaults() {
+        let logger = LoggerBuilder::new()
+            .name(String::from("test"))
+            .build()
+            .unwrap();
+        let config = logger.snapshot_config();
+
+        assert_eq!(config.min_level, LogLevel::Info);
+        assert_eq!(config.sinks, vec![SinkConfig::Console { min_level: None }]);
+    }
+
+    #[test]
+    fn build_should_write_to_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_builder.log");
+        let _ = std::fs::remove_file(&path);
+
+        let mut logger = LoggerBuilder::new()
+            .name(String::from("test"))
+            .min_level(LogLevel::Warning)
+            .file(&path)
+            .console(false)
+            .build()
+            .unwrap();
+        logger.info("info");
+        logger.warning("warning");
+
+        assert_eq!(logger.sinks().len(), 1);
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.ends_with("[test] [warning] warning\n"));
+    }
+
+    #[test]
+    fn truncate_on_open_should_only_keep_last_run() {
+        let path = std::env::temp_dir().join("ultimate_logger_builder_truncate.log");
+        let _ = std::fs::remove_file(&path);
+        let run = |truncate_on_open: bool, message: &str| {
+            let mut logger = LoggerBuilder::new()
+                .name(String::from("test"))
+                .file(&path)
+                .console(false)
+                .truncate_on_open(truncate_on_open)
+                .build()
+                .unwrap();
+            logger.info(message);
+        };
+
+        run(false, "first run");
+        run(false, "second run");
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+        run(true, "third run");
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.ends_with("[test] [info] third run\n"));
+    }
+
+    #[test]
+    fn with_start_banner_should_delimit_appended_runs() {
+        let path = std::env::temp_dir().join("ultimate_logger_builder_banner.log");
+        let _ = std::fs::remove_file(&path);
+
+        for run in 0..2 {
+            let mut logger = LoggerBuilder::new()
+                .name(String::from("test"))
+                .file(&path)
+                .console(false)
+                .with_start_banner(true)
+                .build()
+                .unwrap();
+            logger.info(&format!("run {}", run));
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("===== test started at "));
+        assert!(lines[1].ends_with("[info] run 0"));
+        assert!(lines[2].starts_with("===== test started at "));
+        assert!(lines[3].ends_with("[info] run 1"));
+    }
+
+    #[test]
+    fn with_json_schema_should_write_schema_next_to_log_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_builder_schema.log");
+        let schema_path =
+            std::env::temp_dir().join("ultimate_logger_builder_schema.log.schema.json");
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&schema_path);
+
+        let logger = LoggerBuilder::new()
+            .name(String::from("test"))
+            .file(&path)
+            .console(false)
+            .output_format(OutputFormat::Json)
+            .with_json_schema(true)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&schema_path).unwrap(),
+            crate::json::SCHEMA
+        );
+        let config = logger.snapshot_config();
+        assert_eq!(config.output_format, OutputFormat::Json);
+        assert!(config.json_schema);
+    }
+
+    #[test]
+    fn rotate_size_should_rotate_log_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_builder_rotate.txt");
+        let rotated = |index: usize| {
+            std::env::temp_dir().join(format!("ultimate_logger_builder_rotate.{}.txt", index))
+        };
+        let _ = std::fs::remove_file(&path);
+        for index in 1..=3 {
+            let _ = std::fs::remove_file(rotated(index));
+        }
+
+        let mut logger = LoggerBuilder::new()
+            .name(String::from("test"))
+            .file(&path)
+            .console(false)
+            .rotate_size(80, 2)
+            .build()
+            .unwrap();
+
+        // Every line is 50 bytes long, so every file holds a single line.
+        for i in 0..4 {
+            logger.info(&format!("message {}", i));
+        }
+
+        logger.flush().unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .ends_with("message 3\n"));
+        assert!(std::fs::read_to_string(rotated(1))
+            .unwrap()
+            .ends_with("message 2\n"));
+        assert!(std::fs::read_to_string(rotated(2))
+            .unwrap()
+            .ends_with("message 1\n"));
+        assert!(!rotated(3).exists());
+        assert_eq!(logger.snapshot_config().size_rotation, Some((80, 2)));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn compress_rotated_should_compress_rotated_files() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let path = std::env::temp_dir().join("ultimate_logger_builder_compress.txt");
+        let rotated = |index: usize| {
+            std::env::temp_dir().join(format!("ultimate_logger_builder_compress.{}.txt", index))
+        };
+        let compressed = |index: usize| {
+            std::env::temp_dir().join(format!("ultimate_logger_builder_compress.{}.txt.gz", index))
+        };
+        let decompress = |index: usize| {
+            let mut contents = String::new();
+            GzDecoder::new(std::fs::File::open(compressed(index)).unwrap())
+                .read_to_string(&mut contents)
+                .unwrap();
+            contents
+        };
+        let _ = std::fs::remove_file(&path);
+        for index in 1..=3 {
+            let _ = std::fs::remove_file(rotated(index));
+            let _ = std::fs::remove_file(compressed(index));
+        }
+
+        let mut logger = LoggerBuilder::new()
+            .name(String::from("test"))
+            .file(&path)
+            .console(false)
+            .rotate_size(80, 2)
+            .compress_rotated(true)
+            .build()
+            .unwrap();
+
+        // Every line is 50 bytes long, so every file holds a single line.
+        for i in 0..4 {
+            logger.info(&format!("message {}", i));
+        }
+
+        logger.flush().unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .ends_with("message 3\n"));
+        assert!(decompress(1).ends_with("message 2\n"));
+        assert!(decompress(2).ends_with("message 1\n"));
+        for index in 1..=3 {
+            assert!(!rotated(index).exists());
+        }
+        assert!(!compressed(3).exists());
+        assert!(logger.snapshot_config().compress_rotated);
+    }
+
+    #[test]
+    fn rotate_daily_should_add_date_placeholder() {
+        let config = LoggerBuilder::new()
+            .name(String::from("test"))
+            .file("logs/app.log")
+            .rotate_daily()
+            .to_config();
+        assert_eq!(
+            config.sinks[0],
+            SinkConfig::File {
+                path: String::from("logs/app-{date}.log"),
+                min_level: None,
+            }
+        );
+
+        let config = LoggerBuilder::new()
+            .name(String::from("test"))
+            .file("{date}.log")
+            .rotate_daily()
+            .to_config();
+        assert_eq!(
+            config.sinks[0],
+            SinkConfig::File {
+                path: String::from("{date}.log"),
+                min_level: None,
+            }
+        );
+    }
+
+    #[test]
+    fn build_should_reject_missing_name_or_sinks() {
+        let error = LoggerBuilder::new().build().err().unwrap();
+        assert!(matches!(
+            error,
+            LoggerError::InvalidConfig(errors) if errors == vec![ConfigError::EmptyName]
+        ));
+
+        let error = LoggerBuilder::new()
+            .name(String::from("test"))
+            .console(false)
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error,
+            LoggerError::InvalidConfig(errors) if errors == vec![ConfigError::NoSinks]
+        ));
+    }
+
+    #[test]
+    fn build_should_tell_why_file_cant_be_opened() {
+        let directory = std::env::temp_dir();
+
+        let error = LoggerBuilder::new()
+            .name(String::from("test"))
+            .file(&directory)
+            .build()
+            .err()
+            .unwrap();
+
+        assert!(matches!(error, LoggerError::FileIsDirectory(_)));
+    }
+}
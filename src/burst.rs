@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::dedup::Expired;
+use crate::log_level::LogLevel;
+
+/// The maximum number of distinct messages tracked at once.
+const MAX_ENTRIES: usize = 256;
+
+struct Entry {
+    count: u32,
+    suppressed: u64,
+    interval_start: Instant,
+}
+
+/// Lets the first occurrences of a message through, then suppresses it and summarizes periodically.
+pub(crate) struct BurstLimiter {
+    settings: Option<(u32, Duration)>,
+    entries: HashMap<(LogLevel, String), Entry>,
+}
+
+impl BurstLimiter {
+    pub(crate) fn new() -> Self {
+        Self {
+            settings: None,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn settings(&self) -> Option<(u32, Duration)> {
+        self.settings
+    }
+
+    pub(crate) fn interval(&self) -> Option<Duration> {
+        self.settings.map(|(_, interval)| interval)
+    }
+
+    pub(crate) fn set(&mut self, settings: Option<(u32, Duration)>) {
+        self.settings = settings;
+        self.entries.clear();
+    }
+
+    /// Returns a summary for every message whose interval has passed and that was suppressed since.
+    /// Messages that weren't suppressed in their interval are forgotten, so their next burst is logged again.
+    pub(crate) fn summarize(&mut self, now: Instant) -> Vec<Expired> {
+        let interval = match self.interval() {
+            Some(interval) => interval,
+            None => return Vec::new(),
+        };
+
+        let mut summaries = Vec::new();
+
+        self.entries.retain(|(level, message), entry| {
+            if now.duration_since(entry.interval_start) < interval {
+                return true;
+            }
+
+            if entry.suppressed == 0 {
+                return false;
+            }
+
+            summaries.push(Expired {
+                level: *level,
+                message: message.clone(),
+                suppressed: entry.suppressed,
+            });
+            entry.suppressed = 0;
+            entry.interval_start = now;
+
+            true
+        });
+
+        summaries
+    }
+
+    /// Returns a summary for every message that was suppressed since its last summary, and forgets all messages.
+    pub(crate) fn drain(&mut self) -> Vec<Expired> {
+        self.entries
+            .drain()
+            .filter(|(_, entry)| entry.suppressed > 0)
+            .map(|((level, message), entry)| Expired {
+                level,
+                message,
+                suppressed: entry.suppressed,
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the message should be logged, and `false` if it should be suppressed.
+    pub(crate) fn check(&mut self, level: LogLevel, message: &str, now: Instant) -> bool {
+        let first_k = match self.settings {
+            Some((first_k, _)) => first_k,
+            None => return true,
+        };
+
+        let key = (level, message.to_string());
+
+        if !self.entries.contains_key(&key) && self.entries.len() >= MAX_ENTRIES {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.interval_start)
+                .map(|(key, _)| key.clone());
+
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        let entry = self.entries.entry(key).or_insert(Entry {
+            count: 0,
+            suppressed: 0,
+            interval_start: now,
+        });
+
+        if entry.count < first_k {
+            entry.count += 1;
+            return true;
+        }
+
+        entry.suppressed += 1;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_limiter_should_allow_everything() {
+        let mut limiter = BurstLimiter::new();
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            assert!(limiter.check(LogLevel::Error, "test", now));
+        }
+    }
+
+    #[test]
+    fn should_allow_first_k_then_suppress() {
+        let mut limiter = BurstLimiter::new();
+        limiter.set(Some((2, Duration::from_secs(60))));
+        let now = Instant::now();
+
+        assert!(limiter.check(LogLevel::Error, "test", now));
+        assert!(limiter.check(LogLevel::Error, "test", now));
+        assert!(!limiter.check(LogLevel::Error, "test", now));
+        assert!(limiter.check(LogLevel::Error, "other", now));
+    }
+
+    #[test]
+    fn summarize_should_report_suppressed_after_interval() {
+        let mut limiter = BurstLimiter::new();
+        limiter.set(Some((1, Duration::from_secs(60))));
+        let now = Instant::now();
+        limiter.check(LogLevel::Error, "test", now);
+        limiter.check(LogLevel::Error, "test", now);
+        limiter.check(LogLevel::Error, "test", now);
+
+        assert!(limiter.summarize(now + Duration::from_secs(30)).is_empty());
+
+        let summaries = limiter.summarize(now + Duration::from_secs(60));
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].suppressed, 2);
+        assert!(!limiter.check(LogLevel::Error, "test", now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn quiet_message_should_be_forgotten_after_interval() {
+        let mut limiter = BurstLimiter::new();
+        limiter.set(Some((1, Duration::from_secs(60))));
+        let now = Instant::now();
+        limiter.check(LogLevel::Error, "test", now);
+
+        assert!(limiter.summarize(now + Duration::from_secs(60)).is_empty());
+        assert!(limiter.check(LogLevel::Error, "test", now + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn drain_should_report_pending_suppressed() {
+        let mut limiter = BurstLimiter::new();
+        limiter.set(Some((1, Duration::from_secs(60))));
+        let now = Instant::now();
+        limiter.check(LogLevel::Error, "test", now);
+        limiter.check(LogLevel::Error, "test", now);
+
+        let summaries = limiter.drain();
+
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].suppressed, 1);
+    }
+}
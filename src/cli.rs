@@ -0,0 +1,151 @@
+//! Helper to create a logger from the usual command line options
+
+use std::path::PathBuf;
+
+use crate::config::{LoggerConfig, SinkConfig};
+use crate::error::LoggerError;
+use crate::log_level::LogLevel;
+use crate::Logger;
+
+/// The logging options of a command line tool, as parsed from its arguments,
+/// for example `--log-file`, `--log-level`, `--quiet` and `-v`/`-vv`.
+///
+/// The minimum log level is, in order of precedence:
+/// - the explicit log level, if there is one,
+/// - "Error" if `quiet` is set,
+/// - "Debug" for one `-v`, and "Trace" for two or more,
+/// - "Info" otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use ultimate_logger::cli::CliOptions;
+/// use ultimate_logger::log_level::LogLevel;
+///
+/// let options = CliOptions {
+///     verbose: 1,
+///     ..CliOptions::default()
+/// };
+///
+/// assert_eq!(options.min_level(), LogLevel::Debug);
+///
+/// let mut logger = options.build_logger(String::from("example")).unwrap();
+/// logger.debug("This is a debug message");
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct CliOptions {
+    /// The file to log to besides the console, for example from `--log-file`.
+    pub log_file: Option<PathBuf>,
+    /// The explicit minimum log level, for example from `--log-level`.
+    pub log_level: Option<LogLevel>,
+    /// Whether only errors should be logged, for example from `--quiet`.
+    pub quiet: bool,
+    /// The number of times the verbose flag was given, for example from `-v` or `-vv`.
+    pub verbose: u8,
+}
+
+impl CliOptions {
+    /// Returns the minimum log level following the precedence described above.
+    pub fn min_level(&self) -> LogLevel {
+        match self.log_level {
+            Some(log_level) => log_level,
+            None if self.quiet => LogLevel::Error,
+            None => match self.verbose {
+                0 => LogLevel::Info,
+                1 => LogLevel::Debug,
+                _ => LogLevel::Trace,
+            },
+        }
+    }
+
+    /// Returns the configuration of a logger that writes to the console, and to the log file if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the logger.
+    pub fn to_config(&self, name: String) -> LoggerConfig {
+        let mut config = LoggerConfig::new(name, self.min_level());
+
+        if let Some(log_file) = &self.log_file {
+            config.sinks.push(SinkConfig::File {
+                path: log_file.to_string_lossy().into_owned(),
+                min_level: None,
+            });
+        }
+
+        config
+    }
+
+    /// Creates a logger that writes to the console, and to the log file if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the logger.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error that tells why if the log file can't be created or opened,
+    /// like `LoggerError::DirectoryNotFound`. The error message contains the path of the file.
+    pub fn build_logger(&self, name: String) -> Result<Logger, LoggerError> {
+        let config = self.to_config(name.clone());
+        let mut logger = Logger::new(name, config.min_level);
+        logger.try_reconfigure(config)?;
+
+        Ok(logger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_level_should_follow_precedence() {
+        let mut options = CliOptions::default();
+        assert_eq!(options.min_level(), LogLevel::Info);
+
+        options.verbose = 3;
+        assert_eq!(options.min_level(), LogLevel::Trace);
+
+        options.quiet = true;
+        assert_eq!(options.min_level(), LogLevel::Error);
+
+        options.log_level = Some(LogLevel::Warning);
+        assert_eq!(options.min_level(), LogLevel::Warning);
+    }
+
+    #[test]
+    fn to_config_should_add_file_sink() {
+        let options = CliOptions {
+            log_file: Some(PathBuf::from("app.log")),
+            ..CliOptions::default()
+        };
+
+        let config = options.to_config(String::from("test"));
+
+        assert_eq!(
+            config.sinks,
+            vec![
+                SinkConfig::Console { min_level: None },
+                SinkConfig::File {
+                    path: String::from("app.log"),
+                    min_level: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn build_logger_should_fail_for_invalid_file() {
+        let directory = std::env::temp_dir();
+        let options = CliOptions {
+            log_file: Some(directory.clone()),
+            ..CliOptions::default()
+        };
+
+        let error = options.build_logger(String::from("test")).err().unwrap();
+
+        assert!(matches!(error, LoggerError::FileIsDirectory(_)));
+        assert!(error.to_string().contains(&*directory.to_string_lossy()));
+    }
+}
@@ -0,0 +1,543 @@
+//! Color settings of the console output
+
+use colored::{Color, ColoredString, Colorize, Styles};
+
+use crate::log_level::LogLevel;
+
+/// The colors of logger names, see `Logger::set_colorize_name_by_hash`.
+/// Red and yellow are left out, because they are used for errors and warnings.
+const NAME_PALETTE: [Color; 8] = [
+    Color::Cyan,
+    Color::Magenta,
+    Color::Blue,
+    Color::Green,
+    Color::BrightCyan,
+    Color::BrightMagenta,
+    Color::BrightBlue,
+    Color::BrightGreen,
+];
+
+/// Returns the color of a logger name, based on the 32-bit FNV-1a hash of the name,
+/// which, unlike the hasher of the standard library, is the same across runs and Rust versions.
+pub(crate) fn name_color(name: &str) -> Color {
+    let mut hash: u32 = 0x811c_9dc5;
+
+    for byte in name.bytes() {
+        hash ^= u32::from(byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+
+    NAME_PALETTE[hash as usize % NAME_PALETTE.len()]
+}
+
+/// The escape codes of the text styles, in the order the `colored` crate writes them.
+const STYLE_CODES: [(Styles, &str); 8] = [
+    (Styles::Bold, "1"),
+    (Styles::Underline, "4"),
+    (Styles::Reversed, "7"),
+    (Styles::Italic, "3"),
+    (Styles::Blink, "5"),
+    (Styles::Hidden, "8"),
+    (Styles::Dimmed, "2"),
+    (Styles::Strikethrough, "9"),
+];
+
+/// Returns the text with the escape codes of its colors and styles, like the `colored` crate writes it,
+/// but whatever that crate decided for the process: every logger decides itself whether it writes colors.
+pub(crate) fn paint(colored: &ColoredString) -> String {
+    if colored.is_plain() {
+        return colored.input.clone();
+    }
+
+    let mut codes: Vec<String> = STYLE_CODES
+        .iter()
+        .filter(|(style, _)| colored.style.contains(*style))
+        .map(|(_, code)| code.to_string())
+        .collect();
+    codes.extend(colored.bgcolor.map(|color| color.to_bg_str().into_owned()));
+    codes.extend(colored.fgcolor.map(|color| color.to_fg_str().into_owned()));
+
+    format!("\x1b[{}m{}\x1b[0m", codes.join(";"), colored.input)
+}
+
+/// The style of the console output of a log level: a color, a background color, and text attributes.
+/// Use it with `Logger::set_level_style`, or pick a [`Theme`] with `Logger::set_theme`.
+///
+/// # Examples
+///
+/// ```
+/// use colored::Color;
+/// use ultimate_logger::color::LevelStyle;
+///
+/// let style = LevelStyle {
+///     color: Some(Color::Cyan),
+///     bold: true,
+///     ..LevelStyle::default()
+/// };
+///
+/// assert_eq!(style.description(), "cyan bold");
+/// assert_eq!(LevelStyle::default().description(), "plain");
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct LevelStyle {
+    /// The color of the text, or `None` for the color of the terminal.
+    pub color: Option<Color>,
+    /// The background color, or `None` for the background of the terminal.
+    pub background: Option<Color>,
+    /// Whether the text is bold.
+    pub bold: bool,
+    /// Whether the text is dimmed.
+    pub dimmed: bool,
+    /// Whether the text is underlined.
+    pub underline: bool,
+}
+
+impl LevelStyle {
+    const fn plain() -> Self {
+        Self {
+            color: None,
+            background: None,
+            bold: false,
+            dimmed: false,
+            underline: false,
+        }
+    }
+
+    const fn color(color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..Self::plain()
+        }
+    }
+
+    const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self::color(Color::TrueColor { r, g, b })
+    }
+
+    const fn bold(self) -> Self {
+        Self { bold: true, ..self }
+    }
+
+    const fn dimmed(self) -> Self {
+        Self {
+            dimmed: true,
+            ..self
+        }
+    }
+
+    const fn underline(self) -> Self {
+        Self {
+            underline: true,
+            ..self
+        }
+    }
+
+    /// Applies the style to the text.
+    pub fn apply(&self, text: &str) -> ColoredString {
+        let mut colored = ColoredString::from(text);
+
+        if let Some(color) = self.color {
+            colored = colored.color(color);
+        }
+        if let Some(background) = self.background {
+            colored = colored.on_color(background);
+        }
+        if self.bold {
+            colored = colored.bold();
+        }
+        if self.dimmed {
+            colored = colored.dimmed();
+        }
+        if self.underline {
+            colored = colored.underline();
+        }
+
+        colored
+    }
+
+    /// Describes the style in words, like `red bold` or `plain`, as shown in the legend (see `Logger::set_print_legend`).
+    pub fn description(&self) -> String {
+        let mut words = Vec::new();
+
+        if let Some(color) = self.color {
+            words.push(color_name(color));
+        }
+        if let Some(background) = self.background {
+            words.push(format!("on {}", color_name(background)));
+        }
+        if self.bold {
+            words.push(String::from("bold"));
+        }
+        if self.dimmed {
+            words.push(String::from("dim"));
+        }
+        if self.underline {
+            words.push(String::from("underline"));
+        }
+
+        if words.is_empty() {
+            String::from("plain")
+        } else {
+            words.join(" ")
+        }
+    }
+}
+
+fn color_name(color: Color) -> String {
+    let name = match color {
+        Color::Black => "black",
+        Color::Red => "red",
+        Color::Green => "green",
+        Color::Yellow => "yellow",
+        Color::Blue => "blue",
+        Color::Magenta => "magenta",
+        Color::Cyan => "cyan",
+        Color::White => "white",
+        Color::BrightBlack => "bright black",
+        Color::BrightRed => "bright red",
+        Color::BrightGreen => "bright green",
+        Color::BrightYellow => "bright yellow",
+        Color::BrightBlue => "bright blue",
+        Color::BrightMagenta => "bright magenta",
+        Color::BrightCyan => "bright cyan",
+        Color::BrightWhite => "bright white",
+        Color::TrueColor { r, g, b } => return format!("#{:02x}{:02x}{:02x}", r, g, b),
+    };
+
+    String::from(name)
+}
+
+/// A preset of the styles of all log levels, to use with `Logger::set_theme`.
+///
+/// # Examples
+///
+/// ```
+/// use colored::Color;
+/// use ultimate_logger::color::Theme;
+/// use ultimate_logger::log_level::LogLevel;
+///
+/// assert_eq!(Theme::default(), Theme::Default);
+/// assert_eq!(Theme::Default.style(LogLevel::Warning).color, Some(Color::Yellow));
+/// assert_eq!(Theme::Monochrome.style(LogLevel::Warning).color, None);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum Theme {
+    /// Trace dimmed, debug dimmed and underlined, info plain, warning yellow, error red, and critical red and bold.
+    Default,
+    /// Bright colors and bold text for warnings and worse, for terminals where dimmed text is hard to read.
+    HighContrast,
+    /// No colors, only dimmed, bold and underlined text, for terminals without colors.
+    Monochrome,
+    /// The Solarized palette, for terminals with a dark Solarized background. Needs a terminal with true color support.
+    SolarizedDark,
+    /// The Solarized palette, for terminals with a light Solarized background. Needs a terminal with true color support.
+    SolarizedLight,
+}
+
+impl Theme {
+    /// Returns the style of a log level in this theme.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level.
+    pub fn style(self, level: LogLevel) -> LevelStyle {
+        self.styles()[level as usize]
+    }
+
+    /// Returns the styles of all log levels in this theme, from "Trace" to "Critical".
+    pub fn styles(self) -> [LevelStyle; 6] {
+        match self {
+            Theme::Default => [
+                LevelStyle::plain().dimmed(),
+                LevelStyle::plain().dimmed().underline(),
+                LevelStyle::plain(),
+                LevelStyle::color(Color::Yellow),
+                LevelStyle::color(Color::Red),
+                LevelStyle::color(Color::Red).bold(),
+            ],
+            Theme::HighContrast => [
+                LevelStyle::color(Color::White),
+                LevelStyle::color(Color::BrightCyan),
+                LevelStyle::color(Color::BrightWhite),
+                LevelStyle::color(Color::BrightYellow).bold(),
+                LevelStyle::color(Color::BrightRed).bold(),
+                LevelStyle {
+                    background: Some(Color::Red),
+                    ..LevelStyle::color(Color::BrightWhite).bold()
+                },
+            ],
+            Theme::Monochrome => [
+                LevelStyle::plain().dimmed(),
+                LevelStyle::plain().dimmed().underline(),
+                LevelStyle::plain(),
+                LevelStyle::plain().underline(),
+                LevelStyle::plain().bold(),
+                LevelStyle::plain().bold().underline(),
+            ],
+            Theme::SolarizedDark => [
+                LevelStyle::rgb(0x58, 0x6e, 0x75),
+                LevelStyle::rgb(0x2a, 0xa1, 0x98),
+                LevelStyle::rgb(0x83, 0x94, 0x96),
+                LevelStyle::rgb(0xb5, 0x89, 0x00),
+                LevelStyle::rgb(0xcb, 0x4b, 0x16),
+                LevelStyle::rgb(0xdc, 0x32, 0x2f).bold(),
+            ],
+            Theme::SolarizedLight => [
+                LevelStyle::rgb(0x93, 0xa1, 0xa1),
+                LevelStyle::rgb(0x2a, 0xa1, 0x98),
+                LevelStyle::rgb(0x65, 0x7b, 0x83),
+                LevelStyle::rgb(0xb5, 0x89, 0x00),
+                LevelStyle::rgb(0xcb, 0x4b, 0x16),
+                LevelStyle::rgb(0xdc, 0x32, 0x2f).bold(),
+            ],
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::Default
+    }
+}
+
+/// The styles of all log levels on the console, to use with `Logger::set_color_scheme`.
+/// It starts from a [`Theme`], by default `Theme::Default`, and each log level can be changed separately.
+///
+/// # Examples
+///
+/// ```
+/// use colored::Color;
+/// use ultimate_logger::color::{ColorScheme, LevelStyle, Theme};
+/// use ultimate_logger::log_level::LogLevel;
+///
+/// let scheme = ColorScheme::default()
+///     .with_color(LogLevel::Trace, Color::Cyan)
+///     .with_color(LogLevel::Debug, Color::Blue);
+///
+/// assert_eq!(scheme.style(LogLevel::Trace).description(), "cyan");
+/// assert_eq!(scheme.style(LogLevel::Warning), Theme::Default.style(LogLevel::Warning));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ColorScheme {
+    styles: [LevelStyle; 6],
+}
+
+impl ColorScheme {
+    /// Creates a color scheme with the styles of a theme.
+    ///
+    /// # Arguments
+    ///
+    /// * `theme` - The theme to start from.
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            styles: theme.styles(),
+        }
+    }
+
+    /// Returns the style of a log level.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level.
+    pub fn style(&self, level: LogLevel) -> LevelStyle {
+        self.styles[level as usize]
+    }
+
+    /// Returns the styles of all log levels, from "Trace" to "Critical".
+    pub fn styles(&self) -> [LevelStyle; 6] {
+        self.styles
+    }
+
+    /// Sets the style of a log level.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level.
+    /// * `style` - The style.
+    pub fn with_style(mut self, level: LogLevel, style: LevelStyle) -> Self {
+        self.styles[level as usize] = style;
+        self
+    }
+
+    /// Sets the style of a log level to a color, without other attributes like dimmed or bold text.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level.
+    /// * `color` - The color.
+    pub fn with_color(self, level: LogLevel, color: Color) -> Self {
+        self.with_style(level, LevelStyle::color(color))
+    }
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        Self::new(Theme::Default)
+    }
+}
+
+impl From<Theme> for ColorScheme {
+    fn from(theme: Theme) -> Self {
+        Self::new(theme)
+    }
+}
+
+impl From<[LevelStyle; 6]> for ColorScheme {
+    fn from(styles: [LevelStyle; 6]) -> Self {
+        Self { styles }
+    }
+}
+
+/// Whether the console output of a logger is colored, to use with `Logger::set_color_choice`.
+///
+/// # Examples
+///
+/// ```
+/// use ultimate_logger::color::ColorChoice;
+///
+/// assert_eq!(ColorChoice::default(), ColorChoice::Auto);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum ColorChoice {
+    /// Let the `colored` crate decide, based on the environment:
+    /// the output is colored if stdout is a terminal, unless `NO_COLOR` or `CLICOLOR=0` is set, or `CLICOLOR_FORCE` is set.
+    Auto,
+    /// Never color the output.
+    Never,
+    /// Always color the output, even if stdout isn't a terminal.
+    Always,
+}
+
+impl Default for ColorChoice {
+    fn default() -> Self {
+        ColorChoice::Auto
+    }
+}
+
+/// Which parts of a console line are colored, to use with `Logger::set_color_targets`.
+///
+/// # Examples
+///
+/// ```
+/// use ultimate_logger::color::ColorTargets;
+///
+/// assert_eq!(ColorTargets::default(), ColorTargets::Both);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ColorTargets {
+    /// Color both the log level and the message.
+    Both,
+    /// Only color the log level.
+    Level,
+    /// Only color the message.
+    Message,
+    /// Color nothing.
+    Neither,
+}
+
+impl ColorTargets {
+    pub(crate) fn colors_level(self) -> bool {
+        match self {
+            ColorTargets::Both | ColorTargets::Level => true,
+            ColorTargets::Message | ColorTargets::Neither => false,
+        }
+    }
+
+    pub(crate) fn colors_message(self) -> bool {
+        match self {
+            ColorTargets::Both | ColorTargets::Message => true,
+            ColorTargets::Level | ColorTargets::Neither => false,
+        }
+    }
+
+    pub(crate) fn with_message(self) -> ColorTargets {
+        match self {
+            ColorTargets::Both | ColorTargets::Level => ColorTargets::Both,
+            ColorTargets::Message | ColorTargets::Neither => ColorTargets::Message,
+        }
+    }
+}
+
+impl Default for ColorTargets {
+    fn default() -> Self {
+        ColorTargets::Both
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paint_should_write_escape_codes() {
+        assert_eq!(paint(&"plain".clear()), "plain");
+        assert_eq!(paint(&"red".red()), "\x1b[31mred\x1b[0m");
+        assert_eq!(
+            paint(&"styled".dimmed().bold().on_blue().yellow()),
+            "\x1b[1;2;44;33mstyled\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn default_theme_should_apply_original_colors() {
+        let style = |level| Theme::Default.style(level);
+
+        assert_eq!(style(LogLevel::Trace).apply("trace"), "trace".dimmed());
+        assert_eq!(
+            style(LogLevel::Debug).apply("debug"),
+            "debug".dimmed().underline()
+        );
+        assert_eq!(style(LogLevel::Info).apply("info"), "info".clear());
+        assert_eq!(
+            style(LogLevel::Warning).apply("warning"),
+            "warning".yellow()
+        );
+        assert_eq!(style(LogLevel::Error).apply("error"), "error".red());
+        assert_eq!(
+            style(LogLevel::Critical).apply("critical"),
+            "critical".red().bold()
+        );
+    }
+
+    #[test]
+    fn level_style_should_describe_itself() {
+        let styles = Theme::Default.styles();
+        let descriptions: Vec<String> = styles.iter().map(LevelStyle::description).collect();
+
+        assert_eq!(
+            descriptions,
+            vec!["dim", "dim underline", "plain", "yellow", "red", "red bold"]
+        );
+        assert_eq!(
+            Theme::HighContrast.style(LogLevel::Critical).description(),
+            "bright white on red bold"
+        );
+        assert_eq!(
+            Theme::SolarizedDark.style(LogLevel::Error).description(),
+            "#cb4b16"
+        );
+    }
+
+    #[test]
+    fn monochrome_theme_should_have_no_colors() {
+        for style in Theme::Monochrome.styles().iter() {
+            assert_eq!(style.color, None);
+            assert_eq!(style.background, None);
+        }
+    }
+
+    #[test]
+    fn name_color_should_be_stable() {
+        assert_eq!(name_color("server"), name_color("server"));
+        assert_eq!(
+            name_color(""),
+            NAME_PALETTE[0x811c_9dc5 % NAME_PALETTE.len()]
+        );
+        assert_eq!(
+            name_color("a"),
+            NAME_PALETTE[0xe40c_292c % NAME_PALETTE.len()]
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Logger configuration, to apply a whole configuration to a logger at once
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::color::{ColorChoice, ColorTargets, LevelStyle, Theme};
+use crate::filter::MessageFilter;
+use crate::log_level::LogLevel;
+use crate::sink::{DuplicateSinkPolicy, OutputFormat, PipeAtomicPolicy, SpecialFilePolicy};
+use crate::timestamp::{self, TimestampPreset, TimestampStyle};
+
+/// The configuration of a sink in a [`LoggerConfig`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SinkConfig {
+    /// Write to the console.
+    Console {
+        /// The minimum log level of the sink, besides the one of the logger.
+        min_level: Option<LogLevel>,
+    },
+    /// Write to a file. If the file doesn't exist, it will be created.
+    File {
+        /// The path to the file. If it contains `{date}`, a new file is started every day,
+        /// see `Logger::add_file_sink`.
+        path: String,
+        /// The minimum log level of the sink, besides the one of the logger.
+        min_level: Option<LogLevel>,
+    },
+}
+
+/// A problem with a [`LoggerConfig`], as returned by [`LoggerConfig::validate`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The name of the logger is empty.
+    EmptyName,
+    /// The configuration has no sinks, so nothing would be logged.
+    NoSinks,
+    /// The sink at this index is a file sink without a path.
+    EmptyFilePath(usize),
+    /// The timestamp format isn't a valid chrono format string.
+    InvalidTimestampFormat(String),
+    /// The maximum number of bytes per second is 0, so nothing would be written to files.
+    ZeroMaxBytesPerSec,
+    /// The deduplication window is zero.
+    ZeroDedupWindow,
+    /// The burst summary lets no occurrences through, or has a zero interval.
+    InvalidBurstSummary,
+    /// The maximum size of a log file before it's rotated is 0.
+    ZeroRotationSize,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::EmptyName => write!(f, "the name of the logger is empty"),
+            ConfigError::NoSinks => write!(f, "the logger has no sinks"),
+            ConfigError::EmptyFilePath(index) => {
+                write!(f, "the file sink at index {} has an empty path", index)
+            }
+            ConfigError::InvalidTimestampFormat(format) => {
+                write!(f, "invalid timestamp format: {:?}", format)
+            }
+            ConfigError::ZeroMaxBytesPerSec => {
+                write!(f, "the maximum number of bytes per second is 0")
+            }
+            ConfigError::ZeroDedupWindow => write!(f, "the deduplication window is zero"),
+            ConfigError::InvalidBurstSummary => write!(
+                f,
+                "the burst summary must let at least one occurrence through and have a non-zero interval"
+            ),
+            ConfigError::ZeroRotationSize => {
+                write!(f, "the maximum size of a log file before it's rotated is 0")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// The complete configuration of a logger, to apply with `Logger::reconfigure`.
+///
+/// # Examples
+///
+/// ```
+/// use ultimate_logger::config::{LoggerConfig, SinkConfig};
+/// use ultimate_logger::log_level::LogLevel;
+///
+/// let mut config = LoggerConfig::new(String::from("example"), LogLevel::Info);
+/// config.sinks.push(SinkConfig::File {
+///     path: String::from("log.txt"),
+///     min_level: None,
+/// });
+/// config.show_line_number = true;
+/// ```
+///
+/// This will create a configuration for a logger named "example" with the minimum log level "Info",
+/// which writes to the console and the file "log.txt", with line numbers in the file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LoggerConfig {
+    /// The name of the logger.
+    pub name: String,
+    /// See `Logger::set_source`.
+    pub source: Option<String>,
+    /// The minimum log level.
+    pub min_level: LogLevel,
+    /// The sinks, in the order they're written to.
+    pub sinks: Vec<SinkConfig>,
+    /// The chrono format string of the timestamps.
+    pub timestamp_format: String,
+    /// Whether the timestamps are in UTC instead of local time.
+    pub use_utc: bool,
+    /// See `Logger::set_timestamp_style`.
+    pub timestamp_style: TimestampStyle,
+    /// See `Logger::set_compact_date`.
+    pub compact_date: bool,
+    /// See `Logger::set_show_line_number`.
+    pub show_line_number: bool,
+    /// See `Logger::set_line_number_reset_on_rotation`.
+    pub line_number_reset_on_rotation: bool,
+    /// See `Logger::set_trim_trailing_newline`.
+    pub trim_trailing_newline: bool,
+    /// See `Logger::set_trace_id`.
+    pub trace_id: Option<String>,
+    /// See `Logger::set_context`.
+    pub context: Option<String>,
+    /// See `Logger::set_max_bytes_per_sec`.
+    pub max_bytes_per_sec: Option<u64>,
+    /// See `Logger::set_dedup_window`.
+    pub dedup_window: Option<Duration>,
+    /// See `Logger::set_dedup_summary`.
+    pub dedup_summary: bool,
+    /// The number of occurrences and the summary interval, see `Logger::set_burst_summary`.
+    pub burst_summary: Option<(u32, Duration)>,
+    /// See `Logger::set_print_legend`.
+    pub print_legend: bool,
+    /// See `Logger::set_show_scope`.
+    pub show_scope: bool,
+    /// See `Logger::set_max_field_value_len`.
+    pub max_field_value_len: Option<usize>,
+    /// Whether the files of the sinks are appended to instead of truncated, see `Logger::set_append`.
+    pub append: bool,
+    /// See `Logger::set_color_choice`.
+    pub color_choice: ColorChoice,
+    /// See `Logger::set_prefix_message_separator`.
+    pub prefix_message_separator: String,
+    /// The colored parts of the console lines per log level, from "Trace" to "Critical".
+    /// See `Logger::set_color_targets`.
+    pub color_targets: [ColorTargets; 6],
+    /// The styles of the console lines per log level, from "Trace" to "Critical".
+    /// See `Logger::set_theme` and `Logger::set_level_style`.
+    pub level_styles: [LevelStyle; 6],
+    /// See `Logger::set_pipe_atomic_policy`.
+    pub pipe_atomic_policy: PipeAtomicPolicy,
+    /// See `Logger::set_colorize_name_by_hash`.
+    pub colorize_name_by_hash: bool,
+    /// What happens with file sinks that write to the same file, see `Logger::set_duplicate_sink_policy`.
+    pub duplicate_sink_policy: DuplicateSinkPolicy,
+    /// See `Logger::set_output_format`.
+    pub output_format: OutputFormat,
+    /// The minimum log level of the text lines in the dual format, see `Logger::set_dual_min_levels`.
+    pub dual_text_min_level: LogLevel,
+    /// The minimum log level of the JSON lines in the dual format, see `Logger::set_dual_min_levels`.
+    pub dual_json_min_level: LogLevel,
+    /// What happens with files that aren't regular files, see `Logger::set_special_file_policy`.
+    pub special_file_policy: SpecialFilePolicy,
+    /// See `Logger::set_flush_on_critical`.
+    pub flush_on_critical: bool,
+    /// See `Logger::set_flush_each`.
+    pub flush_each: bool,
+    /// Only messages that match this filter are logged, see `Logger::set_message_filter`.
+    pub message_filter: Option<MessageFilter>,
+    /// See `Logger::set_json_schema`.
+    pub json_schema: bool,
+    /// The maximum size of a log file in bytes and the number of rotated files to keep,
+    /// see `Logger::set_size_rotation`.
+    pub size_rotation: Option<(u64, usize)>,
+    /// See `Logger::set_compress_rotated`.
+    #[cfg(feature = "gzip")]
+    pub compress_rotated: bool,
+    /// Whether warnings and errors are written to stderr instead of stdout, see `Logger::set_split_streams`.
+    pub split_streams: bool,
+    /// Whether a banner line is written to files when they're opened, see `Logger::set_start_banner`.
+    pub start_banner: bool,
+    /// `false` if the logger is turned off, see `Logger::disable`.
+    pub enabled: bool,
+}
+
+impl LoggerConfig {
+    /// Creates the configuration of a logger that writes to the console, with all other settings at their defaults.
+    /// This is the configuration of a logger created with `Logger::new`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the logger.
+    /// * `min_level` - The minimum log level.
+    pub fn new(name: String, min_level: LogLevel) -> Self {
+        Self {
+            name,
+            source: None,
+            min_level,
+            sinks: vec![SinkConfig::Console { min_level: None }],
+            timestamp_format: String::from(TimestampPreset::Default.format_str()),
+            use_utc: TimestampPreset::Default.uses_utc(),
+            timestamp_style: TimestampStyle::default(),
+            compact_date: false,
+            show_line_number: false,
+            line_number_reset_on_rotation: true,
+            trim_trailing_newline: true,
+            trace_id: None,
+            context: None,
+            max_bytes_per_sec: None,
+            dedup_window: None,
+            dedup_summary: false,
+            burst_summary: None,
+            print_legend: false,
+            show_scope: false,
+            max_field_value_len: None,
+            append: true,
+            color_choice: ColorChoice::Auto,
+            prefix_message_separator: String::from(" "),
+            color_targets: [ColorTargets::Both; 6],
+            level_styles: Theme::Default.styles(),
+            pipe_atomic_policy: PipeAtomicPolicy::Split,
+            colorize_name_by_hash: false,
+            duplicate_sink_policy: DuplicateSinkPolicy::Warn,
+            output_format: OutputFormat::Text,
+            dual_text_min_level: LogLevel::Trace,
+            dual_json_min_level: LogLevel::Trace,
+            special_file_policy: SpecialFilePolicy::Allow,
+            flush_on_critical: true,
+            flush_each: false,
+            message_filter: None,
+            json_schema: false,
+            size_rotation: None,
+            #[cfg(feature = "gzip")]
+            compress_rotated: false,
+            split_streams: true,
+            start_banner: false,
+            enabled: true,
+        }
+    }
+
+    /// Checks the configuration for inconsistencies, and returns all problems at once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::config::{ConfigError, LoggerConfig};
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut config = LoggerConfig::new(String::new(), LogLevel::Info);
+    /// config.timestamp_format = String::from("%Q");
+    ///
+    /// assert_eq!(
+    ///     config.validate(),
+    ///     Err(vec![
+    ///         ConfigError::EmptyName,
+    ///         ConfigError::InvalidTimestampFormat(String::from("%Q")),
+    ///     ])
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return every problem that was found, in the order of the fields.
+    pub fn validate(&self) -> Result<(), Vec<ConfigError>> {
+        let mut errors = Vec::new();
+
+        if self.name.is_empty() {
+            errors.push(ConfigError::EmptyName);
+        }
+
+        if self.sinks.is_empty() {
+            errors.push(ConfigError::NoSinks);
+        }
+
+        for (index, sink) in self.sinks.iter().enumerate() {
+            if let SinkConfig::File { path, .. } = sink {
+                if path.is_empty() {
+                    errors.push(ConfigError::EmptyFilePath(index));
+                }
+            }
+        }
+
+        if !timestamp::is_valid_format(&self.timestamp_format) {
+            errors.push(ConfigError::InvalidTimestampFormat(
+                self.timestamp_format.clone(),
+            ));
+        }
+
+        if self.max_bytes_per_sec == Some(0) {
+            errors.push(ConfigError::ZeroMaxBytesPerSec);
+        }
+
+        if self.dedup_window == Some(Duration::from_secs(0)) {
+            errors.push(ConfigError::ZeroDedupWindow);
+        }
+
+        if let Some((first_k, interval)) = self.burst_summary {
+            if first_k == 0 || interval == Duration::from_secs(0) {
+                errors.push(ConfigError::InvalidBurstSummary);
+            }
+        }
+
+        if matches!(self.size_rotation, Some((0, _))) {
+            errors.push(ConfigError::ZeroRotationSize);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_should_be_valid() {
+        let config = LoggerConfig::new(String::from("test"), LogLevel::Info);
+
+        assert_eq!(config.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_should_return_all_errors() {
+        let mut config = LoggerConfig::new(String::new(), LogLevel::Info);
+        config.sinks.push(SinkConfig::File {
+            path: String::new(),
+            min_level: None,
+        });
+        config.timestamp_format = String::from("%F %");
+        config.max_bytes_per_sec = Some(0);
+        config.dedup_window = Some(Duration::from_secs(0));
+        config.burst_summary = Some((0, Duration::from_secs(1)));
+        config.size_rotation = Some((0, 3));
+
+        assert_eq!(
+            config.validate(),
+            Err(vec![
+                ConfigError::EmptyName,
+                ConfigError::EmptyFilePath(1),
+                ConfigError::InvalidTimestampFormat(String::from("%F %")),
+                ConfigError::ZeroMaxBytesPerSec,
+                ConfigError::ZeroDedupWindow,
+                ConfigError::InvalidBurstSummary,
+                ConfigError::ZeroRotationSize,
+            ])
+        );
+    }
+
+    #[test]
+    fn validate_should_report_missing_sinks() {
+        let mut config = LoggerConfig::new(String::from("test"), LogLevel::Info);
+        config.sinks.clear();
+
+        assert_eq!(config.validate(), Err(vec![ConfigError::NoSinks]));
+    }
+}
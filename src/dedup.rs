@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::log_level::LogLevel;
+
+/// The maximum number of distinct messages remembered at once.
+const MAX_ENTRIES: usize = 256;
+
+struct Entry {
+    first_seen: Instant,
+    suppressed: u64,
+}
+
+/// A message that was suppressed at least once during a window that has since expired.
+pub(crate) struct Expired {
+    pub(crate) level: LogLevel,
+    pub(crate) message: String,
+    pub(crate) suppressed: u64,
+}
+
+/// Remembers recently logged messages to suppress duplicates within a time window.
+pub(crate) struct DedupCache {
+    window: Option<Duration>,
+    entries: HashMap<(LogLevel, String), Entry>,
+}
+
+impl DedupCache {
+    pub(crate) fn new(window: Option<Duration>) -> Self {
+        Self {
+            window,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn window(&self) -> Option<Duration> {
+        self.window
+    }
+
+    pub(crate) fn set_window(&mut self, window: Option<Duration>) {
+        self.window = window;
+        self.entries.clear();
+    }
+
+    /// Removes the entries whose window has expired.
+    /// Returns the ones that suppressed at least one duplicate.
+    pub(crate) fn expire(&mut self, now: Instant) -> Vec<Expired> {
+        let window = match self.window {
+            Some(window) => window,
+            None => return Vec::new(),
+        };
+
+        let expired_keys: Vec<(LogLevel, String)> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| now.duration_since(entry.first_seen) >= window)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        expired_keys
+            .into_iter()
+            .filter_map(|key| self.remove(key))
+            .collect()
+    }
+
+    /// Returns `true` if the message should be logged and `false` if it is a duplicate
+    /// of a message seen within the window. The second value is set if an old entry had
+    /// to be evicted to stay within the size bound and had suppressed duplicates.
+    pub(crate) fn check(
+        &mut self,
+        level: LogLevel,
+        message: &str,
+        now: Instant,
+    ) -> (bool, Option<Expired>) {
+        if self.window.is_none() {
+            return (true, None);
+        }
+
+        let key = (level, message.to_string());
+
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.suppressed += 1;
+            return (false, None);
+        }
+
+        let mut evicted = None;
+
+        if self.entries.len() >= MAX_ENTRIES {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.first_seen)
+                .map(|(key, _)| key.clone());
+
+            if let Some(oldest) = oldest {
+                evicted = self.remove(oldest);
+            }
+        }
+
+        self.entries.insert(
+            key,
+            Entry {
+                first_seen: now,
+                suppressed: 0,
+            },
+        );
+
+        (true, evicted)
+    }
+
+    fn remove(&mut self, key: (LogLevel, String)) -> Option<Expired> {
+        let entry = self.entries.remove(&key)?;
+
+        if entry.suppressed == 0 {
+            return None;
+        }
+
+        Some(Expired {
+            level: key.0,
+            message: key.1,
+            suppressed: entry.suppressed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_cache_should_allow_duplicates() {
+        let mut cache = DedupCache::new(None);
+        let now = Instant::now();
+
+        assert!(cache.check(LogLevel::Info, "test", now).0);
+        assert!(cache.check(LogLevel::Info, "test", now).0);
+    }
+
+    #[test]
+    fn duplicate_within_window_should_be_suppressed() {
+        let mut cache = DedupCache::new(Some(Duration::from_secs(10)));
+        let now = Instant::now();
+
+        assert!(cache.check(LogLevel::Info, "test", now).0);
+        assert!(cache.check(LogLevel::Info, "other", now).0);
+        assert!(
+            !cache
+                .check(LogLevel::Info, "test", now + Duration::from_secs(5))
+                .0
+        );
+    }
+
+    #[test]
+    fn same_message_with_other_level_should_not_be_suppressed() {
+        let mut cache = DedupCache::new(Some(Duration::from_secs(10)));
+        let now = Instant::now();
+
+        assert!(cache.check(LogLevel::Info, "test", now).0);
+        assert!(cache.check(LogLevel::Error, "test", now).0);
+    }
+
+    #[test]
+    fn expire_should_report_suppressed_count_and_allow_message_again() {
+        let mut cache = DedupCache::new(Some(Duration::from_secs(10)));
+        let now = Instant::now();
+        cache.check(LogLevel::Info, "test", now);
+        cache.check(LogLevel::Info, "test", now);
+        cache.check(LogLevel::Info, "test", now);
+
+        let expired = cache.expire(now + Duration::from_secs(10));
+
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].message, "test");
+        assert_eq!(expired[0].suppressed, 2);
+        assert!(
+            cache
+                .check(LogLevel::Info, "test", now + Duration::from_secs(10))
+                .0
+        );
+    }
+
+    #[test]
+    fn cache_should_not_grow_beyond_max_entries() {
+        let mut cache = DedupCache::new(Some(Duration::from_secs(10)));
+        let now = Instant::now();
+
+        for i in 0..MAX_ENTRIES * 2 {
+            cache.check(LogLevel::Info, &i.to_string(), now);
+        }
+
+        assert_eq!(cache.entries.len(), MAX_ENTRIES);
+    }
+}
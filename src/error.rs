@@ -0,0 +1,124 @@
+//! The error type of the fallible constructors of a logger
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::config::ConfigError;
+
+/// Why a logger couldn't be created or a sink couldn't be added, as returned by `Logger::try_new_to_file`,
+/// `Logger::add_file_sink`, `LoggerBuilder::build` and `CliOptions::build_logger`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum LoggerError {
+    /// The path of the log file is a directory.
+    FileIsDirectory(String),
+    /// The directory the log file should be in doesn't exist.
+    DirectoryNotFound(String),
+    /// The process isn't allowed to create or open the log file.
+    PermissionDenied(String),
+    /// The configuration of the logger isn't valid.
+    InvalidConfig(Vec<ConfigError>),
+    /// Another I/O error.
+    Io(io::Error),
+}
+
+impl LoggerError {
+    /// Finds the cause of an error that occurred while opening the log file at the path.
+    pub(crate) fn opening(path: &str, e: io::Error) -> Self {
+        if Path::new(path).is_dir() {
+            return LoggerError::FileIsDirectory(path.to_string());
+        }
+
+        match e.kind() {
+            io::ErrorKind::NotFound => LoggerError::DirectoryNotFound(path.to_string()),
+            io::ErrorKind::PermissionDenied => LoggerError::PermissionDenied(path.to_string()),
+            _ => LoggerError::Io(io::Error::new(
+                e.kind(),
+                format!("Error opening log file {}: {}", path, e),
+            )),
+        }
+    }
+
+    /// Returns the kind of I/O error that corresponds to this error.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            LoggerError::FileIsDirectory(_) => io::ErrorKind::Other,
+            LoggerError::DirectoryNotFound(_) => io::ErrorKind::NotFound,
+            LoggerError::PermissionDenied(_) => io::ErrorKind::PermissionDenied,
+            LoggerError::InvalidConfig(_) => io::ErrorKind::InvalidInput,
+            LoggerError::Io(e) => e.kind(),
+        }
+    }
+}
+
+impl fmt::Display for LoggerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoggerError::FileIsDirectory(path) => {
+                write!(f, "the log file {} is a directory", path)
+            }
+            LoggerError::DirectoryNotFound(path) => {
+                write!(f, "the directory of the log file {} doesn't exist", path)
+            }
+            LoggerError::PermissionDenied(path) => {
+                write!(f, "permission denied to open the log file {}", path)
+            }
+            LoggerError::InvalidConfig(errors) => {
+                let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+                write!(f, "invalid logger configuration: {}", messages.join(", "))
+            }
+            LoggerError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoggerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoggerError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LoggerError {
+    fn from(e: io::Error) -> Self {
+        LoggerError::Io(e)
+    }
+}
+
+/// So a `LoggerError` can be returned with `?` from functions that return an `io::Result`.
+impl From<LoggerError> for io::Error {
+    fn from(e: LoggerError) -> Self {
+        match e {
+            LoggerError::Io(e) => e,
+            e => io::Error::new(e.kind(), e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opening_should_find_cause() {
+        let directory = std::env::temp_dir().to_string_lossy().into_owned();
+        let missing = std::env::temp_dir()
+            .join("ultimate_logger_missing_directory")
+            .join("log.txt")
+            .to_string_lossy()
+            .into_owned();
+
+        let error = LoggerError::opening(&directory, io::Error::from(io::ErrorKind::Other));
+        assert!(matches!(&error, LoggerError::FileIsDirectory(path) if *path == directory));
+
+        let error = LoggerError::opening(&missing, io::Error::from(io::ErrorKind::NotFound));
+        assert!(matches!(error, LoggerError::DirectoryNotFound(_)));
+        assert_eq!(
+            io::Error::from(error).to_string(),
+            format!("the directory of the log file {} doesn't exist", missing)
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Escaping and quoting of names, levels, keys and values for every output format
+//!
+//! Every formatter escapes through these functions, so a name or key can never break the format it's written in.
+//! They're public, so custom formatters can escape the same way.
+//!
+//! # Examples
+//!
+//! ```
+//! use ultimate_logger::escape;
+//!
+//! assert_eq!(escape::text("db] [critical"), "db\\] \\[critical");
+//! assert_eq!(escape::json("say \"hi\""), "\"say \\\"hi\\\"\"");
+//! assert_eq!(escape::csv("a,b"), "\"a,b\"");
+//! assert_eq!(escape::logfmt_value("two words"), "\"two words\"");
+//! assert_eq!(escape::logfmt_key("user id"), "user_id");
+//! ```
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// Escapes a value for a bracketed part of a text line, like the name in `[name]`:
+/// brackets and backslashes are prefixed with a backslash, and control characters are written as escape sequences.
+pub fn text(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '[' | ']' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c if c.is_control() => escaped.extend(c.escape_default()),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Returns the value as a JSON string, including the quotes.
+pub fn json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+/// Returns the value as a CSV field (RFC 4180): quoted if it contains a comma, quote, line break
+/// or leading or trailing whitespace, with quotes doubled.
+pub fn csv(value: &str) -> Cow<'_, str> {
+    let needs_quotes = value.contains(|c| matches!(c, ',' | '"' | '\n' | '\r'))
+        || value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace);
+
+    if needs_quotes {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Returns the value for a `key=value` pair in logfmt: quoted and escaped like a JSON string
+/// if it's empty or contains whitespace, `=`, quotes or control characters.
+pub fn logfmt_value(value: &str) -> Cow<'_, str> {
+    let needs_quotes = value.is_empty()
+        || value.contains(|c: char| c.is_whitespace() || c.is_control() || c == '=' || c == '"');
+
+    if needs_quotes {
+        Cow::Owned(json(value))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// Returns the key for a `key=value` pair in logfmt.
+/// Keys can't be quoted, so whitespace, `=`, quotes and control characters are replaced by underscores.
+pub fn logfmt_key(key: &str) -> Cow<'_, str> {
+    let is_invalid = |c: char| c.is_whitespace() || c.is_control() || c == '=' || c == '"';
+
+    if key.is_empty() {
+        Cow::Borrowed("_")
+    } else if key.contains(is_invalid) {
+        Cow::Owned(
+            key.chars()
+                .map(|c| if is_invalid(c) { '_' } else { c })
+                .collect(),
+        )
+    } else {
+        Cow::Borrowed(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRICKY: &str = "a \"quoted\", spaced=value\nwith ünïcödé \u{1F600}\t\\";
+
+    #[test]
+    fn text_should_escape_brackets_and_control_characters() {
+        assert_eq!(text("server-01"), "server-01");
+        assert_eq!(
+            text("a] [critical] b\n[x]\\\t"),
+            "a\\] \\[critical\\] b\\n\\[x\\]\\\\\\t"
+        );
+        assert_eq!(text("ünïcödé"), "ünïcödé");
+    }
+
+    #[test]
+    fn json_should_escape_quotes_and_control_characters() {
+        assert_eq!(json("plain"), "\"plain\"");
+        assert_eq!(
+            json(TRICKY),
+            "\"a \\\"quoted\\\", spaced=value\\nwith ünïcödé \u{1F600}\\t\\\\\""
+        );
+        assert_eq!(json("\u{1}"), "\"\\u0001\"");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_should_match_serde_json() {
+        let escaped = json(TRICKY);
+
+        assert_eq!(serde_json::from_str::<String>(&escaped).unwrap(), TRICKY);
+    }
+
+    #[test]
+    fn csv_should_quote_when_needed() {
+        assert_eq!(csv("plain"), "plain");
+        assert_eq!(csv("ünïcödé"), "ünïcödé");
+        assert_eq!(csv("with space"), "with space");
+        assert_eq!(csv(" leading"), "\" leading\"");
+        assert_eq!(
+            csv(TRICKY),
+            "\"a \"\"quoted\"\", spaced=value\nwith ünïcödé \u{1F600}\t\\\""
+        );
+    }
+
+    #[test]
+    fn logfmt_should_quote_values_and_replace_in_keys() {
+        assert_eq!(logfmt_value("plain"), "plain");
+        assert_eq!(logfmt_value("ünïcödé"), "ünïcödé");
+        assert_eq!(logfmt_value(""), "\"\"");
+        assert_eq!(logfmt_value("a b"), "\"a b\"");
+        assert_eq!(logfmt_value(TRICKY), json(TRICKY));
+
+        assert_eq!(logfmt_key("key"), "key");
+        assert_eq!(logfmt_key("ünï"), "ünï");
+        assert_eq!(logfmt_key(""), "_");
+        assert_eq!(logfmt_key("a key=\"x\"\n"), "a_key__x__");
+    }
+}
@@ -0,0 +1,85 @@
+use std::ffi::OsStr;
+use std::io;
+use std::os::windows::ffi::OsStrExt;
+use std::ptr;
+
+use windows_sys::Win32::Foundation::HANDLE;
+use windows_sys::Win32::System::EventLog::{
+    DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE,
+    EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE, REPORT_EVENT_TYPE,
+};
+
+use crate::log_level::LogLevel;
+
+pub(crate) struct EventLog {
+    handle: HANDLE,
+    source_name: String,
+}
+
+// The handle returned by RegisterEventSourceW may be used from any thread.
+unsafe impl Send for EventLog {}
+
+fn to_wide(s: &str) -> Vec<u16> {
+    OsStr::new(s).encode_wide().chain(Some(0)).collect()
+}
+
+fn event_type(level: LogLevel) -> REPORT_EVENT_TYPE {
+    match level {
+        LogLevel::Error | LogLevel::Critical => EVENTLOG_ERROR_TYPE,
+        LogLevel::Warning => EVENTLOG_WARNING_TYPE,
+        LogLevel::Trace | LogLevel::Debug | LogLevel::Info => EVENTLOG_INFORMATION_TYPE,
+    }
+}
+
+impl EventLog {
+    pub(crate) fn new(source_name: &str) -> io::Result<Self> {
+        let wide_source_name = to_wide(source_name);
+        let handle = unsafe { RegisterEventSourceW(ptr::null(), wide_source_name.as_ptr()) };
+
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self {
+            handle,
+            source_name: source_name.to_string(),
+        })
+    }
+
+    pub(crate) fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    pub(crate) fn report(&mut self, level: LogLevel, message: &str) -> io::Result<()> {
+        let wide_message = to_wide(message);
+        let strings = [wide_message.as_ptr()];
+
+        let succeeded = unsafe {
+            ReportEventW(
+                self.handle,
+                event_type(level),
+                0,
+                0,
+                ptr::null_mut(),
+                1,
+                0,
+                strings.as_ptr(),
+                ptr::null(),
+            )
+        };
+
+        if succeeded == 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for EventLog {
+    fn drop(&mut self) {
+        unsafe {
+            DeregisterEventSource(self.handle);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Backend for the `log` crate, so messages logged with its macros are written by a [`Logger`]
+
+use crate::log_level::LogLevel;
+use crate::shared::SharedLogger;
+use crate::Logger;
+
+impl From<log::Level> for LogLevel {
+    /// Maps the levels of the `log` crate to the log level with the same name.
+    /// The `log` crate has no level for critical messages, so nothing maps to "Critical".
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => LogLevel::Error,
+            log::Level::Warn => LogLevel::Warning,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Debug => LogLevel::Debug,
+            log::Level::Trace => LogLevel::Trace,
+        }
+    }
+}
+
+/// Returns the most verbose level of the `log` crate a logger with this minimum log level writes.
+/// A logger that only writes critical messages gets `Error`, the closest level; those messages are filtered by the logger itself.
+fn level_filter(min_level: LogLevel) -> log::LevelFilter {
+    match min_level {
+        LogLevel::Trace => log::LevelFilter::Trace,
+        LogLevel::Debug => log::LevelFilter::Debug,
+        LogLevel::Info => log::LevelFilter::Info,
+        LogLevel::Warning => log::LevelFilter::Warn,
+        LogLevel::Error | LogLevel::Critical => log::LevelFilter::Error,
+    }
+}
+
+impl log::Log for SharedLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.lock().would_log(LogLevel::from(metadata.level()))
+    }
+
+    fn log(&self, record: &log::Record) {
+        self.lock()
+            .log_fmt(LogLevel::from(record.level()), *record.args());
+    }
+
+    fn flush(&self) {
+        if let Err(e) = SharedLogger::flush(self) {
+            eprintln!("{}", e);
+        }
+    }
+}
+
+/// Registers the logger as the backend of the `log` crate, so the messages of `log::info!`, `log::warn!` and the other macros,
+/// including those in libraries, are written by it, in its format.
+/// The maximum level of the `log` crate is set to the minimum log level of the logger;
+/// call `log::set_max_level` to change it if the minimum log level is changed later.
+///
+/// This is only available with the `log` feature.
+///
+/// # Arguments
+///
+/// * `logger` - The logger to write the messages with.
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::log_level::LogLevel;
+///
+/// ultimate_logger::facade::init(Logger::new(String::from("example"), LogLevel::Info)).unwrap();
+///
+/// log::warn!("Disk usage is at {}%", 91);
+/// ```
+///
+/// This will log the following message:
+/// [2020-12-31 23:59:59.999] [example] [warning] Disk usage is at 91%
+///
+/// # Errors
+///
+/// This function will return an error if a backend was registered already.
+pub fn init(logger: Logger) -> Result<(), log::SetLoggerError> {
+    let max_level = level_filter(logger.min_level());
+    log::set_boxed_logger(Box::new(SharedLogger::new(logger)))?;
+    log::set_max_level(max_level);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn level_filter_should_map_critical_to_error() {
+        assert_eq!(level_filter(LogLevel::Warning), log::LevelFilter::Warn);
+        assert_eq!(level_filter(LogLevel::Critical), log::LevelFilter::Error);
+        assert_eq!(LogLevel::from(log::Level::Warn), LogLevel::Warning);
+    }
+
+    // The backend can only be registered once per process, so this is the only test that does.
+    #[test]
+    fn init_should_write_log_macros() {
+        let buffer = SharedBuffer(Arc::new(Mutex::new(Vec::new())));
+        let logger = Logger::new_to_writer(
+            String::from("test"),
+            LogLevel::Info,
+            Box::new(buffer.clone()),
+        );
+
+        init(logger).unwrap();
+        log::debug!("hidden");
+        log::warn!("Disk usage is at {}%", 91);
+        log::logger().flush();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.ends_with("[test] [warning] Disk usage is at 91%\n"));
+        assert!(init(Logger::new(String::from("test"), LogLevel::Info)).is_err());
+    }
+}
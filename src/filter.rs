@@ -0,0 +1,107 @@
+//! Filters on the text of messages, to only log the messages of interest
+
+use std::fmt;
+
+/// A filter on the text of messages, to use with `Logger::set_message_filter`.
+/// Only messages that match the filter are logged.
+#[derive(Clone)]
+pub enum MessageFilter {
+    /// Matches messages that contain the substring.
+    Contains(String),
+    /// Matches messages that match the regular expression anywhere.
+    /// Only available with the `regex` feature.
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl MessageFilter {
+    /// Creates a filter that matches messages that match the regular expression anywhere.
+    /// Only available with the `regex` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The regular expression, in the syntax of the `regex` crate.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the pattern isn't a valid regular expression.
+    #[cfg(feature = "regex")]
+    pub fn regex(pattern: &str) -> Result<Self, regex::Error> {
+        Ok(MessageFilter::Regex(regex::Regex::new(pattern)?))
+    }
+
+    /// Returns the substring or the regular expression of the filter.
+    pub fn pattern(&self) -> &str {
+        match self {
+            MessageFilter::Contains(substring) => substring,
+            #[cfg(feature = "regex")]
+            MessageFilter::Regex(regex) => regex.as_str(),
+        }
+    }
+
+    /// Returns `true` if the message matches the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    pub fn matches(&self, message: &str) -> bool {
+        match self {
+            MessageFilter::Contains(substring) => message.contains(substring.as_str()),
+            #[cfg(feature = "regex")]
+            MessageFilter::Regex(regex) => regex.is_match(message),
+        }
+    }
+}
+
+impl fmt::Debug for MessageFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MessageFilter::Contains(substring) => {
+                f.debug_tuple("Contains").field(substring).finish()
+            }
+            #[cfg(feature = "regex")]
+            MessageFilter::Regex(regex) => f.debug_tuple("Regex").field(&regex.as_str()).finish(),
+        }
+    }
+}
+
+/// Filters are equal if they're of the same kind and have the same pattern.
+impl PartialEq for MessageFilter {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MessageFilter::Contains(a), MessageFilter::Contains(b)) => a == b,
+            #[cfg(feature = "regex")]
+            (MessageFilter::Regex(a), MessageFilter::Regex(b)) => a.as_str() == b.as_str(),
+            #[cfg(feature = "regex")]
+            _ => false,
+        }
+    }
+}
+
+impl Eq for MessageFilter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_should_match_substring() {
+        let filter = MessageFilter::Contains(String::from("db::pool"));
+
+        assert!(filter.matches("[db::pool] connection opened"));
+        assert!(!filter.matches("[http] request"));
+        assert_eq!(filter.pattern(), "db::pool");
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_should_match_pattern() {
+        let filter = MessageFilter::regex(r"^user \d+ ").unwrap();
+
+        assert!(filter.matches("user 42 logged in"));
+        assert!(!filter.matches("admin 42 logged in"));
+        assert_eq!(filter, MessageFilter::regex(r"^user \d+ ").unwrap());
+        assert_ne!(filter, MessageFilter::Contains(String::from(r"^user \d+ ")));
+        assert!(MessageFilter::regex("(").is_err());
+    }
+}
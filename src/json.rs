@@ -0,0 +1,175 @@
+//! The JSON output format: one JSON object per line, and the JSON Schema describing it
+
+use std::fmt::Write;
+
+use crate::escape;
+use crate::log_level::LogLevel;
+
+/// The JSON Schema of the records written in the JSON output format,
+/// written next to the log files if `Logger::set_json_schema` is enabled.
+pub(crate) const SCHEMA: &str = r#"{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "title": "ultimate_logger record",
+  "type": "object",
+  "properties": {
+    "timestamp": { "type": "string", "format": "date-time" },
+    "logger": { "type": "string" },
+    "source": { "type": "string" },
+    "level": { "enum": ["trace", "debug", "info", "warning", "error", "critical"] },
+    "trace_id": { "type": "string" },
+    "context": { "type": "string" },
+    "scopes": { "type": "array", "items": { "type": "string" } },
+    "fields": { "type": "object", "additionalProperties": { "type": "string" } },
+    "payload": { "type": "object" },
+    "message": { "type": "string" }
+  },
+  "required": ["timestamp", "logger", "level", "message"]
+}
+"#;
+
+/// Returns the path of the schema file of a log file: the path of the log file followed by `.schema.json`.
+pub(crate) fn schema_path(path: &str) -> String {
+    format!("{}.schema.json", path)
+}
+
+/// Returns the fields as a JSON object with string values, like `{"user_id":"42"}`.
+pub(crate) fn fields_object(fields: &[(&str, &str)]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("{}:{}", escape::json(key), escape::json(value)))
+        .collect();
+
+    format!("{{{}}}", fields.join(","))
+}
+
+/// A single record in the JSON output format.
+pub(crate) struct JsonRecord<'a> {
+    pub(crate) timestamp: &'a str,
+    pub(crate) logger: &'a str,
+    pub(crate) source: Option<&'a str>,
+    pub(crate) level: LogLevel,
+    pub(crate) trace_id: Option<&'a str>,
+    pub(crate) context: Option<&'a str>,
+    pub(crate) scopes: &'a [String],
+    pub(crate) fields: &'a [(&'a str, &'a str)],
+    /// A JSON object that is embedded as it is.
+    pub(crate) payload: Option<&'a str>,
+    pub(crate) message: &'a str,
+}
+
+impl JsonRecord<'_> {
+    /// Returns the record as a single line of JSON, without the newline.
+    /// The optional keys are left out if they have no value.
+    pub(crate) fn to_line(&self) -> String {
+        let mut line = String::with_capacity(64 + self.message.len());
+
+        let _ = write!(
+            line,
+            "{{\"timestamp\":{},\"logger\":{}",
+            escape::json(self.timestamp),
+            escape::json(self.logger)
+        );
+
+        if let Some(source) = self.source {
+            let _ = write!(line, ",\"source\":{}", escape::json(source));
+        }
+
+        let _ = write!(line, ",\"level\":\"{}\"", self.level);
+
+        if let Some(trace_id) = self.trace_id {
+            let _ = write!(line, ",\"trace_id\":{}", escape::json(trace_id));
+        }
+
+        if let Some(context) = self.context {
+            let _ = write!(line, ",\"context\":{}", escape::json(context));
+        }
+
+        if !self.scopes.is_empty() {
+            let scopes: Vec<String> = self
+                .scopes
+                .iter()
+                .map(|scope| escape::json(scope))
+                .collect();
+            let _ = write!(line, ",\"scopes\":[{}]", scopes.join(","));
+        }
+
+        if !self.fields.is_empty() {
+            let _ = write!(line, ",\"fields\":{}", fields_object(self.fields));
+        }
+
+        if let Some(payload) = self.payload {
+            let _ = write!(line, ",\"payload\":{}", payload);
+        }
+
+        let _ = write!(line, ",\"message\":{}}}", escape::json(self.message));
+
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(message: &str) -> JsonRecord<'_> {
+        JsonRecord {
+            timestamp: "2020-05-01T12:00:00.000+00:00",
+            logger: "test",
+            source: None,
+            level: LogLevel::Info,
+            trace_id: None,
+            context: None,
+            scopes: &[],
+            fields: &[],
+            payload: None,
+            message,
+        }
+    }
+
+    #[test]
+    fn to_line_should_write_required_keys() {
+        assert_eq!(
+            record("hello").to_line(),
+            "{\"timestamp\":\"2020-05-01T12:00:00.000+00:00\",\"logger\":\"test\",\"level\":\"info\",\"message\":\"hello\"}"
+        );
+    }
+
+    #[test]
+    fn to_line_should_write_optional_keys() {
+        let scopes = [String::from("load"), String::from("parse \"x\"")];
+        let line = JsonRecord {
+            source: Some("resize"),
+            trace_id: Some("abc"),
+            context: Some("worker-1"),
+            scopes: &scopes,
+            fields: &[("user id", "4\"2")],
+            payload: Some("{\"n\":1}"),
+            ..record("hello")
+        }
+        .to_line();
+
+        assert_eq!(
+            line,
+            "{\"timestamp\":\"2020-05-01T12:00:00.000+00:00\",\"logger\":\"test\",\"source\":\"resize\",\"level\":\"info\",\
+             \"trace_id\":\"abc\",\"context\":\"worker-1\",\"scopes\":[\"load\",\"parse \\\"x\\\"\"],\"fields\":{\"user id\":\"4\\\"2\"},\"payload\":{\"n\":1},\"message\":\"hello\"}"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn to_line_should_escape_quotes_and_newlines() {
+        let message = "say \"hi\"\nand \\ leave\t\u{1}";
+        let value: serde_json::Value = serde_json::from_str(&record(message).to_line()).unwrap();
+
+        assert_eq!(value["message"], message);
+        assert_eq!(value["level"], "info");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn schema_should_be_valid_json() {
+        let schema: serde_json::Value = serde_json::from_str(SCHEMA).unwrap();
+
+        assert_eq!(schema["required"][3], "message");
+    }
+}
@@ -64,21 +64,194 @@
 //! - Colored output
 //! - Timestamps
 //! - Multiple loggers with different names
+//! - Multiple files, each with its own minimum log level
 
+#[macro_use]
+mod macros;
+
+pub mod binary;
+pub mod builder;
+mod burst;
+pub mod cli;
+pub mod color;
+pub mod config;
+mod dedup;
+pub mod error;
+pub mod escape;
+#[cfg(all(windows, feature = "eventlog"))]
+mod event_log;
+#[cfg(feature = "log")]
+pub mod facade;
+pub mod filter;
+mod json;
 mod log_file;
 pub mod log_level;
+pub mod log_record;
+pub mod logging;
+#[cfg(feature = "otel")]
+mod otel;
+mod rate_limit;
+pub mod shared;
+pub mod sink;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+pub mod timestamp;
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::sync::{Arc, Mutex, TryLockError, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use burst::BurstLimiter;
+use chrono::{offset, DateTime, Local, Utc};
+use colored::{Color, ColoredString, Colorize};
+use dedup::DedupCache;
+use log_file::{LogFile, SharedLogFile};
+use rate_limit::ByteRateLimiter;
+use sink::{
+    DuplicateSinkPolicy, OutputFormat, PipeAtomicPolicy, Sink, SinkId, SinkInfo, SinkTarget,
+    SpecialFilePolicy,
+};
+
+/// Options that only apply to a single message.
+#[derive(Default, Clone, Copy)]
+struct MessageOptions<'a> {
+    trace_id: Option<&'a str>,
+    /// The context that is written after the name, see `Logger::set_context`.
+    context: Option<&'a str>,
+    color: Option<Color>,
+    scope: Option<&'a str>,
+    /// A JSON object that is embedded in records of the JSON format, see `Logger::log_json_value`.
+    payload: Option<&'a str>,
+    /// Key-value pairs that are appended to the message, see `Logger::log_with_fields`.
+    fields: &'a [(&'a str, &'a str)],
+    /// The message as bytes, which are written verbatim to file and writer sinks in the text format, see `Logger::log_bytes`.
+    raw: Option<&'a [u8]>,
+    /// The time of the message, instead of now, see `Logger::log_at`.
+    time: Option<DateTime<Local>>,
+}
+
+/// Restores the minimum log level of a logger when dropped, see `Logger::with_min_level`.
+struct MinLevelGuard<'a> {
+    logger: &'a mut Logger,
+    previous_min_level: log_level::LogLevel,
+}
+
+impl Drop for MinLevelGuard<'_> {
+    fn drop(&mut self) {
+        self.logger.min_level = self.previous_min_level;
+    }
+}
+
+/// Turns a logger back on and restores its minimum log level when dropped, see `Logger::with_suppressed`.
+struct SuppressGuard<'a> {
+    logger: &'a mut Logger,
+    previous_min_level: log_level::LogLevel,
+    previous_enabled: bool,
+}
+
+impl Drop for SuppressGuard<'_> {
+    fn drop(&mut self) {
+        self.logger.min_level = self.previous_min_level;
+        self.logger.enabled = self.previous_enabled;
+    }
+}
+
+/// Leaves a scope of a logger when dropped, see `Logger::with_scope`.
+struct ScopeGuard<'a> {
+    logger: &'a mut Logger,
+}
+
+impl Drop for ScopeGuard<'_> {
+    fn drop(&mut self) {
+        self.logger.scopes.pop();
+    }
+}
+
+/// Stops the captures started by `Logger::capture` when dropped, including nested ones.
+struct CaptureGuard<'a> {
+    logger: &'a mut Logger,
+    depth: usize,
+}
+
+impl Drop for CaptureGuard<'_> {
+    fn drop(&mut self) {
+        self.logger.captures.truncate(self.depth);
+    }
+}
 
-use chrono::offset;
-use colored::ColoredString;
-use log_file::LogFile;
+/// A function that is called with the log level and the message of every message that is logged,
+/// see [`Logger::set_hook`].
+pub type Hook = Box<dyn FnMut(log_level::LogLevel, &str) + Send>;
 
 /// A logger that can write to a file and/or the console.
+///
+/// Brackets, backslashes and control characters in the name of a logger are escaped with a backslash
+/// (for example `\]` and `\n`), so a name that comes from external input can't corrupt or forge log lines.
 pub struct Logger {
-    name: String,
+    escaped_name: String,
+    unescaped_name: String,
+    source: Option<String>,
+    display_name: String,
     min_level: log_level::LogLevel,
-    log_file: Option<log_file::LogFile>,
-    write_to_console: bool,
-    write_to_file: bool,
+    sinks: Vec<Sink>,
+    next_sink_id: u64,
+    file_rate_limiter: ByteRateLimiter,
+    compact_date: bool,
+    dedup: DedupCache,
+    dedup_summary: bool,
+    burst: BurstLimiter,
+    show_line_number: bool,
+    line_number_reset_on_rotation: bool,
+    trace_id: Option<String>,
+    context: Option<String>,
+    trim_trailing_newline: bool,
+    timestamp_format: String,
+    use_utc: bool,
+    timestamp_style: timestamp::TimestampStyle,
+    print_legend: bool,
+    legend_printed: bool,
+    message_counts: [u64; 6],
+    suppressed_count: u64,
+    scopes: Vec<String>,
+    captures: Vec<Vec<log_record::LogRecord>>,
+    /// The plain lines of the messages written while `Logger::log_formatted` runs.
+    formatted: Option<Vec<String>>,
+    show_scope: bool,
+    max_field_value_len: Option<usize>,
+    append: bool,
+    color_choice: color::ColorChoice,
+    last_message_at: Instant,
+    heartbeat: Option<(log_level::LogLevel, Duration)>,
+    heartbeat_generation: u64,
+    prefix_message_separator: String,
+    created_at: Instant,
+    color_targets: [color::ColorTargets; 6],
+    level_styles: [color::LevelStyle; 6],
+    pipe_atomic_policy: PipeAtomicPolicy,
+    discarded_pipe_writes: u64,
+    colorize_name_by_hash: bool,
+    duplicate_sink_policy: DuplicateSinkPolicy,
+    output_format: OutputFormat,
+    dual_text_min_level: log_level::LogLevel,
+    dual_json_min_level: log_level::LogLevel,
+    special_file_policy: SpecialFilePolicy,
+    flush_on_critical: bool,
+    flush_each: bool,
+    message_filter: Option<filter::MessageFilter>,
+    hook: Option<Hook>,
+    json_schema: bool,
+    size_rotation: Option<(u64, usize)>,
+    #[cfg(feature = "gzip")]
+    compress_rotated: bool,
+    split_streams: bool,
+    console_writer: Option<Box<dyn io::Write + Send>>,
+    start_banner: bool,
+    enabled: bool,
 }
 
 impl Logger {
@@ -100,12 +273,72 @@ impl Logger {
     ///
     /// This will create a logger that writes to the console and has the name "example" and the minimum log level "Warning".
     pub fn new(name: String, min_level: log_level::LogLevel) -> Self {
+        let mut logger = Self::without_sinks(name, min_level);
+        logger.add_sink(SinkTarget::Console, None);
+
+        logger
+    }
+
+    fn without_sinks(name: String, min_level: log_level::LogLevel) -> Self {
         Self {
-            name,
+            display_name: escape::text(&name),
+            escaped_name: escape::text(&name),
+            unescaped_name: name,
+            source: None,
             min_level,
-            log_file: None,
-            write_to_console: true,
-            write_to_file: false,
+            sinks: Vec::new(),
+            next_sink_id: 0,
+            file_rate_limiter: ByteRateLimiter::new(None),
+            compact_date: false,
+            dedup: DedupCache::new(None),
+            dedup_summary: false,
+            burst: BurstLimiter::new(),
+            show_line_number: false,
+            line_number_reset_on_rotation: true,
+            trace_id: None,
+            context: None,
+            trim_trailing_newline: true,
+            timestamp_format: String::from(timestamp::TimestampPreset::Default.format_str()),
+            use_utc: false,
+            timestamp_style: timestamp::TimestampStyle::default(),
+            print_legend: false,
+            legend_printed: false,
+            message_counts: [0; 6],
+            suppressed_count: 0,
+            scopes: Vec::new(),
+            captures: Vec::new(),
+            formatted: None,
+            show_scope: false,
+            max_field_value_len: None,
+            append: true,
+            color_choice: color::ColorChoice::Auto,
+            last_message_at: Instant::now(),
+            heartbeat: None,
+            heartbeat_generation: 0,
+            prefix_message_separator: String::from(" "),
+            created_at: Instant::now(),
+            color_targets: [color::ColorTargets::Both; 6],
+            level_styles: color::Theme::Default.styles(),
+            pipe_atomic_policy: PipeAtomicPolicy::Split,
+            discarded_pipe_writes: 0,
+            colorize_name_by_hash: false,
+            duplicate_sink_policy: DuplicateSinkPolicy::Warn,
+            output_format: OutputFormat::Text,
+            dual_text_min_level: log_level::LogLevel::Trace,
+            dual_json_min_level: log_level::LogLevel::Trace,
+            special_file_policy: SpecialFilePolicy::Allow,
+            flush_on_critical: true,
+            flush_each: false,
+            message_filter: None,
+            hook: None,
+            json_schema: false,
+            size_rotation: None,
+            #[cfg(feature = "gzip")]
+            compress_rotated: false,
+            split_streams: true,
+            console_writer: None,
+            start_banner: false,
+            enabled: true,
         }
     }
 
@@ -144,154 +377,131 @@ impl Logger {
     /// - The file doesn't exist and the parent directory is a file.
     /// - The file doesn't exist and the parent directory is a symlink.
     /// - ...
+    ///
+    /// Use [`try_new_to_file`](#method.try_new_to_file) to handle these errors instead.
     pub fn new_to_file(
         name: String,
         min_level: log_level::LogLevel,
         filepath: String,
         write_to_console_too: bool,
     ) -> Self {
-        let log_file = log_file::LogFile::new(&filepath);
-
-        Self {
-            name,
-            min_level,
-            log_file: Some(log_file),
-            write_to_console: write_to_console_too,
-            write_to_file: true,
-        }
+        Self::try_new_to_file(name, min_level, &filepath, write_to_console_too)
+            .unwrap_or_else(|e| panic!("Error opening log file: {}", e))
     }
 
-    /// Creates a new logger that writes to the console. The minimum log level is set to "Trace".
+    /// Creates a new logger that writes to a file, like [`new_to_file`](#method.new_to_file),
+    /// but returns an error instead of panicking if the file can't be created or opened.
     ///
     /// # Arguments
     ///
     /// * `name` - The name of the logger.
+    /// * `min_level` - The minimum log level.
+    /// * `filepath` - The path to the file. If the file doesn't exist, it will be created.
+    /// * `write_to_console_too` - Whether the logger should write to the console too.
     ///
     /// # Example
     ///
     /// ```
     /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
     ///
-    /// let mut logger = Logger::new_default(String::from("example"));
+    /// let logger = match Logger::try_new_to_file(String::from("example"), LogLevel::Info, "log.txt", false) {
+    ///     Ok(logger) => logger,
+    ///     Err(e) => {
+    ///         eprintln!("Can't open the log file, logging to the console instead: {}", e);
+    ///         Logger::new(String::from("example"), LogLevel::Info)
+    ///     }
+    /// };
     /// ```
     ///
-    /// This will create a logger that writes to the console, has the name "example" and the minimum log level "Trace".
-    pub fn new_default(name: String) -> Self {
-        Self::new(name, log_level::LogLevel::Trace)
-    }
-
-    fn get_date_time() -> String {
-        offset::Local::now().format("%F %T%.3f").to_string()
-    }
-
-    fn get_colored_level_name(level: log_level::LogLevel) -> ColoredString {
-        level.color_string(level.to_string())
-    }
+    /// # Errors
+    ///
+    /// This function will return an error if the file can't be created or opened,
+    /// for the same reasons [`new_to_file`](#method.new_to_file) panics.
+    /// The error tells why, for example [`LoggerError::FileIsDirectory`](error::LoggerError::FileIsDirectory)
+    /// if the path is a directory.
+    pub fn try_new_to_file(
+        name: String,
+        min_level: log_level::LogLevel,
+        filepath: &str,
+        write_to_console_too: bool,
+    ) -> Result<Self, error::LoggerError> {
+        let log_file = log_file::LogFile::new(filepath, true)
+            .map_err(|e| error::LoggerError::opening(filepath, e))?;
 
-    fn get_colored_message(level: log_level::LogLevel, message: &str) -> ColoredString {
-        level.color_string(message)
-    }
+        let mut logger = Self::without_sinks(name, min_level);
+        logger.add_sink(SinkTarget::File(SharedLogFile::new(log_file)), None);
 
-    fn log_to_file(log_file: &mut LogFile, level: log_level::LogLevel, message: &str, name: &str) {
-        log_file.write(&format!(
-            "[{}] [{}] [{}] {}\n",
-            Logger::get_date_time(),
-            name,
-            level.to_string(),
-            message
-        ));
-    }
+        if write_to_console_too {
+            logger.add_sink(SinkTarget::Console, None);
+        }
 
-    fn log_to_console(level: log_level::LogLevel, message: &str, name: &str) {
-        println!(
-            "[{}] [{}] [{}] {}",
-            Logger::get_date_time(),
-            name,
-            Logger::get_colored_level_name(level),
-            Logger::get_colored_message(level, message)
-        );
+        Ok(logger)
     }
 
-    /// Logs a message with the specified log level.
-    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
-    ///
-    /// # Arguments
-    ///
-    /// * `level` - The log level of the message.
-    /// * `message` - The message.
+    /// Creates a new logger that writes to a writer, like an in-memory buffer or a socket.
+    /// The lines are written like to a file: without colors, and in the output format of the logger
+    /// (see [`set_output_format`](#method.set_output_format)).
+    /// Errors of the writer are handled like errors of a log file, see [`log`](#method.log).
     ///
-    /// # Examples
+    /// The writer isn't part of the configuration of the logger,
+    /// so [`reconfigure`](#method.reconfigure) removes it, and [`snapshot_config`](#method.snapshot_config) leaves it out.
     ///
-    /// ## Default log level
+    /// # Arguments
     ///
-    /// ```
-    /// use ultimate_logger::Logger;
-    /// use ultimate_logger::log_level::LogLevel;
+    /// * `name` - The name of the logger.
+    /// * `min_level` - The minimum log level.
+    /// * `writer` - The writer.
     ///
-    /// let mut logger = Logger::new_default(String::from("example"));
+    /// # Example
     ///
-    /// logger.log(LogLevel::Trace, "This is a trace message.");
-    /// logger.log(LogLevel::Debug, "This is a debug message.");
-    /// logger.log(LogLevel::Info, "This is an info message.");
     /// ```
+    /// use std::io::{self, Write};
+    /// use std::sync::{Arc, Mutex};
     ///
-    /// This will log the following messages:
-    /// [2020-12-31 23:59:59.999] [example] [trace] This is a trace message.
-    /// [2020-12-31 23:59:59.999] [example] [debug] This is a debug message.
-    /// [2020-12-31 23:59:59.999] [example] [info] This is an info message.
-    ///
-    /// All messages will be logged, because the minimum log level is the default "Trace".
-    ///
-    /// ## Custom log level
-    /// ```
     /// use ultimate_logger::Logger;
     /// use ultimate_logger::log_level::LogLevel;
     ///
-    /// let mut logger = Logger::new(String::from("example"), LogLevel::Debug);
+    /// #[derive(Clone, Default)]
+    /// struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
     ///
-    /// logger.log(LogLevel::Trace, "This is a trace message.");
-    /// logger.log(LogLevel::Debug, "This is a debug message.");
-    /// logger.log(LogLevel::Info, "This is an info message.");
-    /// ```
+    /// impl Write for SharedBuffer {
+    ///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    ///         self.0.lock().unwrap().write(buf)
+    ///     }
     ///
-    /// This will log the following messages:
-    /// [2020-12-31 23:59:59.999] [example] [debug] This is a debug message.
-    /// [2020-12-31 23:59:59.999] [example] [info] This is an info message.
+    ///     fn flush(&mut self) -> io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
     ///
-    /// Only the messages with the log level "Debug" and "Info" will be logged, because the minimum log level is "Debug".
+    /// let buffer = SharedBuffer::default();
+    /// let mut logger = Logger::new_to_writer(String::from("example"), LogLevel::Info, Box::new(buffer.clone()));
     ///
-    /// # Panics
+    /// logger.info("This is an info message");
     ///
-    /// This function will panic if we try to log to a file and we can't write to the file.
-    pub fn log(&mut self, level: log_level::LogLevel, message: &str) -> bool {
-        if level as u8 >= self.min_level as u8 {
-            if self.write_to_file {
-                if let Some(log_file) = &mut self.log_file {
-                    Logger::log_to_file(log_file, level, message, &self.name);
-                }
-            }
-
-            if self.write_to_console {
-                Logger::log_to_console(level, message, &self.name);
-            }
-
-            return true;
-        }
+    /// let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    /// assert!(output.ends_with("[example] [info] This is an info message\n"));
+    /// ```
+    pub fn new_to_writer(
+        name: String,
+        min_level: log_level::LogLevel,
+        writer: Box<dyn io::Write + Send>,
+    ) -> Self {
+        let mut logger = Self::without_sinks(name, min_level);
+        logger.add_sink(
+            SinkTarget::Writer(SharedLogFile::new(LogFile::from_writer(writer))),
+            None,
+        );
 
-        false
+        logger
     }
 
-    /// Logs a message with the log level "Trace".
-    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
-    /// This is a shortcut for [`log`](#method.log) with the log level "Trace".
-    /// See [`log`](#method.log) for more information.
-    ///
-    /// The message will only be logged if the minimum log level is "Trace".
-    /// This is the default minimum log level.
+    /// Creates a new logger that writes to the console. The minimum log level is set to "Trace".
     ///
     /// # Arguments
     ///
-    /// * `message` - The message.
+    /// * `name` - The name of the logger.
     ///
     /// # Example
     ///
@@ -299,307 +509,7597 @@ impl Logger {
     /// use ultimate_logger::Logger;
     ///
     /// let mut logger = Logger::new_default(String::from("example"));
-    ///
-    /// logger.trace("This is a trace message.");
     /// ```
     ///
-    /// This will log the following message:
-    /// [2020-12-31 23:59:59.999] [example] [trace] This is a trace message.
-    ///
-    /// # Panics
-    ///
-    /// This function will panic if we try to log to a file and we can't write to the file.
-    pub fn trace(&mut self, message: &str) -> bool {
-        self.log(log_level::LogLevel::Trace, message)
+    /// This will create a logger that writes to the console, has the name "example" and the minimum log level "Trace".
+    pub fn new_default(name: String) -> Self {
+        Self::new(name, log_level::LogLevel::Trace)
     }
 
-    /// Logs a message with the log level "Debug".
-    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
-    /// This is a shortcut for [`log`](#method.log) with the log level "Debug".
-    /// See [`log`](#method.log) for more information.
-    ///
-    /// The message will only be logged if the minimum log level is "Trace" or "Debug".
+    /// Creates a new logger that writes to the console, with the minimum log level read from an environment variable,
+    /// like `MYAPP_LOG=debug`. The name of the log level is parsed ignoring case. If the variable isn't set,
+    /// the minimum log level is "Info". If it has an invalid value, the minimum log level is "Info" as well,
+    /// and a warning about the value is logged.
     ///
     /// # Arguments
     ///
-    /// * `message` - The message.
+    /// * `name` - The name of the logger.
+    /// * `var` - The name of the environment variable.
     ///
     /// # Example
     ///
     /// ```
     /// use ultimate_logger::Logger;
     ///
-    /// let mut logger = Logger::new_default(String::from("example"));
-    ///
-    /// logger.debug("This is a debug message.");
+    /// let mut logger = Logger::from_env(String::from("example"), "EXAMPLE_LOG");
     /// ```
     ///
-    /// This will log the following message:
-    /// [2020-12-31 23:59:59.999] [example] [debug] This is a debug message.
-    ///
-    /// # Panics
-    ///
-    /// This function will panic if we try to log to a file and we can't write to the file.
-    pub fn debug(&mut self, message: &str) -> bool {
-        self.log(log_level::LogLevel::Debug, message)
+    /// This will create a logger that writes to the console and has the name "example",
+    /// with the minimum log level "Debug" if the program is run with `EXAMPLE_LOG=debug`.
+    pub fn from_env(name: String, var: &str) -> Self {
+        let value = std::env::var(var);
+        let parsed = value
+            .as_ref()
+            .ok()
+            .map(|value| value.parse::<log_level::LogLevel>());
+        let mut logger = Self::new(
+            name,
+            match parsed {
+                Some(Ok(level)) => level,
+                _ => log_level::LogLevel::Info,
+            },
+        );
+
+        if let Some(Err(e)) = parsed {
+            logger.warning(&format!(
+                "The environment variable {} has an invalid value, using info: {}",
+                var, e
+            ));
+        } else if let Err(std::env::VarError::NotUnicode(_)) = value {
+            logger.warning(&format!(
+                "The environment variable {} isn't valid unicode, using info",
+                var
+            ));
+        }
+
+        logger
     }
 
-    /// Logs a message with the log level "Info".
-    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
-    /// This is a shortcut for [`log`](#method.log) with the log level "Info".
-    /// See [`log`](#method.log) for more information.
-    ///
-    /// The message will only be logged if the minimum log level is "Trace", "Debug" or "Info".
+    /// Creates a new logger that logs nothing: it has no sinks and it's turned off,
+    /// so every log method returns `false` right after checking [`would_log`](#method.would_log).
+    /// Use this as the default of a library that accepts an optional logger,
+    /// instead of an `Option<Logger>` that has to be checked everywhere.
+    /// To be generic over the logger instead, see [`logging::Log`] and [`logging::NullLogger`].
     ///
     /// # Arguments
     ///
-    /// * `message` - The message.
+    /// * `name` - The name of the logger.
     ///
     /// # Example
     ///
     /// ```
     /// use ultimate_logger::Logger;
     ///
-    /// let mut logger = Logger::new_default(String::from("example"));
+    /// let mut logger = Logger::new_null(String::from("example"));
     ///
-    /// logger.info("This is an info message.");
+    /// assert!(!logger.critical("This critical message isn't logged."));
     /// ```
-    ///
-    /// This will log the following message:
-    /// [2020-12-31 23:59:59.999] [example] [info] This is an info message.
-    ///
-    /// # Panics
-    ///
-    /// This function will panic if we try to log to a file and we can't write to the file.
-    pub fn info(&mut self, message: &str) -> bool {
-        self.log(log_level::LogLevel::Info, message)
+    pub fn new_null(name: String) -> Self {
+        let mut logger = Self::without_sinks(name, log_level::LogLevel::Critical);
+        logger.enabled = false;
+
+        logger
     }
 
-    /// Logs a message with the log level "Warning".
-    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
-    /// This is a shortcut for [`log`](#method.log) with the log level "Warning".
-    /// See [`log`](#method.log) for more information.
+    /// Creates a new logger that writes to the Windows Event Log.
+    /// The log levels are mapped to event types: "Error" and "Critical" become errors,
+    /// "Warning" becomes a warning, and the other log levels become information events.
+    /// This is only available on Windows, with the `eventlog` feature.
     ///
-    /// The message will only be logged if the minimum log level is "Trace", "Debug", "Info" or "Warning".
+    /// The event source should be registered once, which requires administrator rights,
+    /// for example with `New-EventLog -LogName Application -Source <source_name>` in PowerShell.
+    /// If it isn't registered, the events are still written to the Application log,
+    /// but the Event Viewer shows a note that the event description can't be found.
     ///
     /// # Arguments
     ///
-    /// * `message` - The message.
+    /// * `source_name` - The name of the event source, which is also used as the name of the logger.
+    /// * `min_level` - The minimum log level.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
     ///
-    /// let mut logger = Logger::new_default(String::from("example"));
+    /// let mut logger = Logger::new_to_event_log(String::from("MyService"), LogLevel::Info).unwrap();
     ///
-    /// logger.warning("This is a warning message.");
+    /// logger.warning("This is written to the Windows Event Log as a warning");
     /// ```
     ///
-    /// This will log the following message:
-    /// [2020-12-31 23:59:59.999] [example] [warning] This is a warning message.
-    ///
-    /// # Panics
+    /// # Errors
     ///
-    /// This function will panic if we try to log to a file and we can't write to the file.
-    pub fn warning(&mut self, message: &str) -> bool {
-        self.log(log_level::LogLevel::Warning, message)
+    /// This function will return an error if the event source can't be opened.
+    #[cfg(all(windows, feature = "eventlog"))]
+    pub fn new_to_event_log(
+        source_name: String,
+        min_level: log_level::LogLevel,
+    ) -> Result<Self, error::LoggerError> {
+        let event_log = event_log::EventLog::new(&source_name)?;
+
+        let mut logger = Self::without_sinks(source_name, min_level);
+        logger.add_sink(SinkTarget::EventLog(event_log), None);
+
+        Ok(logger)
     }
 
-    /// Logs a message with the log level "Error".
-    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
-    /// This is a shortcut for [`log`](#method.log) with the log level "Error".
-    /// See [`log`](#method.log) for more information.
-    ///
-    /// The message will only be logged if the minimum log level is "Trace", "Debug", "Info", "Warning" or "Error".
+    /// Creates a new logger that inserts every message as a row into a SQLite table,
+    /// with the columns `timestamp`, `logger`, `level`, `trace_id`, `message` and `fields`.
+    /// The table is created if it doesn't exist. Rows are inserted in batches, in a single transaction each,
+    /// so they may only show up in the table after [`sqlite_connection`](#method.sqlite_connection)
+    /// is called or the logger is dropped.
+    /// This is only available with the `sqlite` feature.
     ///
     /// # Arguments
     ///
-    /// * `message` - The message.
+    /// * `name` - The name of the logger.
+    /// * `min_level` - The minimum log level.
+    /// * `connection` - The connection to the SQLite database.
+    /// * `table` - The name of the table, which may only contain ASCII letters, digits and underscores.
     ///
     /// # Example
     ///
     /// ```
+    /// use rusqlite::Connection;
     /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
     ///
-    /// let mut logger = Logger::new_default(String::from("example"));
+    /// let connection = Connection::open_in_memory().unwrap();
+    /// let mut logger = Logger::new_to_sqlite(String::from("example"), LogLevel::Info, connection, "logs").unwrap();
     ///
-    /// logger.error("This is an error message.");
+    /// logger.error("This is an error message");
+    ///
+    /// let errors: i64 = logger
+    ///     .sqlite_connection()
+    ///     .unwrap()
+    ///     .query_row("SELECT COUNT(*) FROM logs WHERE level = 'error'", [], |row| row.get(0))
+    ///     .unwrap();
+    /// assert_eq!(errors, 1);
     /// ```
     ///
-    /// This will log the following message:
-    /// [2020-12-31 23:59:59.999] [example] [error] This is an error message.
+    /// # Errors
     ///
-    /// # Panics
+    /// This function will return an error if the name of the table isn't valid or the table can't be created.
+    #[cfg(feature = "sqlite")]
+    pub fn new_to_sqlite(
+        name: String,
+        min_level: log_level::LogLevel,
+        connection: rusqlite::Connection,
+        table: &str,
+    ) -> Result<Self, error::LoggerError> {
+        let sqlite_table = sqlite::SqliteTable::new(connection, table)?;
+
+        let mut logger = Self::without_sinks(name, min_level);
+        logger.add_sink(SinkTarget::Sqlite(sqlite_table), None);
+
+        Ok(logger)
+    }
+
+    /// Inserts the pending rows into the table of the first SQLite sink, and returns its connection,
+    /// for example to query the logged messages.
+    /// Returns `None` if the logger doesn't write to a SQLite table.
+    /// This is only available with the `sqlite` feature.
     ///
-    /// This function will panic if we try to log to a file and we can't write to the file.
-    pub fn error(&mut self, message: &str) -> bool {
-        self.log(log_level::LogLevel::Error, message)
+    /// See [`new_to_sqlite`](#method.new_to_sqlite) for an example.
+    #[cfg(feature = "sqlite")]
+    pub fn sqlite_connection(&mut self) -> Option<&rusqlite::Connection> {
+        let sqlite_table = self
+            .sinks
+            .iter_mut()
+            .find_map(|sink| match &mut sink.target {
+                SinkTarget::Sqlite(sqlite_table) => Some(sqlite_table),
+                _ => None,
+            })?;
+
+        if let Err(e) = sqlite_table.flush() {
+            eprintln!(
+                "Error writing to the SQLite table: {}\nTable was: {}",
+                e,
+                sqlite_table.table()
+            );
+        }
+
+        Some(sqlite_table.connection())
     }
 
-    /// Logs a message with the log level "Critical".
-    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
-    /// This is a shortcut for [`log`](#method.log) with the log level "Critical".
-    /// See [`log`](#method.log) for more information.
-    /// The message will always be logged, because this is the highest log level.
+    fn add_sink(&mut self, target: SinkTarget, min_level: Option<log_level::LogLevel>) -> SinkId {
+        let id = SinkId(self.next_sink_id);
+        self.next_sink_id += 1;
+        self.sinks.push(Sink {
+            id,
+            target,
+            min_level,
+        });
+
+        id
+    }
+
+    /// Adds a file the logger writes to, besides its other sinks.
+    /// Only messages with at least the given log level (and the minimum log level of the logger) are written to the file.
+    /// Returns the id of the new sink.
     ///
     /// # Arguments
     ///
-    /// * `message` - The message.
+    /// * `filepath` - The path to the file. If the file doesn't exist, it will be created.
+    ///   If it exists, it's appended to or truncated, see [`set_append`](#method.set_append).
+    ///   If it contains `{date}`, that is replaced by the local date, like `app-2024-01-15.log` for `app-{date}.log`,
+    ///   and the file of the new date is opened on the first write after midnight.
+    /// * `min_level` - The minimum log level of messages written to the file.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
     ///
-    /// let mut logger = Logger::new_default(String::from("example"));
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), true);
     ///
-    /// logger.critical("This is a critical message.");
+    /// let errors_sink = logger.add_file_sink("errors.txt", LogLevel::Error).unwrap();
     /// ```
     ///
-    /// This will log the following message:
-    /// [2020-12-31 23:59:59.999] [example] [critical] This is a critical message.
+    /// This will write all messages to "log.txt" and the console, and messages with the log level "Error" or "Critical" to "errors.txt" too.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// This function will panic if we try to log to a file and we can't write to the file.
-    pub fn critical(&mut self, message: &str) -> bool {
-        self.log(log_level::LogLevel::Critical, message)
+    /// This function will return an error if the file can't be created or opened,
+    /// which tells why, like [`try_new_to_file`](#method.try_new_to_file).
+    pub fn add_file_sink(
+        &mut self,
+        filepath: &str,
+        min_level: log_level::LogLevel,
+    ) -> Result<SinkId, error::LoggerError> {
+        let log_file = log_file::LogFile::new(filepath, self.append)
+            .map_err(|e| error::LoggerError::opening(filepath, e))?;
+        Logger::check_file_kind(&log_file, self.special_file_policy)?;
+
+        Ok(self.add_file_target(SharedLogFile::new(log_file), Some(min_level)))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use colored::Colorize;
+    fn check_file_kind(log_file: &LogFile, policy: SpecialFilePolicy) -> io::Result<()> {
+        let kind = log_file.kind();
 
-    use super::*;
+        if kind == log_file::FileKind::Regular {
+            return Ok(());
+        }
 
-    // Logger::new()
+        match policy {
+            SpecialFilePolicy::Allow => {}
+            SpecialFilePolicy::Warn => eprintln!(
+                "Warning: log file is a {}, not a regular file, so writing to it may block\nPath to log file was: {}",
+                kind.description(),
+                log_file.path()
+            ),
+            SpecialFilePolicy::Reject => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Log file is a {}, not a regular file: {}",
+                        kind.description(),
+                        log_file.path()
+                    ),
+                ))
+            }
+        }
 
-    #[test]
-    fn new_logger_should_have_correct_min_level() {
+        Ok(())
+    }
+
+    /// Sets what happens when a file opened from now on for a file sink turns out to be a pipe, socket or device
+    /// instead of a regular file, for example with [`add_file_sink`](#method.add_file_sink) or [`reconfigure`](#method.reconfigure).
+    /// See [`SpecialFilePolicy`](sink::SpecialFilePolicy) for the options. The default is `SpecialFilePolicy::Allow`.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    /// use ultimate_logger::sink::SpecialFilePolicy;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_special_file_policy(SpecialFilePolicy::Reject);
+    ///
+    /// # #[cfg(unix)]
+    /// assert!(logger.add_file_sink("/dev/null", LogLevel::Trace).is_err());
+    /// ```
+    pub fn set_special_file_policy(&mut self, policy: SpecialFilePolicy) {
+        self.special_file_policy = policy;
+    }
+
+    /// Adds a file sink, unless another file sink already writes to the same file,
+    /// in which case the duplicate sink policy decides what happens.
+    fn add_file_target(
+        &mut self,
+        shared_log_file: SharedLogFile,
+        min_level: Option<log_level::LogLevel>,
+    ) -> SinkId {
+        {
+            let mut log_file = shared_log_file.lock();
+            let existing = log_file.canonical_path().and_then(|canonical_path| {
+                self.sinks.iter_mut().find(|sink| match &sink.target {
+                    SinkTarget::File(other) => {
+                        other.lock().canonical_path() == Some(canonical_path)
+                    }
+                    _ => false,
+                })
+            });
+
+            match (existing, self.duplicate_sink_policy) {
+                (Some(existing), DuplicateSinkPolicy::Merge) => {
+                    existing.min_level = match (existing.min_level, min_level) {
+                        (Some(existing_min_level), Some(min_level)) => {
+                            Some(existing_min_level.min(min_level))
+                        }
+                        _ => None,
+                    };
+
+                    return existing.id;
+                }
+                (Some(existing), DuplicateSinkPolicy::Warn) => {
+                    eprintln!(
+                        "Warning: log file is already written to by sink {:?}, so messages will be written to it twice\nPath to log file was: {}",
+                        existing.id,
+                        log_file.path()
+                    );
+                }
+                _ => {}
+            }
+
+            if self.json_schema && self.writes_json() {
+                Logger::report_write_error(Logger::write_json_schema(&log_file));
+            }
+
+            log_file.set_size_rotation(self.size_rotation);
+            log_file.set_reset_line_numbers(self.line_number_reset_on_rotation);
+            #[cfg(feature = "gzip")]
+            log_file.set_compress_rotated(self.compress_rotated);
+
+            if self.start_banner && self.output_format == OutputFormat::Text {
+                Logger::report_write_error(self.write_start_banner(&mut log_file));
+            }
+        }
+
+        self.add_sink(SinkTarget::File(shared_log_file), min_level)
+    }
+
+    fn write_start_banner(&self, log_file: &mut LogFile) -> io::Result<()> {
+        let banner = format!(
+            "===== {} started at {} pid={} =====\n",
+            self.escaped_name,
+            self.get_date_time(&offset::Local::now()),
+            std::process::id()
+        );
+
+        log_file.write(banner.as_bytes())
+    }
+
+    /// Sets whether a banner line with the name of the logger, the time and the process id is written to files
+    /// when they're added as sinks, like `===== example started at 2020-12-31 23:59:59.999 pid=1234 =====`,
+    /// so the start of every run stands out when a file is appended to by several runs.
+    /// The banner is also written to files that are appended to, but not to files that were open already,
+    /// and only in the text output format. The default is `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_banner` - Whether the banner should be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_start_banner(true);
+    /// logger.add_file_sink("log.txt", LogLevel::Trace).unwrap();
+    /// ```
+    ///
+    /// This will write the following line to "log.txt":
+    /// ===== example started at 2020-12-31 23:59:59.999 pid=1234 =====
+    pub fn set_start_banner(&mut self, start_banner: bool) {
+        self.start_banner = start_banner;
+    }
+
+    /// Sets what happens when a file sink is added for a file another file sink of the logger already writes to,
+    /// for example with [`add_file_sink`](#method.add_file_sink) or [`reconfigure`](#method.reconfigure).
+    /// See [`DuplicateSinkPolicy`](sink::DuplicateSinkPolicy) for the options. The default is `DuplicateSinkPolicy::Warn`.
+    /// Sinks that were added before aren't affected.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    /// use ultimate_logger::sink::DuplicateSinkPolicy;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), true);
+    ///
+    /// logger.set_duplicate_sink_policy(DuplicateSinkPolicy::Merge);
+    /// let errors_sink = logger.add_file_sink("./log.txt", LogLevel::Error).unwrap();
+    ///
+    /// assert_eq!(logger.sinks().len(), 2);
+    /// assert_eq!(errors_sink, logger.sinks()[0].id);
+    /// ```
+    ///
+    /// This will write every message to "log.txt" only once, even though two sinks were asked for.
+    pub fn set_duplicate_sink_policy(&mut self, policy: DuplicateSinkPolicy) {
+        self.duplicate_sink_policy = policy;
+    }
+
+    /// Sets whether files that are opened from now on are appended to, or truncated when they're opened.
+    /// This doesn't affect files that are already open, only files opened later,
+    /// for example with [`add_file_sink`](#method.add_file_sink).
+    /// The default is `true`, so existing logs are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `append` - Whether files should be appended to instead of truncated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_append(false);
+    /// logger.add_file_sink("log.txt", LogLevel::Trace).unwrap();
+    /// ```
+    ///
+    /// This will empty "log.txt" before the logger writes to it.
+    pub fn set_append(&mut self, append: bool) {
+        self.append = append;
+    }
+
+    /// Rotates the log files when they grow too large: before a write would make a file larger than `max_bytes`,
+    /// the file is renamed to the same name with `.1` before the extension, for example `log.1.txt` for `log.txt`,
+    /// and a new, empty file is opened in its place. Files that were rotated before are shifted up by one,
+    /// and the oldest one is deleted, so at most `max_files` rotated files are kept.
+    /// The old file is flushed before it's renamed, so no lines are lost, and a message is never split over two files.
+    /// If rotating fails, for example because a rotated file can't be renamed, the error is reported,
+    /// the message is still written to the file, and the next write tries to rotate again.
+    /// This applies to the files the logger writes to now and to files opened later. Only regular files are rotated.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes` - The maximum size of a log file in bytes.
+    /// * `max_files` - The number of rotated files to keep. If it's 0, the file is emptied instead of rotated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Info, String::from("log.txt"), false);
+    ///
+    /// logger.set_size_rotation(10 * 1024 * 1024, 5);
+    /// ```
+    ///
+    /// This will keep "log.txt" under 10 MiB, and keep the previous logs in "log.1.txt" up to "log.5.txt".
+    pub fn set_size_rotation(&mut self, max_bytes: u64, max_files: usize) {
+        self.apply_size_rotation(Some((max_bytes, max_files)));
+    }
+
+    /// Stops rotating the log files. Files that were rotated before are kept.
+    /// See [`set_size_rotation`](#method.set_size_rotation) for more information.
+    pub fn disable_size_rotation(&mut self) {
+        self.apply_size_rotation(None);
+    }
+
+    fn apply_size_rotation(&mut self, size_rotation: Option<(u64, usize)>) {
+        self.size_rotation = size_rotation;
+
+        for sink in &mut self.sinks {
+            if let SinkTarget::File(log_file) = &mut sink.target {
+                log_file.lock().set_size_rotation(size_rotation);
+            }
+        }
+    }
+
+    /// Sets whether log files that are rotated out are compressed with gzip, into a file with `.gz` added,
+    /// like `log.1.txt.gz`. This applies to rotation by size (see [`set_size_rotation`](#method.set_size_rotation))
+    /// and to the file of the previous day when a new file is started every day. The file that is written to
+    /// stays uncompressed, and files that already end in `.gz` aren't compressed again.
+    /// The compression runs in a background thread; [`flush`](#method.flush) waits for it to finish.
+    /// Compressed files count towards the number of rotated files to keep. The default is `false`.
+    /// Only available with the `gzip` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `compress_rotated` - Whether rotated files are compressed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Info, String::from("log.txt"), false);
+    ///
+    /// logger.set_size_rotation(10 * 1024 * 1024, 5);
+    /// logger.set_compress_rotated(true);
+    /// ```
+    ///
+    /// This will keep the previous logs in "log.1.txt.gz" up to "log.5.txt.gz".
+    #[cfg(feature = "gzip")]
+    pub fn set_compress_rotated(&mut self, compress_rotated: bool) {
+        self.compress_rotated = compress_rotated;
+
+        for sink in &mut self.sinks {
+            if let SinkTarget::File(log_file) = &mut sink.target {
+                log_file.lock().set_compress_rotated(compress_rotated);
+            }
+        }
+    }
+
+    /// Adds an OpenTelemetry logger the logger forwards its messages to, besides its other sinks.
+    /// Every message becomes an OpenTelemetry log record with the message as body, the name of the logger as target,
+    /// and the trace id, if there is one, as the attribute `trace_id`.
+    /// The log levels are mapped to the severities `TRACE`, `DEBUG`, `INFO`, `WARN`, `ERROR` and `FATAL`.
+    /// Returns the id of the new sink.
+    /// This is only available with the `otel` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `otel_logger` - The OpenTelemetry logger, for example created by the logger provider of the OpenTelemetry SDK.
+    /// * `min_level` - The minimum log level of messages forwarded to OpenTelemetry.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use opentelemetry::logs::{LoggerProvider, NoopLoggerProvider};
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let provider = NoopLoggerProvider::new();
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.add_otel_sink(provider.logger("example"), LogLevel::Info);
+    /// logger.warning("This is forwarded to OpenTelemetry too");
+    /// ```
+    #[cfg(feature = "otel")]
+    pub fn add_otel_sink<L>(&mut self, otel_logger: L, min_level: log_level::LogLevel) -> SinkId
+    where
+        L: opentelemetry::logs::Logger + Send + 'static,
+    {
+        self.add_sink(
+            SinkTarget::OpenTelemetry(otel::OtelSink::new(otel_logger)),
+            Some(min_level),
+        )
+    }
+
+    /// Removes a sink from the logger, so no more messages are written to it.
+    /// A file sink is flushed before its file is closed.
+    /// Because this takes `&mut self`, no message can be in the middle of being written to the sink.
+    /// Returns `true` if the sink was removed and `false` if the logger has no sink with this id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the sink, as returned by [`add_file_sink`](#method.add_file_sink) or [`sinks`](#method.sinks).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let path = std::env::temp_dir().join("debug.txt");
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Info);
+    /// let debug_sink = logger.add_file_sink(path.to_str().unwrap(), LogLevel::Trace).unwrap();
+    ///
+    /// logger.info("This is written to the console and debug.txt");
+    ///
+    /// assert!(logger.remove_sink(debug_sink));
+    /// logger.info("This is only written to the console");
+    ///
+    /// assert!(!logger.remove_sink(debug_sink));
+    /// ```
+    pub fn remove_sink(&mut self, id: SinkId) -> bool {
+        let index = match self.sinks.iter().position(|sink| sink.id == id) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        let mut sink = self.sinks.remove(index);
+
+        if let SinkTarget::File(log_file) | SinkTarget::Writer(log_file) = &mut sink.target {
+            Logger::report_write_error(log_file.lock().flush());
+        }
+
+        true
+    }
+
+    /// Changes the minimum log level of a sink while the logger is running, without affecting its other sinks.
+    /// Messages are still only written if they have at least the minimum log level of the logger too.
+    /// Returns `true` if the minimum log level was changed and `false` if the logger has no sink with this id.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The id of the sink, as returned by [`add_file_sink`](#method.add_file_sink) or [`sinks`](#method.sinks).
+    /// * `level` - The new minimum log level of messages written to the sink.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Trace);
+    /// let console_sink = logger.sinks()[0].id;
+    /// let file_sink = logger.add_file_sink("log.txt", LogLevel::Warning).unwrap();
+    /// logger.set_min_level_for_sink(console_sink, LogLevel::Info);
+    ///
+    /// // During an incident
+    /// logger.set_min_level_for_sink(file_sink, LogLevel::Trace);
+    /// logger.debug("This is only written to log.txt");
+    /// ```
+    pub fn set_min_level_for_sink(&mut self, id: SinkId, level: log_level::LogLevel) -> bool {
+        match self.sinks.iter_mut().find(|sink| sink.id == id) {
+            Some(sink) => {
+                sink.min_level = Some(level);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns information about every sink of the logger, in the order they're written to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    /// use ultimate_logger::sink::SinkKind;
+    ///
+    /// let logger = Logger::new_to_file(String::from("example"), LogLevel::Info, String::from("log.txt"), true);
+    ///
+    /// for sink in logger.sinks() {
+    ///     match sink.kind {
+    ///         SinkKind::Console => println!("{:?}: console, {:?}", sink.id, sink.min_level),
+    ///         SinkKind::File => println!("{:?}: {}, {:?}", sink.id, sink.path.unwrap(), sink.min_level),
+    ///         _ => println!("{:?}: {:?}, {:?}", sink.id, sink.kind, sink.min_level),
+    ///     }
+    /// }
+    /// ```
+    pub fn sinks(&self) -> Vec<SinkInfo> {
+        self.sinks
+            .iter()
+            .map(|sink| sink.info(self.min_level))
+            .collect()
+    }
+
+    /// Applies a whole new configuration to the logger at once.
+    /// All files of the new configuration are opened before the old files are closed,
+    /// so there is no moment without sinks. The sinks get new ids.
+    /// Files the logger already writes to are kept open instead of being reopened, so they're never truncated.
+    /// Sinks that can't be described in a configuration, like writers and SQLite tables, are kept as they are,
+    /// and then the configuration doesn't need sinks of its own.
+    /// If a file can't be opened, the logger keeps its old configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The new configuration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::config::{LoggerConfig, SinkConfig};
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Info);
+    ///
+    /// let mut config = LoggerConfig::new(String::from("example"), LogLevel::Debug);
+    /// config.sinks = vec![SinkConfig::File { path: String::from("log.txt"), min_level: None }];
+    ///
+    /// logger.reconfigure(config).unwrap();
+    /// ```
+    ///
+    /// This will make the logger write messages with the log level "Debug" or higher to "log.txt" only.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of the kind `InvalidInput` if the configuration isn't valid
+    /// (see [`LoggerConfig::validate`](config::LoggerConfig::validate)),
+    /// or an error if one of the files can't be created or opened.
+    /// The error message contains the problems with the configuration or the path of the file.
+    pub fn reconfigure(&mut self, config: config::LoggerConfig) -> io::Result<()> {
+        self.try_reconfigure(config).map_err(io::Error::from)
+    }
+
+    /// Like `reconfigure`, but returns why the configuration couldn't be applied,
+    /// for the constructors that return a `LoggerError`.
+    pub(crate) fn try_reconfigure(
+        &mut self,
+        config: config::LoggerConfig,
+    ) -> Result<(), error::LoggerError> {
+        if let Err(mut errors) = config.validate() {
+            // Sinks that can't be described in a configuration are kept, so the logger still has sinks.
+            if self.sinks.iter().any(|sink| !sink.target.is_configurable()) {
+                errors.retain(|e| *e != config::ConfigError::NoSinks);
+            }
+
+            if !errors.is_empty() {
+                return Err(error::LoggerError::InvalidConfig(errors));
+            }
+        }
+
+        // Only lives while reconfiguring, so the size of the open targets doesn't matter.
+        #[allow(clippy::large_enum_variant)]
+        enum NewTarget {
+            Open(SinkTarget),
+            /// The index of an open sink in `self.sinks`.
+            Keep(usize),
+        }
+
+        let mut targets = Vec::with_capacity(config.sinks.len());
+
+        for sink in &config.sinks {
+            match sink {
+                config::SinkConfig::Console { min_level } => {
+                    targets.push((NewTarget::Open(SinkTarget::Console), *min_level))
+                }
+                config::SinkConfig::File { path, min_level } => {
+                    let open_sink = self.sinks.iter().enumerate().position(|(index, sink)| {
+                        match &sink.target {
+                            SinkTarget::File(log_file) => {
+                                log_file.lock().configured_path() == path
+                                    && !targets.iter().any(|(target, _)| {
+                                        matches!(target, NewTarget::Keep(kept) if *kept == index)
+                                    })
+                            }
+                            _ => false,
+                        }
+                    });
+
+                    if let Some(index) = open_sink {
+                        targets.push((NewTarget::Keep(index), *min_level));
+                        continue;
+                    }
+
+                    let log_file = log_file::LogFile::new(path, config.append)
+                        .map_err(|e| error::LoggerError::opening(path, e))?;
+                    Logger::check_file_kind(&log_file, config.special_file_policy)?;
+                    targets.push((
+                        NewTarget::Open(SinkTarget::File(SharedLogFile::new(log_file))),
+                        *min_level,
+                    ));
+                }
+            }
+        }
+
+        self.flush_burst_summaries();
+
+        let mut old_sinks: Vec<Option<Sink>> = std::mem::take(&mut self.sinks)
+            .into_iter()
+            .map(Some)
+            .collect();
+        // Whether the target was opened now, or was already open and is kept.
+        // Every open sink is kept at most once, so it's still there when it's taken.
+        let targets: Vec<(SinkTarget, Option<log_level::LogLevel>, bool)> = targets
+            .into_iter()
+            .filter_map(|(target, min_level)| match target {
+                NewTarget::Open(target) => Some((target, min_level, true)),
+                NewTarget::Keep(index) => old_sinks[index]
+                    .take()
+                    .map(|sink| (sink.target, min_level, false)),
+            })
+            .collect();
+
+        for mut sink in old_sinks.into_iter().flatten() {
+            if !sink.target.is_configurable() {
+                self.sinks.push(sink);
+            } else if let SinkTarget::File(log_file) = &mut sink.target {
+                Logger::report_write_error(log_file.lock().flush());
+            }
+        }
+
+        self.apply_sink_settings(&config);
+
+        for (target, min_level, opened) in targets {
+            match target {
+                SinkTarget::File(log_file) if opened => {
+                    self.add_file_target(log_file, min_level);
+                }
+                target => {
+                    self.add_sink(target, min_level);
+                }
+            }
+        }
+
+        self.apply_settings(config);
+
+        Ok(())
+    }
+
+    /// Applies the settings of a configuration that have to be in place before its sinks are added,
+    /// because adding a file sink depends on them, like the start banner.
+    fn apply_sink_settings(&mut self, config: &config::LoggerConfig) {
+        self.set_name(config.name.clone());
+        self.set_source(config.source.clone());
+        self.timestamp_format = config.timestamp_format.clone();
+        self.use_utc = config.use_utc;
+        self.timestamp_style = config.timestamp_style;
+        self.duplicate_sink_policy = config.duplicate_sink_policy;
+        self.output_format = config.output_format;
+        self.start_banner = config.start_banner;
+    }
+
+    /// Applies the other settings of a configuration, after `apply_sink_settings` and adding the sinks.
+    fn apply_settings(&mut self, config: config::LoggerConfig) {
+        self.dual_text_min_level = config.dual_text_min_level;
+        self.dual_json_min_level = config.dual_json_min_level;
+        self.min_level = config.min_level;
+        self.set_compact_date(config.compact_date);
+        self.show_line_number = config.show_line_number;
+        self.set_line_number_reset_on_rotation(config.line_number_reset_on_rotation);
+        self.trim_trailing_newline = config.trim_trailing_newline;
+        self.trace_id = config.trace_id;
+        self.context = config.context;
+        self.set_max_bytes_per_sec(config.max_bytes_per_sec);
+        self.set_dedup_window(config.dedup_window);
+        self.dedup_summary = config.dedup_summary;
+        self.print_legend = config.print_legend;
+        self.show_scope = config.show_scope;
+        self.max_field_value_len = config.max_field_value_len;
+        self.append = config.append;
+        self.set_color_choice(config.color_choice);
+        self.prefix_message_separator = config.prefix_message_separator;
+        self.color_targets = config.color_targets;
+        self.level_styles = config.level_styles;
+        self.pipe_atomic_policy = config.pipe_atomic_policy;
+        self.colorize_name_by_hash = config.colorize_name_by_hash;
+        self.special_file_policy = config.special_file_policy;
+        self.flush_on_critical = config.flush_on_critical;
+        self.flush_each = config.flush_each;
+        self.message_filter = config.message_filter;
+        self.json_schema = config.json_schema;
+        self.write_json_schemas();
+        self.apply_size_rotation(config.size_rotation);
+        #[cfg(feature = "gzip")]
+        self.set_compress_rotated(config.compress_rotated);
+        self.split_streams = config.split_streams;
+        self.enabled = config.enabled;
+
+        match config.burst_summary {
+            Some((first_k, summary_interval)) => self.set_burst_summary(first_k, summary_interval),
+            None => self.disable_burst_summary(),
+        }
+    }
+
+    /// Creates a logger with another name that writes to the same console, files and writers as this one,
+    /// for example for the components of an application that share a log file.
+    /// The files and writers are shared rather than opened again, so the lines of both loggers are never interleaved,
+    /// and rotation keeps working for both. The other settings are copied, like with [`snapshot_config`](#method.snapshot_config);
+    /// changing them later only affects one of the loggers.
+    /// Sinks that can't be part of a configuration, like SQLite tables, the hook and the console writer aren't copied.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the new logger.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut database = Logger::new_to_file(String::from("database"), LogLevel::Info, String::from("log.txt"), false);
+    /// let mut server = database.clone_with_name(String::from("server"));
+    ///
+    /// database.info("Connected");
+    /// server.info("Listening on port 8080");
+    /// ```
+    ///
+    /// This will log the following messages to "log.txt":
+    /// [2020-12-31 23:59:59.999] [database] [info] Connected
+    /// [2020-12-31 23:59:59.999] [server] [info] Listening on port 8080
+    pub fn clone_with_name(&self, name: String) -> Logger {
+        let mut config = self.snapshot_config();
+        config.name = name;
+        let mut logger = Logger::without_sinks(config.name.clone(), config.min_level);
+        logger.apply_sink_settings(&config);
+        logger.apply_settings(config);
+
+        for sink in &self.sinks {
+            let target = match &sink.target {
+                SinkTarget::Console => SinkTarget::Console,
+                SinkTarget::File(log_file) => SinkTarget::File(log_file.clone()),
+                SinkTarget::Writer(log_file) => SinkTarget::Writer(log_file.clone()),
+                #[allow(unreachable_patterns)]
+                _ => continue,
+            };
+            logger.add_sink(target, sink.min_level);
+        }
+
+        logger
+    }
+
+    /// Returns the current configuration of the logger, to restore it later with [`restore_config`](#method.restore_config),
+    /// for example to isolate a test or to change settings temporarily.
+    /// Console and file sinks are part of the snapshot, with files referenced by path rather than by the open file.
+    /// Other sinks, like writers and SQLite tables, can't be part of a configuration and are left out,
+    /// but restoring the snapshot keeps them.
+    ///
+    /// # Example
+    ///
+    /// See [`restore_config`](#method.restore_config).
+    pub fn snapshot_config(&self) -> config::LoggerConfig {
+        let sinks = self
+            .sinks
+            .iter()
+            .filter_map(|sink| match &sink.target {
+                SinkTarget::Console => Some(config::SinkConfig::Console {
+                    min_level: sink.min_level,
+                }),
+                SinkTarget::File(log_file) => Some(config::SinkConfig::File {
+                    path: log_file.lock().configured_path().to_string(),
+                    min_level: sink.min_level,
+                }),
+                #[allow(unreachable_patterns)]
+                _ => None,
+            })
+            .collect();
+
+        config::LoggerConfig {
+            name: self.unescaped_name.clone(),
+            source: self.source.clone(),
+            min_level: self.min_level,
+            sinks,
+            timestamp_format: self.timestamp_format.clone(),
+            use_utc: self.use_utc,
+            timestamp_style: self.timestamp_style,
+            compact_date: self.compact_date,
+            show_line_number: self.show_line_number,
+            line_number_reset_on_rotation: self.line_number_reset_on_rotation,
+            trim_trailing_newline: self.trim_trailing_newline,
+            trace_id: self.trace_id.clone(),
+            context: self.context.clone(),
+            max_bytes_per_sec: self.file_rate_limiter.max_bytes_per_sec(),
+            dedup_window: self.dedup.window(),
+            dedup_summary: self.dedup_summary,
+            burst_summary: self.burst.settings(),
+            print_legend: self.print_legend,
+            show_scope: self.show_scope,
+            max_field_value_len: self.max_field_value_len,
+            append: self.append,
+            color_choice: self.color_choice,
+            prefix_message_separator: self.prefix_message_separator.clone(),
+            color_targets: self.color_targets,
+            level_styles: self.level_styles,
+            pipe_atomic_policy: self.pipe_atomic_policy,
+            colorize_name_by_hash: self.colorize_name_by_hash,
+            duplicate_sink_policy: self.duplicate_sink_policy,
+            output_format: self.output_format,
+            dual_text_min_level: self.dual_text_min_level,
+            dual_json_min_level: self.dual_json_min_level,
+            special_file_policy: self.special_file_policy,
+            flush_on_critical: self.flush_on_critical,
+            flush_each: self.flush_each,
+            message_filter: self.message_filter.clone(),
+            json_schema: self.json_schema,
+            size_rotation: self.size_rotation,
+            #[cfg(feature = "gzip")]
+            compress_rotated: self.compress_rotated,
+            split_streams: self.split_streams,
+            start_banner: self.start_banner,
+            enabled: self.enabled,
+        }
+    }
+
+    /// Restores a configuration saved with [`snapshot_config`](#method.snapshot_config).
+    /// This works like [`reconfigure`](#method.reconfigure): files the logger still writes to are kept open,
+    /// and files it stopped writing to in the meantime are reopened (appended to, or truncated if the snapshot says so).
+    /// Sinks that can't be part of a snapshot, like writers and SQLite tables, are kept as they are.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - The configuration to restore.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Info, String::from("log.txt"), true);
+    /// let snapshot = logger.snapshot_config();
+    ///
+    /// logger.set_show_line_number(true);
+    /// logger.remove_sink(logger.sinks()[1].id);
+    /// logger.info("This is only written to log.txt, with a line number");
+    ///
+    /// logger.restore_config(snapshot).unwrap();
+    /// logger.info("This is written to log.txt and the console again, without a line number");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the configuration isn't valid or one of the files can't be opened,
+    /// see [`reconfigure`](#method.reconfigure). The logger keeps its current configuration then.
+    pub fn restore_config(&mut self, config: config::LoggerConfig) -> io::Result<()> {
+        self.reconfigure(config)
+    }
+
+    /// Sets the maximum number of bytes per second that may be written to the log file.
+    /// Lines that would exceed the limit are dropped from the file (the console is unaffected),
+    /// and their size is added to the [`suppressed_bytes`](#method.suppressed_bytes) counter.
+    /// Pass `None` to remove the limit, which is the default.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes_per_sec` - The maximum number of bytes per second, or `None` for no limit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), false);
+    ///
+    /// logger.set_max_bytes_per_sec(Some(64 * 1024));
+    /// ```
+    ///
+    /// This will limit the file output of the logger to 64 KiB per second.
+    pub fn set_max_bytes_per_sec(&mut self, max_bytes_per_sec: Option<u64>) {
+        self.file_rate_limiter
+            .set_max_bytes_per_sec(max_bytes_per_sec);
+    }
+
+    /// Returns the total number of bytes that were not written to the log file
+    /// because the limit set with [`set_max_bytes_per_sec`](#method.set_max_bytes_per_sec) was exceeded.
+    pub fn suppressed_bytes(&self) -> u64 {
+        self.file_rate_limiter.suppressed_bytes()
+    }
+
+    /// Sets what happens to a message that is too large to be written atomically to a file sink that is a pipe (a FIFO).
+    /// See [`PipeAtomicPolicy`](sink::PipeAtomicPolicy) for the options. The default is `PipeAtomicPolicy::Split`.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::sink::PipeAtomicPolicy;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_pipe_atomic_policy(PipeAtomicPolicy::Discard);
+    /// ```
+    pub fn set_pipe_atomic_policy(&mut self, policy: PipeAtomicPolicy) {
+        self.pipe_atomic_policy = policy;
+    }
+
+    /// Sets whether files are flushed right after a message with the log level "Critical" is written to them,
+    /// so the message reaches the operating system even if the process crashes right after it.
+    /// This happens regardless of any buffering. The default is `true`;
+    /// disable it only if throughput matters more than keeping the last messages before a crash.
+    ///
+    /// # Arguments
+    ///
+    /// * `flush_on_critical` - Whether critical messages are flushed immediately.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), false);
+    ///
+    /// logger.set_flush_on_critical(false);
+    /// ```
+    pub fn set_flush_on_critical(&mut self, flush_on_critical: bool) {
+        self.flush_on_critical = flush_on_critical;
+    }
+
+    /// Sets a substring messages must contain to be logged, for example the name of a module,
+    /// or `None` to log all messages, which is the default.
+    /// The filter is checked after the log level; messages that don't contain the substring aren't logged,
+    /// and the logging methods return `false`, like for messages with a log level that is too low.
+    /// Key-value pairs added with [`log_with_fields`](#method.log_with_fields) aren't searched.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The substring, or `None` to remove the filter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_message_filter(Some(String::from("db::pool")));
+    ///
+    /// assert!(logger.trace("db::pool: connection opened"));
+    /// assert!(!logger.error("http: request failed"));
+    /// ```
+    pub fn set_message_filter(&mut self, pattern: Option<String>) {
+        self.message_filter = pattern.map(filter::MessageFilter::Contains);
+    }
+
+    /// Sets a regular expression messages must match to be logged, or `None` to log all messages.
+    /// This works like [`set_message_filter`](#method.set_message_filter), with a regular expression instead of a substring.
+    /// It replaces a substring filter, and the other way around.
+    ///
+    /// This is only available with the `regex` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The regular expression, in the syntax of the `regex` crate, or `None` to remove the filter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_message_filter_regex(Some(r"^(db|cache)::")).unwrap();
+    ///
+    /// assert!(logger.info("cache::lru: evicted 3 entries"));
+    /// assert!(!logger.info("http: request handled"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the pattern isn't a valid regular expression.
+    /// The filter isn't changed then.
+    #[cfg(feature = "regex")]
+    pub fn set_message_filter_regex(&mut self, pattern: Option<&str>) -> Result<(), regex::Error> {
+        self.message_filter = pattern.map(filter::MessageFilter::regex).transpose()?;
+
+        Ok(())
+    }
+
+    /// Sets a function that is called with the log level and the message of every message that is logged,
+    /// before it's written to the sinks, for example to forward errors to a crash reporter.
+    /// It isn't called for messages that aren't logged, for example because their log level is too low.
+    /// Calling this again replaces the previous hook. The hook is kept when the logger is reconfigured.
+    ///
+    /// If the hook panics, the panic is caught, and the message is still logged;
+    /// the hook is called again for the next message.
+    /// The hook must not log with the same logger, which is borrowed while the hook runs.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The function to call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let errors = Arc::new(AtomicUsize::new(0));
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// let counter = Arc::clone(&errors);
+    /// logger.set_hook(Box::new(move |level, _message| {
+    ///     if level >= LogLevel::Error {
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }));
+    /// logger.error("Connection lost");
+    ///
+    /// assert_eq!(errors.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn set_hook(&mut self, hook: Hook) {
+        self.hook = Some(hook);
+    }
+
+    /// Removes the function set with [`set_hook`](#method.set_hook).
+    pub fn remove_hook(&mut self) {
+        self.hook = None;
+    }
+
+    /// Sets whether files and writers are flushed after every message, so no message is lost if the process crashes,
+    /// at the cost of a system call per message. With `false`, messages are buffered, see [`flush`](#method.flush).
+    /// The default is `false`, for speed.
+    /// Flushing hands the messages to the operating system; it doesn't wait until they're on the disk.
+    ///
+    /// # Arguments
+    ///
+    /// * `flush_each` - Whether every message is flushed immediately.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), false);
+    ///
+    /// logger.set_flush_each(true);
+    /// logger.info("This message is in log.txt right away.");
+    /// ```
+    pub fn set_flush_each(&mut self, flush_each: bool) {
+        self.flush_each = flush_each;
+    }
+
+    /// Writes the buffered messages to the files and writers of the logger.
+    /// Writes to regular files are buffered, so many messages can be logged with few system calls;
+    /// they're written when the buffer is full, when this is called, and when the logger is dropped.
+    /// Messages with the log level "Critical" are flushed right away, see [`set_flush_on_critical`](#method.set_flush_on_critical).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), false);
+    ///
+    /// for i in 0..1000 {
+    ///     logger.debug(&format!("Step {}", i));
+    /// }
+    ///
+    /// logger.flush().unwrap();
+    /// ```
+    ///
+    /// This will write the 1000 debug messages to "log.txt" in a few large writes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error that occurred while flushing; the other sinks are flushed anyway.
+    /// The error message contains the path of the file.
+    pub fn flush(&mut self) -> io::Result<()> {
+        let mut result = match &mut self.console_writer {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        };
+
+        for sink in &mut self.sinks {
+            if let SinkTarget::File(log_file) | SinkTarget::Writer(log_file) = &mut sink.target {
+                let sink_result = log_file.lock().flush();
+
+                if result.is_ok() {
+                    result = sink_result;
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Sets the format the logger writes its file and writer sinks in.
+    /// See [`OutputFormat`](sink::OutputFormat) for the options. The default is `OutputFormat::Text`.
+    /// The console gets text, except in the JSON format, where it gets the same uncolored JSON lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `output_format` - The format.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    /// use ultimate_logger::sink::OutputFormat;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.bin"), false);
+    ///
+    /// logger.set_output_format(OutputFormat::Binary);
+    /// ```
+    ///
+    /// See [`binary::decode_binary_log`](binary::decode_binary_log) to read the file back.
+    pub fn set_output_format(&mut self, output_format: OutputFormat) {
+        self.output_format = output_format;
+        self.write_json_schemas();
+    }
+
+    /// Sets the minimum log levels of the text lines and the JSON lines in the dual format
+    /// (see [`set_output_format`](#method.set_output_format) and [`OutputFormat::Dual`](sink::OutputFormat::Dual)).
+    /// They apply on top of the minimum log levels of the logger and the sinks, and not to the console.
+    /// The default is `LogLevel::Trace` for both.
+    ///
+    /// # Arguments
+    ///
+    /// * `text_min_level` - The minimum log level of the text lines.
+    /// * `json_min_level` - The minimum log level of the JSON lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    /// use ultimate_logger::sink::OutputFormat;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), false);
+    ///
+    /// logger.set_output_format(OutputFormat::Dual);
+    /// logger.set_dual_min_levels(LogLevel::Warning, LogLevel::Trace);
+    /// ```
+    ///
+    /// This will write every message to "log.txt" as JSON, but only warnings and worse as text.
+    pub fn set_dual_min_levels(
+        &mut self,
+        text_min_level: log_level::LogLevel,
+        json_min_level: log_level::LogLevel,
+    ) {
+        self.dual_text_min_level = text_min_level;
+        self.dual_json_min_level = json_min_level;
+    }
+
+    /// Sets whether a JSON Schema describing the records is written next to every log file in the JSON or dual format
+    /// (see [`set_output_format`](#method.set_output_format)), so consumers of the logs can validate them.
+    /// The schema of `app.log` is written to `app.log.schema.json`, when the file sink is added
+    /// or this setting or one of these formats is enabled. Files that aren't regular files, like pipes, don't get a schema.
+    /// Errors writing a schema are printed to stderr. The default is `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_schema` - Whether to write the schema files.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    /// use ultimate_logger::sink::OutputFormat;
+    ///
+    /// let path = std::env::temp_dir().join("log.json");
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Info, path.to_string_lossy().into_owned(), false);
+    ///
+    /// logger.set_output_format(OutputFormat::Json);
+    /// logger.set_json_schema(true);
+    ///
+    /// assert!(std::env::temp_dir().join("log.json.schema.json").exists());
+    /// ```
+    pub fn set_json_schema(&mut self, json_schema: bool) {
+        self.json_schema = json_schema;
+        self.write_json_schemas();
+    }
+
+    /// Returns whether the file and writer sinks get JSON lines, in the JSON or the dual format.
+    fn writes_json(&self) -> bool {
+        matches!(self.output_format, OutputFormat::Json | OutputFormat::Dual)
+    }
+
+    fn write_json_schemas(&self) {
+        if !self.json_schema || !self.writes_json() {
+            return;
+        }
+
+        for sink in &self.sinks {
+            if let SinkTarget::File(log_file) = &sink.target {
+                Logger::report_write_error(Logger::write_json_schema(&log_file.lock()));
+            }
+        }
+    }
+
+    fn write_json_schema(log_file: &LogFile) -> io::Result<()> {
+        if log_file.kind() != log_file::FileKind::Regular {
+            return Ok(());
+        }
+
+        let path = json::schema_path(log_file.path());
+        std::fs::write(&path, json::SCHEMA).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("Error writing JSON schema {}: {}", path, e),
+            )
+        })
+    }
+
+    /// Returns the number of messages that were not written to a pipe because they were too large to be written atomically,
+    /// see [`set_pipe_atomic_policy`](#method.set_pipe_atomic_policy).
+    pub fn discarded_pipe_writes(&self) -> u64 {
+        self.discarded_pipe_writes
+    }
+
+    /// Enables or disables the compact date mode for the log file.
+    /// In compact mode, the lines in the file only contain the time, and a marker line
+    /// with the full date is written whenever the date changes. The console output is unaffected.
+    ///
+    /// # Arguments
+    ///
+    /// * `compact_date` - Whether the compact date mode should be enabled.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), false);
+    ///
+    /// logger.set_compact_date(true);
+    /// logger.info("First message");
+    /// logger.info("Second message");
+    /// ```
+    ///
+    /// This will write the following to the file:
+    /// ```text
+    /// ----- 2020-05-01 -----
+    /// [12:00:00.000] [example] [info] First message
+    /// [12:00:00.000] [example] [info] Second message
+    /// ```
+    pub fn set_compact_date(&mut self, compact_date: bool) {
+        self.compact_date = compact_date;
+
+        for sink in &mut self.sinks {
+            if let SinkTarget::File(log_file) | SinkTarget::Writer(log_file) = &mut sink.target {
+                log_file.lock().set_last_date(None);
+            }
+        }
+    }
+
+    /// Sets the window in which duplicate messages are suppressed.
+    /// A message with the same level and text as a message logged less than `window` ago isn't logged,
+    /// even if other messages were logged in between. Pass `None` to disable this, which is the default.
+    ///
+    /// At most 256 distinct messages are remembered. When more are logged, the oldest one is forgotten.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The duration in which duplicates are suppressed, or `None` to log all duplicates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_dedup_window(Some(Duration::from_secs(60)));
+    ///
+    /// logger.warning("Disk almost full");
+    /// logger.info("Something else");
+    /// logger.warning("Disk almost full");
+    /// ```
+    ///
+    /// The second "Disk almost full" message won't be logged, because it was already logged less than a minute ago.
+    pub fn set_dedup_window(&mut self, window: Option<Duration>) {
+        self.dedup.set_window(window);
+    }
+
+    /// Enables or disables the summary of suppressed duplicates.
+    /// If enabled, a line with the number of suppressed duplicates of a message is logged
+    /// with the next message after the window of that message expired.
+    /// See [`set_dedup_window`](#method.set_dedup_window) for more information.
+    ///
+    /// # Arguments
+    ///
+    /// * `dedup_summary` - Whether the summary should be logged.
+    pub fn set_dedup_summary(&mut self, dedup_summary: bool) {
+        self.dedup_summary = dedup_summary;
+    }
+
+    /// Limits how often the same message is logged in a burst.
+    /// The first `first_k` occurrences of a message (with the same log level) are logged as usual.
+    /// After that, the message is suppressed, and every `summary_interval` a line is logged with
+    /// the number of times it was suppressed. Once a message isn't logged for a whole interval,
+    /// its next burst is logged in full again.
+    /// Suppressed occurrences that weren't summarized yet are summarized when the logger is dropped.
+    ///
+    /// At most 256 distinct messages are tracked. When more are logged, the oldest one is forgotten.
+    ///
+    /// # Arguments
+    ///
+    /// * `first_k` - The number of occurrences that are logged before the message is suppressed.
+    /// * `summary_interval` - The interval between summaries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_burst_summary(3, Duration::from_secs(60));
+    ///
+    /// for _ in 0..1000 {
+    ///     logger.error("Connection refused");
+    /// }
+    /// ```
+    ///
+    /// This will log "Connection refused" three times. When the logger is dropped, it logs:
+    /// [2020-12-31 23:59:59.999] [example] [error] The following message occurred 997 more times since the last summary: Connection refused
+    pub fn set_burst_summary(&mut self, first_k: u32, summary_interval: Duration) {
+        self.flush_burst_summaries();
+        self.burst.set(Some((first_k, summary_interval)));
+    }
+
+    /// Stops limiting bursts of the same message.
+    /// Suppressed occurrences that weren't summarized yet are summarized first.
+    /// See [`set_burst_summary`](#method.set_burst_summary) for more information.
+    pub fn disable_burst_summary(&mut self) {
+        self.flush_burst_summaries();
+        self.burst.set(None);
+    }
+
+    fn flush_burst_summaries(&mut self) {
+        for summary in self.burst.drain() {
+            let result = self.write_lines(
+                summary.level,
+                &[&format!(
+                    "The following message occurred {} more times since the last summary: {}",
+                    summary.suppressed, summary.message
+                )],
+                MessageOptions::default(),
+            );
+            Logger::report_write_error(result);
+        }
+    }
+
+    /// Enables or disables line numbers in the log file.
+    /// If enabled, every line written to the log file is prefixed with its line number,
+    /// counting from 1 for the first line this logger writes to the file.
+    /// The console output is unaffected.
+    ///
+    /// # Arguments
+    ///
+    /// * `show_line_number` - Whether the line numbers should be written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), false);
+    ///
+    /// logger.set_show_line_number(true);
+    /// logger.info("First message");
+    /// logger.info("Second message");
+    /// ```
+    ///
+    /// This will write the following to the file:
+    /// ```text
+    /// 1: [2020-05-01 12:00:00.000] [example] [info] First message
+    /// 2: [2020-05-01 12:00:00.000] [example] [info] Second message
+    /// ```
+    pub fn set_show_line_number(&mut self, show_line_number: bool) {
+        self.show_line_number = show_line_number;
+    }
+
+    /// Sets whether the line numbers (see [`set_show_line_number`](#method.set_show_line_number)) start at 1 again
+    /// in the new file when a log file is rotated (see [`set_size_rotation`](#method.set_size_rotation))
+    /// or a new file is started because the date changed. If disabled, they continue from the previous file.
+    /// The default is `true`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reset` - Whether the line numbers start at 1 again in a new file.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), false);
+    ///
+    /// logger.set_show_line_number(true);
+    /// logger.set_size_rotation(10 * 1024 * 1024, 5);
+    /// logger.set_line_number_reset_on_rotation(false);
+    /// ```
+    ///
+    /// This will number the lines of "log.txt" and the rotated files as one sequence.
+    pub fn set_line_number_reset_on_rotation(&mut self, reset: bool) {
+        self.line_number_reset_on_rotation = reset;
+
+        for sink in &mut self.sinks {
+            if let SinkTarget::File(log_file) = &mut sink.target {
+                log_file.lock().set_reset_line_numbers(reset);
+            }
+        }
+    }
+
+    /// Sets the trace id that is added to every message, to correlate messages across services.
+    /// The trace id is written as `[trace=<id>]` after the log level, escaped like the name of the logger.
+    /// Pass `None` to stop adding a trace id.
+    /// A trace id passed to [`log_with_trace_id`](#method.log_with_trace_id) takes precedence over this one.
+    ///
+    /// # Arguments
+    ///
+    /// * `trace_id` - The trace id, or `None` for no trace id.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_trace_id(Some(String::from("abc123")));
+    /// logger.info("Handling request");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [info] [trace=abc123] Handling request
+    pub fn set_trace_id(&mut self, trace_id: Option<String>) {
+        self.trace_id = trace_id;
+    }
+
+    /// Sets the context that is added to every line, like the id of a thread or a request.
+    /// The context is written as `[<context>]` right after the name, and as the key `context` in the JSON format.
+    /// Pass `None` to stop adding a context. Unlike [`set_trace_id`](#method.set_trace_id),
+    /// the context can't be overridden per call, and a logger made with
+    /// [`clone_with_name`](#method.clone_with_name) can have its own context.
+    ///
+    /// # Arguments
+    ///
+    /// * `context` - The context, or `None` for no context.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_context(Some(String::from("worker-1")));
+    /// logger.info("Handling request");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [worker-1] [info] Handling request
+    pub fn set_context(&mut self, context: Option<String>) {
+        self.context = context;
+    }
+
+    /// Returns the name of the logger, as it was passed, without escaping.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let logger = Logger::new_default(String::from("example"));
+    ///
+    /// assert_eq!(logger.name(), "example");
+    /// ```
+    pub fn name(&self) -> &str {
+        &self.unescaped_name
+    }
+
+    /// Renames the logger. The new name is used from the next message on, and is escaped like the name passed to
+    /// [`new`](#method.new). The source, if there is one, is kept (see [`set_source`](#method.set_source)).
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The new name of the logger.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("loader"));
+    ///
+    /// logger.info("Loading assets");
+    /// logger.set_name(String::from("renderer"));
+    /// logger.info("Rendering the first frame");
+    /// ```
+    ///
+    /// This will log the following messages:
+    /// [2020-12-31 23:59:59.999] [loader] [info] Loading assets
+    /// [2020-12-31 23:59:59.999] [renderer] [info] Rendering the first frame
+    pub fn set_name(&mut self, name: String) {
+        self.escaped_name = escape::text(&name);
+        self.unescaped_name = name;
+        self.set_source(self.source.clone());
+    }
+
+    /// Sets the source of the messages, a second label besides the name of the logger,
+    /// for example the component within a subsystem. The source is written after the name, as `[name/source]`,
+    /// and is escaped like the name. Pass `None` to write only the name.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The source, or `None` for no source.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("worker"));
+    ///
+    /// logger.set_source(Some(String::from("image-resize")));
+    /// logger.info("Resized 12 images");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [worker/image-resize] [info] Resized 12 images
+    pub fn set_source(&mut self, source: Option<String>) {
+        self.display_name = match &source {
+            Some(source) => format!("{}/{}", self.escaped_name, escape::text(source)),
+            None => self.escaped_name.clone(),
+        };
+        self.source = source;
+    }
+
+    /// Prints the last lines of the log file to the console, with the colors of their log levels,
+    /// or writes them to the writer set with [`set_console_writer`](#method.set_console_writer).
+    /// The log file is flushed first, so the lines include the buffered ones.
+    /// Lines that can't be parsed (see [`log_record::parse_line`](log_record/fn.parse_line.html)) are printed as they are.
+    /// This does nothing if the logger doesn't write to a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - The maximum number of lines to print.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Trace, String::from("log.txt"), false);
+    ///
+    /// logger.info("This is only written to the file");
+    /// logger.console_only_mirror(10).unwrap();
+    /// ```
+    ///
+    /// This will print the last 10 lines of "log.txt" to the console.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the log file can't be flushed or read.
+    pub fn console_only_mirror(&mut self, lines: usize) -> io::Result<()> {
+        let log_file = self
+            .sinks
+            .iter_mut()
+            .find_map(|sink| match &mut sink.target {
+                SinkTarget::File(log_file) => Some(log_file),
+                _ => None,
+            });
+        let path = match log_file {
+            Some(log_file) => {
+                let mut log_file = log_file.lock();
+                log_file.flush()?;
+                log_file.path().to_string()
+            }
+            None => return Ok(()),
+        };
+
+        let mut last_lines = VecDeque::with_capacity(lines);
+
+        for line in BufReader::new(File::open(path)?).lines() {
+            if last_lines.len() == lines {
+                last_lines.pop_front();
+            }
+
+            if lines > 0 {
+                last_lines.push_back(line?);
+            }
+        }
+
+        let console_lines: Vec<String> = last_lines
+            .into_iter()
+            .map(|line| match log_record::parse_line(&line) {
+                Some(record) => Logger::format_console_line(
+                    &record.timestamp,
+                    &self.get_console_name(&record.logger),
+                    record.level,
+                    self.level_styles[record.level as usize],
+                    MessageOptions {
+                        trace_id: record.trace_id.as_deref(),
+                        ..MessageOptions::default()
+                    },
+                    &record.message,
+                    self.get_color_targets(record.level, MessageOptions::default()),
+                    &self.prefix_message_separator,
+                ),
+                None => line,
+            })
+            .collect();
+
+        if console_lines.is_empty() {
+            return Ok(());
+        }
+
+        Logger::log_to_console(&mut self.console_writer, &console_lines, false)
+    }
+
+    /// Enables or disables trimming a trailing newline from messages.
+    /// If enabled, a single trailing newline (`\n` or `\r\n`) is removed from every message before it is logged,
+    /// so a message read with `read_line` doesn't cause an empty line in the output. This is enabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `trim_trailing_newline` - Whether a trailing newline should be removed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_trim_trailing_newline(false);
+    /// logger.info("This message is followed by an empty line\n");
+    /// ```
+    pub fn set_trim_trailing_newline(&mut self, trim_trailing_newline: bool) {
+        self.trim_trailing_newline = trim_trailing_newline;
+    }
+
+    /// Sets the format of the timestamps to one of the presets.
+    /// This sets both the format and whether the time is in UTC or local time.
+    /// In the compact date mode (see [`set_compact_date`](#method.set_compact_date)),
+    /// the lines in the log file keep using the time without the date.
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The timestamp preset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::timestamp::TimestampPreset;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_timestamp_preset(TimestampPreset::Rfc3339);
+    /// logger.info("This is an info message.");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31T23:59:59.999+01:00] [example] [info] This is an info message.
+    pub fn set_timestamp_preset(&mut self, preset: timestamp::TimestampPreset) {
+        self.timestamp_format = String::from(preset.format_str());
+        self.use_utc = preset.uses_utc();
+    }
+
+    /// Sets the chrono format string of the timestamps, for example `%Y-%m-%dT%H:%M:%S%.6fZ`.
+    /// See the documentation of `chrono::format::strftime` for the specifiers.
+    /// The default is `%F %T%.3f`, like `2020-05-01 12:00:00.000`.
+    /// If the format string is invalid, a warning is printed to stderr and the format isn't changed.
+    /// In the compact date mode (see [`set_compact_date`](#method.set_compact_date)),
+    /// the lines in the log file keep using the time without the date.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The chrono format string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_timestamp_format(String::from("%d/%m/%Y %H:%M:%S%.6f"));
+    /// logger.info("This is an info message.");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [31/12/2020 23:59:59.999999] [example] [info] This is an info message.
+    pub fn set_timestamp_format(&mut self, format: String) {
+        if timestamp::is_valid_format(&format) {
+            self.timestamp_format = format;
+        } else {
+            eprintln!(
+                "Warning: invalid timestamp format {:?}, keeping {:?}",
+                format, self.timestamp_format
+            );
+        }
+    }
+
+    /// Sets whether the timestamps are in UTC instead of local time. The change applies to the next message.
+    /// The default is local time. The format isn't changed, so use a format that shows the time zone,
+    /// like the `Z` of `%Y-%m-%dT%H:%M:%S%.3fZ` (see [`set_timestamp_format`](#method.set_timestamp_format)),
+    /// or a preset that is in UTC (see [`set_timestamp_preset`](#method.set_timestamp_preset)).
+    ///
+    /// # Arguments
+    ///
+    /// * `use_utc` - Whether the timestamps are in UTC.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_utc(true);
+    /// logger.info("This is an info message.");
+    /// ```
+    ///
+    /// This will log the following message, with the time in UTC:
+    /// [2020-12-31 22:59:59.999] [example] [info] This is an info message.
+    pub fn set_utc(&mut self, use_utc: bool) {
+        self.use_utc = use_utc;
+    }
+
+    /// Sets how the timestamps are shown. The default is [`TimestampStyle::Wall`](timestamp::TimestampStyle::Wall),
+    /// the wall clock time in the timestamp format (see [`set_timestamp_format`](#method.set_timestamp_format)).
+    /// With [`TimestampStyle::Relative`](timestamp::TimestampStyle::Relative), the timestamps are the seconds
+    /// since the logger was created, like `+0.123s`, and the compact date mode
+    /// (see [`set_compact_date`](#method.set_compact_date)) has no effect.
+    /// JSON lines always have the wall clock time.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - The timestamp style.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::timestamp::TimestampStyle;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_timestamp_style(TimestampStyle::Relative);
+    /// logger.info("This is an info message.");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [+0.001s] [example] [info] This is an info message.
+    pub fn set_timestamp_style(&mut self, style: timestamp::TimestampStyle) {
+        self.timestamp_style = style;
+    }
+
+    /// Sets whether a legend of the colors of the log levels is printed to the console,
+    /// once, right before the first message that is written to the console.
+    /// The legend is never written to files. The default is `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `print_legend` - Whether the legend should be printed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_print_legend(true);
+    /// logger.info("This is an info message.");
+    /// ```
+    ///
+    /// This will print the following lines, with every level in its color:
+    /// Legend: trace=dim debug=dim underline info=plain warning=yellow error=red critical=red bold
+    /// [2020-12-31 23:59:59.999] [example] [info] This is an info message.
+    pub fn set_print_legend(&mut self, print_legend: bool) {
+        self.print_legend = print_legend;
+    }
+
+    /// Sets what is written between the prefix of a line (the timestamp, name, log level and tags) and the message,
+    /// in files and on the console. The default is a single space.
+    /// Note that [`log_record::parse_line`](log_record/fn.parse_line.html) only parses lines with the default separator.
+    ///
+    /// # Arguments
+    ///
+    /// * `separator` - The separator, for example `": "` or `"\t"`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_prefix_message_separator(": ");
+    /// logger.info("This is an info message.");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [info]: This is an info message.
+    pub fn set_prefix_message_separator(&mut self, separator: &str) {
+        self.prefix_message_separator = separator.to_string();
+    }
+
+    /// Sets whether messages with the log level "Warning", "Error" or "Critical" are written to stderr instead of stdout,
+    /// so they stay visible when stdout is redirected, for example with `> /dev/null`.
+    /// The default is `true`; with `false`, all messages are written to stdout.
+    ///
+    /// # Arguments
+    ///
+    /// * `split_streams` - Whether warnings and errors are written to stderr.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_split_streams(false);
+    /// logger.error("This error message is written to stdout.");
+    /// ```
+    pub fn set_split_streams(&mut self, split_streams: bool) {
+        self.split_streams = split_streams;
+    }
+
+    /// Sets a writer the console output is written to instead of stdout and stderr, for example a buffer in tests.
+    /// All console output goes to the writer, whatever [`set_split_streams`](#method.set_split_streams) is set to.
+    /// Like stdout, the writer gets colored output unless colors are disabled with [`set_color`](#method.set_color).
+    /// Use [`reset_console_writer`](#method.reset_console_writer) to write to stdout and stderr again.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer to write the console output to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::io::{self, Write};
+    /// use std::sync::{Arc, Mutex};
+    /// use ultimate_logger::Logger;
+    ///
+    /// #[derive(Clone)]
+    /// struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+    ///
+    /// impl Write for SharedBuffer {
+    ///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    ///         self.0.lock().unwrap().extend_from_slice(buf);
+    ///         Ok(buf.len())
+    ///     }
+    ///
+    ///     fn flush(&mut self) -> io::Result<()> {
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let buffer = SharedBuffer(Arc::new(Mutex::new(Vec::new())));
+    /// let mut logger = Logger::new_default(String::from("example"));
+    /// logger.set_color(false);
+    /// logger.set_console_writer(Box::new(buffer.clone()));
+    ///
+    /// logger.info("This is an info message");
+    ///
+    /// let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    /// assert!(output.ends_with("[example] [info] This is an info message\n"));
+    /// ```
+    pub fn set_console_writer(&mut self, writer: Box<dyn io::Write + Send>) {
+        self.console_writer = Some(writer);
+    }
+
+    /// Writes the console output to stdout and stderr again after [`set_console_writer`](#method.set_console_writer).
+    pub fn reset_console_writer(&mut self) {
+        self.console_writer = None;
+    }
+
+    fn console_to_stderr(&self, level: log_level::LogLevel) -> bool {
+        self.split_streams && level >= log_level::LogLevel::Warning
+    }
+
+    /// Sets whether the console output of this logger is colored.
+    /// With [`ColorChoice::Auto`](color::ColorChoice::Auto), which is the default, the `colored` crate decides,
+    /// based on the environment: the output isn't colored if stdout isn't a terminal, for example when it's piped to a file,
+    /// or if the `NO_COLOR` environment variable is set.
+    /// With [`ColorChoice::Never`](color::ColorChoice::Never), the console output is never colored,
+    /// whatever the environment, which makes it predictable, for example in tests.
+    /// With [`ColorChoice::Always`](color::ColorChoice::Always), the console output is colored even if stdout isn't a terminal.
+    /// The choice only applies to this logger, not to other loggers or other users of `colored` in the process.
+    ///
+    /// # Arguments
+    ///
+    /// * `color_choice` - Whether the console output should be colored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::color::ColorChoice;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_color_choice(ColorChoice::Never);
+    /// logger.error("This error message isn't red.");
+    /// ```
+    pub fn set_color_choice(&mut self, color_choice: color::ColorChoice) {
+        self.color_choice = color_choice;
+    }
+
+    /// Enables or disables colors on the console: `true` always colors the output, even if stdout isn't a terminal,
+    /// and `false` never colors it. Without calling this, the output is colored only if stdout is a terminal,
+    /// so piping the output to a file or another program gives plain text.
+    /// See [`set_color_choice`](#method.set_color_choice) for more information.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the console output is colored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_color(false);
+    /// logger.error("This error message isn't red.");
+    /// ```
+    pub fn set_color(&mut self, enabled: bool) {
+        self.set_color_choice(if enabled {
+            color::ColorChoice::Always
+        } else {
+            color::ColorChoice::Never
+        });
+    }
+
+    fn use_color(&self) -> bool {
+        match self.color_choice {
+            color::ColorChoice::Auto => colored::control::SHOULD_COLORIZE.should_colorize(),
+            color::ColorChoice::Always => true,
+            color::ColorChoice::Never => false,
+        }
+    }
+
+    /// Sets which parts of the console lines with the specified log level are colored:
+    /// the log level, the message, both or neither. By default both are colored.
+    /// A color passed to [`log_colored`](#method.log_colored) always colors the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level.
+    /// * `color_targets` - The parts that are colored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::color::ColorTargets;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_color_targets(LogLevel::Debug, ColorTargets::Level);
+    /// logger.set_color_targets(LogLevel::Info, ColorTargets::Level);
+    /// ```
+    ///
+    /// This will only color the log level of debug and info messages, and the whole line of the other messages.
+    pub fn set_color_targets(
+        &mut self,
+        level: log_level::LogLevel,
+        color_targets: color::ColorTargets,
+    ) {
+        self.color_targets[level as usize] = color_targets;
+    }
+
+    /// Sets the styles of all log levels on the console to those of a preset.
+    /// Use [`set_level_style`](#method.set_level_style) afterwards to change single log levels.
+    /// The default is `Theme::Default`.
+    ///
+    /// # Arguments
+    ///
+    /// * `theme` - The preset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::color::Theme;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_theme(Theme::HighContrast);
+    /// logger.warning("This warning is bright yellow and bold");
+    /// ```
+    pub fn set_theme(&mut self, theme: color::Theme) {
+        self.level_styles = theme.styles();
+    }
+
+    /// Sets the styles of the console lines of all log levels at once. Both the log level and the message use the style,
+    /// depending on [`set_color_targets`](#method.set_color_targets).
+    ///
+    /// # Arguments
+    ///
+    /// * `scheme` - The styles of all log levels.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use colored::Color;
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::color::ColorScheme;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Trace);
+    ///
+    /// logger.set_color_scheme(
+    ///     ColorScheme::default()
+    ///         .with_color(LogLevel::Trace, Color::Cyan)
+    ///         .with_color(LogLevel::Debug, Color::Blue),
+    /// );
+    /// logger.trace("This trace message is cyan instead of dimmed");
+    /// ```
+    pub fn set_color_scheme(&mut self, scheme: color::ColorScheme) {
+        self.level_styles = scheme.styles();
+    }
+
+    /// Returns the styles of the console lines of all log levels, to change some of them with
+    /// [`set_color_scheme`](#method.set_color_scheme).
+    pub fn color_scheme(&self) -> color::ColorScheme {
+        color::ColorScheme::from(self.level_styles)
+    }
+
+    /// Sets the style of the console lines with the specified log level.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level.
+    /// * `style` - The style.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use colored::Color;
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::color::{LevelStyle, Theme};
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_theme(Theme::Monochrome);
+    /// logger.set_level_style(
+    ///     LogLevel::Critical,
+    ///     LevelStyle {
+    ///         color: Some(Color::Red),
+    ///         bold: true,
+    ///         ..LevelStyle::default()
+    ///     },
+    /// );
+    /// ```
+    ///
+    /// This will color only critical messages, in red and bold.
+    pub fn set_level_style(&mut self, level: log_level::LogLevel, style: color::LevelStyle) {
+        self.level_styles[level as usize] = style;
+    }
+
+    /// Sets whether the name of the logger is colored on the console, with a color picked from a fixed palette
+    /// based on a hash of the name. The same name always gets the same color, also across runs,
+    /// so the lines of different loggers that share the console are easy to tell apart.
+    /// Files are unaffected. The default is `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `colorize_name_by_hash` - Whether the name should be colored.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut database = Logger::new_default(String::from("database"));
+    /// let mut server = Logger::new_default(String::from("server"));
+    ///
+    /// database.set_colorize_name_by_hash(true);
+    /// server.set_colorize_name_by_hash(true);
+    ///
+    /// database.info("Connected");
+    /// server.info("Listening on port 8080");
+    /// ```
+    pub fn set_colorize_name_by_hash(&mut self, colorize_name_by_hash: bool) {
+        self.colorize_name_by_hash = colorize_name_by_hash;
+    }
+
+    fn get_console_name<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        if self.colorize_name_by_hash && self.use_color() {
+            Cow::Owned(color::paint(&name.color(color::name_color(name))))
+        } else {
+            Cow::Borrowed(name)
+        }
+    }
+
+    fn get_color_targets(
+        &self,
+        level: log_level::LogLevel,
+        options: MessageOptions,
+    ) -> color::ColorTargets {
+        if !self.use_color() {
+            return color::ColorTargets::Neither;
+        }
+
+        let color_targets = self.color_targets[level as usize];
+
+        if options.color.is_some() {
+            color_targets.with_message()
+        } else {
+            color_targets
+        }
+    }
+
+    fn get_legend(use_color: bool, level_styles: &[color::LevelStyle; 6]) -> String {
+        let entries: Vec<String> = log_level::LogLevel::ALL
+            .iter()
+            .map(|level| {
+                let style = level_styles[*level as usize];
+                let entry = format!("{}={}", level, style.description());
+
+                if use_color {
+                    color::paint(&style.apply(&entry))
+                } else {
+                    entry
+                }
+            })
+            .collect();
+
+        format!("Legend: {}", entries.join(" "))
+    }
+
+    fn format_time(&self, now: &DateTime<Local>, format: &str) -> String {
+        if self.use_utc {
+            now.with_timezone(&Utc).format(format).to_string()
+        } else {
+            now.format(format).to_string()
+        }
+    }
+
+    fn get_date_time(&self, now: &DateTime<Local>) -> String {
+        match self.timestamp_style {
+            timestamp::TimestampStyle::Wall => self.format_time(now, &self.timestamp_format),
+            timestamp::TimestampStyle::Relative => {
+                // Measured with the monotonic clock, minus how long ago the time was, for times given to `log_at`.
+                let ago = offset::Local::now().signed_duration_since(*now);
+                let seconds = self.created_at.elapsed().as_secs_f64()
+                    - ago.num_microseconds().unwrap_or(i64::MAX) as f64 / 1_000_000.0;
+
+                if seconds < 0.0 {
+                    format!("-{:.3}s", -seconds)
+                } else {
+                    format!("+{:.3}s", seconds)
+                }
+            }
+        }
+    }
+
+    fn get_colored_level_name(
+        style: color::LevelStyle,
+        level: log_level::LogLevel,
+    ) -> ColoredString {
+        style.apply(level.as_str())
+    }
+
+    fn get_trace_id_tag(trace_id: Option<&str>) -> String {
+        match trace_id {
+            Some(trace_id) => format!(" [trace={}]", escape::text(trace_id)),
+            None => String::new(),
+        }
+    }
+
+    fn get_context_tag(options: MessageOptions) -> String {
+        match options.context {
+            Some(context) => format!(" [{}]", escape::text(context)),
+            None => String::new(),
+        }
+    }
+
+    fn get_tags(options: MessageOptions) -> String {
+        match options.scope {
+            Some(scope) => format!(
+                "{} [{}]",
+                Logger::get_trace_id_tag(options.trace_id),
+                escape::text(scope)
+            ),
+            None => Logger::get_trace_id_tag(options.trace_id),
+        }
+    }
+
+    fn get_colored_message(style: color::LevelStyle, message: &str) -> ColoredString {
+        style.apply(message)
+    }
+
+    fn log_to_file<L: AsRef<[u8]>>(
+        log_file: &mut LogFile,
+        rate_limiter: &mut ByteRateLimiter,
+        show_line_number: bool,
+        date_marker: Option<&str>,
+        lines: &[L],
+        pipe_atomic_policy: PipeAtomicPolicy,
+        discarded_pipe_writes: &mut u64,
+    ) -> io::Result<()> {
+        let mut to_write =
+            Logger::format_file_lines(log_file, show_line_number, date_marker, lines);
+
+        if log_file.is_pipe()
+            && pipe_atomic_policy == PipeAtomicPolicy::Discard
+            && to_write.len() > log_file::PIPE_BUF
+        {
+            *discarded_pipe_writes += 1;
+            return Ok(());
+        }
+
+        if rate_limiter.allow(to_write.len()) {
+            // A rotation or date switch starts a new file, so the line numbers and the date
+            // marker have to be formatted again for it.
+            let lines_written = log_file.lines_written();
+            let last_date = log_file.last_date().map(str::to_string);
+            let prepared = log_file.prepare_write(to_write.len());
+            if log_file.lines_written() != lines_written
+                || log_file.last_date() != last_date.as_deref()
+            {
+                to_write =
+                    Logger::format_file_lines(log_file, show_line_number, date_marker, lines);
+            }
+
+            log_file.write_prepared(&to_write)?;
+
+            if let Some(date) = date_marker {
+                log_file.set_last_date(Some(date.to_string()));
+            }
+
+            prepared?;
+        }
+
+        Ok(())
+    }
+
+    fn format_file_lines<L: AsRef<[u8]>>(
+        log_file: &LogFile,
+        show_line_number: bool,
+        date_marker: Option<&str>,
+        lines: &[L],
+    ) -> Vec<u8> {
+        let mut to_write = Vec::new();
+        let mut line_number = log_file.lines_written() + 1;
+        let mut push_line = |to_write: &mut Vec<u8>, line: &[u8]| {
+            if show_line_number {
+                to_write.extend_from_slice(format!("{}: ", line_number).as_bytes());
+                line_number += 1;
+            }
+
+            to_write.extend_from_slice(line);
+            to_write.push(b'\n');
+        };
+
+        if let Some(date) = date_marker {
+            if log_file.last_date() != Some(date) {
+                push_line(&mut to_write, format!("----- {} -----", date).as_bytes());
+            }
+        }
+
+        for line in lines {
+            push_line(&mut to_write, line.as_ref());
+        }
+
+        to_write
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn log_binary_to_file(
+        log_file: &mut LogFile,
+        rate_limiter: &mut ByteRateLimiter,
+        nanos: u64,
+        name: &str,
+        level: log_level::LogLevel,
+        trace_id: Option<&str>,
+        lines: &[&str],
+        pipe_atomic_policy: PipeAtomicPolicy,
+        discarded_pipe_writes: &mut u64,
+    ) -> io::Result<()> {
+        let (mut to_write, mut needs_definition) =
+            Logger::encode_binary(log_file, nanos, name, level, trace_id, lines);
+
+        if log_file.is_pipe()
+            && pipe_atomic_policy == PipeAtomicPolicy::Discard
+            && to_write.len() > log_file::PIPE_BUF
+        {
+            *discarded_pipe_writes += 1;
+            return Ok(());
+        }
+
+        if rate_limiter.allow(to_write.len()) {
+            // A rotation or date switch starts a new file, which needs the header and the name definition again.
+            let needed_header = log_file.needs_binary_header();
+            let name_index = log_file.binary_name_index(name);
+            let prepared = log_file.prepare_write(to_write.len());
+            if log_file.needs_binary_header() != needed_header
+                || log_file.binary_name_index(name) != name_index
+            {
+                (to_write, needs_definition) =
+                    Logger::encode_binary(log_file, nanos, name, level, trace_id, lines);
+            }
+
+            log_file.write_prepared_bytes(&to_write)?;
+
+            if needs_definition {
+                log_file.define_binary_name(name);
+            }
+
+            prepared?;
+        }
+
+        Ok(())
+    }
+
+    fn encode_binary(
+        log_file: &LogFile,
+        nanos: u64,
+        name: &str,
+        level: log_level::LogLevel,
+        trace_id: Option<&str>,
+        lines: &[&str],
+    ) -> (Vec<u8>, bool) {
+        let mut to_write = Vec::new();
+
+        if log_file.needs_binary_header() {
+            to_write.extend_from_slice(binary::MAGIC);
+        }
+
+        let (name_index, needs_definition) = log_file.binary_name_index(name);
+
+        if needs_definition {
+            binary::encode_name(&mut to_write, name_index, name);
+        }
+
+        for line in lines {
+            binary::encode_record(&mut to_write, nanos, level, name_index, trace_id, line);
+        }
+
+        (to_write, needs_definition)
+    }
+
+    fn log_to_console(
+        console_writer: &mut Option<Box<dyn io::Write + Send>>,
+        lines: &[String],
+        to_stderr: bool,
+    ) -> io::Result<()> {
+        match console_writer {
+            Some(writer) => writeln!(writer, "{}", lines.join("\n")),
+            None if to_stderr => {
+                eprintln!("{}", lines.join("\n"));
+                Ok(())
+            }
+            None => {
+                println!("{}", lines.join("\n"));
+                Ok(())
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn format_console_line(
+        date_time: &str,
+        name: &str,
+        level: log_level::LogLevel,
+        style: color::LevelStyle,
+        options: MessageOptions,
+        message: &str,
+        color_targets: color::ColorTargets,
+        separator: &str,
+    ) -> String {
+        let level_name = if color_targets.colors_level() {
+            color::paint(&Logger::get_colored_level_name(style, level))
+        } else {
+            level.to_string()
+        };
+        let message = if !color_targets.colors_message() {
+            message.to_string()
+        } else {
+            match options.color {
+                Some(color) => color::paint(&message.color(color)),
+                None => color::paint(&Logger::get_colored_message(style, message)),
+            }
+        };
+
+        format!(
+            "[{}] [{}]{} [{}]{}{}{}",
+            date_time,
+            name,
+            Logger::get_context_tag(options),
+            level_name,
+            Logger::get_tags(options),
+            separator,
+            message
+        )
+    }
+
+    fn format_json_line(
+        &self,
+        now: &DateTime<Local>,
+        level: log_level::LogLevel,
+        lines: &[&str],
+        fields: &[(&str, &str)],
+        options: MessageOptions,
+    ) -> String {
+        let preset = if self.use_utc {
+            timestamp::TimestampPreset::Iso8601
+        } else {
+            timestamp::TimestampPreset::Rfc3339
+        };
+        let timestamp = self.format_time(now, preset.format_str());
+        let scopes: &[String] = if options.scope.is_some() {
+            &self.scopes
+        } else {
+            &[]
+        };
+
+        json::JsonRecord {
+            timestamp: &timestamp,
+            logger: &self.unescaped_name,
+            source: self.source.as_deref(),
+            level,
+            trace_id: options.trace_id,
+            context: options.context,
+            scopes,
+            fields,
+            payload: options.payload,
+            message: &lines.join("\n"),
+        }
+        .to_line()
+    }
+
+    /// Returns the fields as space-separated `key=value` pairs with a leading space,
+    /// with values that contain spaces or quotes quoted, or an empty string if there are no fields.
+    fn format_fields(fields: &[(&str, &str)]) -> String {
+        fields
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    " {}={}",
+                    escape::logfmt_key(key),
+                    escape::logfmt_value(value)
+                )
+            })
+            .collect()
+    }
+
+    /// Writes the lines to every sink that accepts the log level.
+    /// If writing to a file fails, the other sinks are still written to, and the first error is returned.
+    fn write_lines(
+        &mut self,
+        level: log_level::LogLevel,
+        lines: &[&str],
+        options: MessageOptions,
+    ) -> io::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let max_field_value_len = self.max_field_value_len;
+        let truncated_fields: Vec<(&str, Cow<str>)> = options
+            .fields
+            .iter()
+            .map(|(key, value)| {
+                (
+                    *key,
+                    Logger::truncate_field_value(value, max_field_value_len),
+                )
+            })
+            .collect();
+        let fields: Vec<(&str, &str)> = truncated_fields
+            .iter()
+            .map(|(key, value)| (*key, value.as_ref()))
+            .collect();
+
+        // The lines without the fields, for the sinks that store the fields separately.
+        let message_lines = lines;
+        let fields_text = Logger::format_fields(&fields);
+        let lines_with_fields: Vec<Cow<str>> = lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                if i + 1 == lines.len() && !fields_text.is_empty() {
+                    Cow::Owned(format!("{}{}", line, fields_text))
+                } else {
+                    Cow::Borrowed(*line)
+                }
+            })
+            .collect();
+        let lines: Vec<&str> = lines_with_fields.iter().map(|line| line.as_ref()).collect();
+        let lines = &lines[..];
+
+        let now = options.time.unwrap_or_else(offset::Local::now);
+        let date_time = self.get_date_time(&now);
+        let compact_date =
+            self.compact_date && self.timestamp_style == timestamp::TimestampStyle::Wall;
+        let file_date_time = if compact_date {
+            self.format_time(&now, "%T%.3f")
+        } else {
+            date_time.clone()
+        };
+        let date_marker = if compact_date {
+            Some(self.format_time(&now, "%F"))
+        } else {
+            None
+        };
+
+        let file_prefix = format!(
+            "[{}] [{}]{} [{}]{}{}",
+            file_date_time,
+            self.display_name,
+            Logger::get_context_tag(options),
+            level,
+            Logger::get_tags(options),
+            self.prefix_message_separator
+        );
+        let file_lines: Vec<Vec<u8>> = match options.raw {
+            Some(raw) => vec![[file_prefix.as_bytes(), raw].concat()],
+            None => lines
+                .iter()
+                .map(|line| format!("{}{}", file_prefix, line).into_bytes())
+                .collect(),
+        };
+        let color_targets = self.get_color_targets(level, options);
+        let console_name = self.get_console_name(&self.display_name);
+        let console_lines: Vec<String> = lines
+            .iter()
+            .map(|line| {
+                Logger::format_console_line(
+                    &date_time,
+                    &console_name,
+                    level,
+                    self.level_styles[level as usize],
+                    options,
+                    line,
+                    color_targets,
+                    &self.prefix_message_separator,
+                )
+            })
+            .collect();
+
+        let json_lines: Vec<String> = if self.writes_json() {
+            vec![self.format_json_line(&now, level, message_lines, &fields, options)]
+        } else {
+            Vec::new()
+        };
+
+        if self.print_legend
+            && self.output_format != OutputFormat::Json
+            && !self.legend_printed
+            && self
+                .sinks
+                .iter()
+                .any(|sink| matches!(sink.target, SinkTarget::Console) && sink.accepts(level))
+        {
+            let legend = vec![Logger::get_legend(self.use_color(), &self.level_styles)];
+            Logger::report_write_error(Logger::log_to_console(
+                &mut self.console_writer,
+                &legend,
+                false,
+            ));
+            self.legend_printed = true;
+        }
+
+        if !self.captures.is_empty() {
+            let record = log_record::LogRecord {
+                timestamp: date_time.clone(),
+                logger: self.display_name.clone(),
+                level,
+                trace_id: options.trace_id.map(String::from),
+                message: lines.join("\n"),
+            };
+
+            for capture in &mut self.captures {
+                capture.push(record.clone());
+            }
+        }
+
+        if let Some(formatted) = &mut self.formatted {
+            let plain_lines: Vec<String> = lines
+                .iter()
+                .map(|line| {
+                    Logger::format_console_line(
+                        &date_time,
+                        &self.display_name,
+                        level,
+                        self.level_styles[level as usize],
+                        options,
+                        line,
+                        color::ColorTargets::Neither,
+                        &self.prefix_message_separator,
+                    )
+                })
+                .collect();
+            formatted.push(plain_lines.join("\n"));
+        }
+
+        #[cfg(feature = "sqlite")]
+        let fields_json = if fields.is_empty() {
+            None
+        } else {
+            Some(json::fields_object(&fields))
+        };
+
+        let to_stderr = self.console_to_stderr(level);
+        let mut result = Ok(());
+
+        for sink in &mut self.sinks {
+            if !sink.accepts(level) {
+                continue;
+            }
+
+            let mut sink_result = match &mut sink.target {
+                SinkTarget::Console if self.output_format == OutputFormat::Json => {
+                    Logger::log_to_console(&mut self.console_writer, &json_lines, to_stderr)
+                }
+                SinkTarget::Console => {
+                    Logger::log_to_console(&mut self.console_writer, &console_lines, to_stderr)
+                }
+                SinkTarget::File(log_file) | SinkTarget::Writer(log_file)
+                    if self.output_format == OutputFormat::Json =>
+                {
+                    Logger::log_to_file(
+                        &mut log_file.lock(),
+                        &mut self.file_rate_limiter,
+                        false,
+                        None,
+                        &json_lines,
+                        self.pipe_atomic_policy,
+                        &mut self.discarded_pipe_writes,
+                    )
+                }
+                SinkTarget::File(log_file) | SinkTarget::Writer(log_file)
+                    if self.output_format == OutputFormat::Dual =>
+                {
+                    let mut log_file = log_file.lock();
+                    let text_result = if level >= self.dual_text_min_level {
+                        Logger::log_to_file(
+                            &mut log_file,
+                            &mut self.file_rate_limiter,
+                            self.show_line_number,
+                            date_marker.as_deref(),
+                            &file_lines,
+                            self.pipe_atomic_policy,
+                            &mut self.discarded_pipe_writes,
+                        )
+                    } else {
+                        Ok(())
+                    };
+                    let json_result = if level >= self.dual_json_min_level {
+                        Logger::log_to_file(
+                            &mut log_file,
+                            &mut self.file_rate_limiter,
+                            false,
+                            None,
+                            &json_lines,
+                            self.pipe_atomic_policy,
+                            &mut self.discarded_pipe_writes,
+                        )
+                    } else {
+                        Ok(())
+                    };
+
+                    text_result.and(json_result)
+                }
+                SinkTarget::File(log_file) | SinkTarget::Writer(log_file)
+                    if self.output_format == OutputFormat::Binary =>
+                {
+                    Logger::log_binary_to_file(
+                        &mut log_file.lock(),
+                        &mut self.file_rate_limiter,
+                        binary::timestamp_nanos(&now),
+                        &self.display_name,
+                        level,
+                        options.trace_id,
+                        lines,
+                        self.pipe_atomic_policy,
+                        &mut self.discarded_pipe_writes,
+                    )
+                }
+                SinkTarget::File(log_file) | SinkTarget::Writer(log_file) => Logger::log_to_file(
+                    &mut log_file.lock(),
+                    &mut self.file_rate_limiter,
+                    self.show_line_number,
+                    date_marker.as_deref(),
+                    &file_lines,
+                    self.pipe_atomic_policy,
+                    &mut self.discarded_pipe_writes,
+                ),
+                #[cfg(all(windows, feature = "eventlog"))]
+                SinkTarget::EventLog(event_log) => {
+                    let message = lines
+                        .iter()
+                        .map(|line| {
+                            format!(
+                                "[{}]{}{} {}",
+                                self.display_name,
+                                Logger::get_context_tag(options),
+                                Logger::get_tags(options),
+                                line
+                            )
+                        })
+                        .collect::<Vec<String>>()
+                        .join("\n");
+
+                    if let Err(e) = event_log.report(level, &message) {
+                        eprintln!("Error writing to the Windows Event Log: {}", e);
+                    }
+
+                    Ok(())
+                }
+                #[cfg(feature = "otel")]
+                SinkTarget::OpenTelemetry(otel_sink) => {
+                    otel_sink.emit(
+                        level,
+                        &self.display_name,
+                        options.trace_id,
+                        &fields,
+                        &message_lines.join("\n"),
+                    );
+                    Ok(())
+                }
+                #[cfg(feature = "sqlite")]
+                SinkTarget::Sqlite(sqlite_table) => {
+                    if let Err(e) = sqlite_table.insert(
+                        &date_time,
+                        &self.display_name,
+                        level,
+                        options.trace_id,
+                        &message_lines.join("\n"),
+                        fields_json.as_deref(),
+                    ) {
+                        eprintln!(
+                            "Error writing to the SQLite table: {}\nTable was: {}",
+                            e,
+                            sqlite_table.table()
+                        );
+                    }
+
+                    Ok(())
+                }
+            };
+
+            if self.flush_each || (level == log_level::LogLevel::Critical && self.flush_on_critical)
+            {
+                if let SinkTarget::File(log_file) | SinkTarget::Writer(log_file) = &mut sink.target
+                {
+                    sink_result = sink_result.and_then(|_| log_file.lock().flush());
+                }
+            }
+
+            if result.is_ok() {
+                result = sink_result;
+            }
+        }
+
+        result
+    }
+
+    fn report_write_error(result: io::Result<()>) {
+        if let Err(e) = result {
+            eprintln!("{}", e);
+        }
+    }
+
+    fn log_lines(
+        &mut self,
+        level: log_level::LogLevel,
+        lines: &[&str],
+        options: MessageOptions,
+    ) -> bool {
+        match self.try_log_lines(level, lines, options) {
+            Ok(logged) => logged,
+            Err(e) => {
+                eprintln!("{}", e);
+                true
+            }
+        }
+    }
+
+    fn try_log_lines(
+        &mut self,
+        level: log_level::LogLevel,
+        lines: &[&str],
+        options: MessageOptions,
+    ) -> io::Result<bool> {
+        if !self.would_log(level) {
+            self.suppressed_count += 1;
+            return Ok(false);
+        }
+
+        if let Some(message_filter) = &self.message_filter {
+            if !lines.iter().any(|line| message_filter.matches(line)) {
+                self.suppressed_count += 1;
+                return Ok(false);
+            }
+        }
+
+        let trimmed_lines: Vec<&str>;
+        let lines = if self.trim_trailing_newline {
+            trimmed_lines = lines
+                .iter()
+                .map(|line| Logger::trim_trailing_newline(line))
+                .collect();
+            &trimmed_lines
+        } else {
+            lines
+        };
+
+        let context_trace_id = self.trace_id.clone();
+        let context = self.context.clone();
+        let scope = if self.show_scope && !self.scopes.is_empty() {
+            Some(self.scopes.join(" > "))
+        } else {
+            None
+        };
+        let options = MessageOptions {
+            trace_id: options.trace_id.or(context_trace_id.as_deref()),
+            context: context.as_deref(),
+            scope: scope.as_deref(),
+            ..options
+        };
+
+        if let Some(window) = self.dedup.window() {
+            let now = Instant::now();
+            let mut expired = self.dedup.expire(now);
+            let (is_new, evicted) = self.dedup.check(level, &lines.join("\n"), now);
+            expired.extend(evicted);
+
+            if self.dedup_summary {
+                for expired in expired {
+                    let result = self.write_lines(
+                        expired.level,
+                        &[&format!(
+                            "The following message was repeated {} more times within {:?}: {}",
+                            expired.suppressed, window, expired.message
+                        )],
+                        MessageOptions::default(),
+                    );
+                    Logger::report_write_error(result);
+                }
+            }
+
+            if !is_new {
+                self.suppressed_count += 1;
+                return Ok(false);
+            }
+        }
+
+        if let Some(interval) = self.burst.interval() {
+            let now = Instant::now();
+
+            for summary in self.burst.summarize(now) {
+                let result = self.write_lines(
+                    summary.level,
+                    &[&format!(
+                        "The following message occurred {} more times in the last {:?}: {}",
+                        summary.suppressed, interval, summary.message
+                    )],
+                    MessageOptions::default(),
+                );
+                Logger::report_write_error(result);
+            }
+
+            if !self.burst.check(level, &lines.join("\n"), now) {
+                self.suppressed_count += 1;
+                return Ok(false);
+            }
+        }
+
+        self.message_counts[level as usize] += 1;
+        self.last_message_at = Instant::now();
+
+        if let Some(hook) = &mut self.hook {
+            let message = lines.join("\n");
+
+            if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| hook(level, &message)))
+                .is_err()
+            {
+                eprintln!("The hook of the logger panicked, the message is logged anyway");
+            }
+        }
+
+        self.write_lines(level, lines, options)?;
+
+        Ok(true)
+    }
+
+    fn trim_trailing_newline(line: &str) -> &str {
+        line.strip_suffix("\r\n")
+            .or_else(|| line.strip_suffix('\n'))
+            .unwrap_or(line)
+    }
+
+    /// Truncates a field value to at most `max_len` bytes on a character boundary,
+    /// followed by an ellipsis and the original length.
+    fn truncate_field_value(value: &str, max_len: Option<usize>) -> Cow<'_, str> {
+        let max_len = match max_len {
+            Some(max_len) if value.len() > max_len => max_len,
+            _ => return Cow::Borrowed(value),
+        };
+
+        let mut end = max_len;
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        Cow::Owned(format!("{}… ({} bytes)", &value[..end], value.len()))
+    }
+
+    fn format_table(
+        title: &str,
+        rows: &[(&str, &str)],
+        max_value_len: Option<usize>,
+    ) -> Vec<String> {
+        let key_width = rows
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = vec![title.to_string()];
+        lines.extend(rows.iter().map(|(key, value)| {
+            format!(
+                "  {:<width$} : {}",
+                key,
+                Logger::truncate_field_value(value, max_value_len),
+                width = key_width
+            )
+        }));
+
+        lines
+    }
+
+    /// Logs a message with the specified log level.
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `message` - The message.
+    ///
+    /// # Examples
+    ///
+    /// ## Default log level
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.log(LogLevel::Trace, "This is a trace message.");
+    /// logger.log(LogLevel::Debug, "This is a debug message.");
+    /// logger.log(LogLevel::Info, "This is an info message.");
+    /// ```
+    ///
+    /// This will log the following messages:
+    /// [2020-12-31 23:59:59.999] [example] [trace] This is a trace message.
+    /// [2020-12-31 23:59:59.999] [example] [debug] This is a debug message.
+    /// [2020-12-31 23:59:59.999] [example] [info] This is an info message.
+    ///
+    /// All messages will be logged, because the minimum log level is the default "Trace".
+    ///
+    /// ## Custom log level
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Debug);
+    ///
+    /// logger.log(LogLevel::Trace, "This is a trace message.");
+    /// logger.log(LogLevel::Debug, "This is a debug message.");
+    /// logger.log(LogLevel::Info, "This is an info message.");
+    /// ```
+    ///
+    /// This will log the following messages:
+    /// [2020-12-31 23:59:59.999] [example] [debug] This is a debug message.
+    /// [2020-12-31 23:59:59.999] [example] [info] This is an info message.
+    ///
+    /// Only the messages with the log level "Debug" and "Info" will be logged, because the minimum log level is "Debug".
+    ///
+    /// If the message can't be written to a file, for example because the disk is full, the error is printed to stderr.
+    /// Use [`try_log`](#method.try_log) to handle the error instead.
+    pub fn log(&mut self, level: log_level::LogLevel, message: &str) -> bool {
+        self.log_lines(level, &[message], MessageOptions::default())
+    }
+
+    /// Logs a message with the specified log level, like [`log`](#method.log),
+    /// but returns the error instead of printing it to stderr if the message can't be written to a file.
+    /// The message is still written to the other sinks.
+    /// Returns `Ok(true)` if the message was logged and `Ok(false)` if the message wasn't logged because the log level was too low.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `message` - The message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_to_file(String::from("example"), LogLevel::Info, String::from("log.txt"), false);
+    ///
+    /// if let Err(e) = logger.try_log(LogLevel::Info, "This is an info message") {
+    ///     eprintln!("Logging failed, retrying later: {}", e);
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message can't be written to one of the files of the logger.
+    /// The error message contains the path of the file.
+    pub fn try_log(&mut self, level: log_level::LogLevel, message: &str) -> io::Result<bool> {
+        self.try_log_lines(level, &[message], MessageOptions::default())
+    }
+
+    /// Logs a message built with `format_args!` with the specified log level.
+    /// The message is only formatted if it will be logged, and all formatting options of `std::fmt`
+    /// (fill, alignment, width, precision, `#` and `0`) work as they do with `format!`.
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `args` - The message, as returned by `format_args!`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.log_fmt(LogLevel::Info, format_args!("Progress: {:>6.2}%", 42.5));
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [info] Progress:  42.50%
+    pub fn log_fmt(&mut self, level: log_level::LogLevel, args: fmt::Arguments) -> bool {
+        if !self.would_log(level) {
+            self.suppressed_count += 1;
+            return false;
+        }
+
+        match args.as_str() {
+            Some(message) => self.log(level, message),
+            None => self.log(level, &args.to_string()),
+        }
+    }
+
+    /// Logs a value with the specified log level, using its `Display` implementation, and returns the value unchanged.
+    /// This makes it possible to log a value in the middle of an expression, like `dbg!`.
+    /// The value is only formatted if it will be logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `value` - The value to log and return.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// let total = logger.log_and_return(LogLevel::Debug, 40 + 2) * 2;
+    ///
+    /// assert_eq!(total, 84);
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [debug] 42
+    pub fn log_and_return<T: fmt::Display>(&mut self, level: log_level::LogLevel, value: T) -> T {
+        self.log_fmt(level, format_args!("{}", value));
+
+        value
+    }
+
+    /// Logs an error with the log level "Error": its message, followed by a line `caused by: <message>`
+    /// for every error in its source chain, from the direct cause to the root cause.
+    /// Every line gets the prefix with the timestamp, the name and the log level.
+    /// Returns `true` if the error was logged and `false` if it wasn't because the log level was too low.
+    /// The error is only formatted if it will be logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// if let Err(error) = std::fs::read_to_string("missing.txt") {
+    ///     logger.log_error(&error);
+    /// }
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [error] No such file or directory (os error 2)
+    pub fn log_error(&mut self, error: &dyn std::error::Error) -> bool {
+        self.log_error_with_level(log_level::LogLevel::Error, error)
+    }
+
+    /// Logs an error with the specified log level, with its source chain,
+    /// like [`log_error`](#method.log_error).
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `error` - The error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// let error = "x".parse::<i32>().unwrap_err();
+    /// logger.log_error_with_level(LogLevel::Warning, &error);
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [warning] invalid digit found in string
+    pub fn log_error_with_level(
+        &mut self,
+        level: log_level::LogLevel,
+        error: &dyn std::error::Error,
+    ) -> bool {
+        if !self.would_log(level) {
+            self.suppressed_count += 1;
+            return false;
+        }
+
+        let mut lines = vec![error.to_string()];
+        let mut source = error.source();
+        while let Some(cause) = source {
+            lines.push(format!("caused by: {}", cause));
+            source = cause.source();
+        }
+
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        self.log_lines(level, &lines, MessageOptions::default())
+    }
+
+    /// Logs a message with the specified log level and trace id.
+    /// The trace id is used instead of the one set with [`set_trace_id`](#method.set_trace_id), for this message only.
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `message` - The message.
+    /// * `trace_id` - The trace id of the message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.log_with_trace_id(LogLevel::Info, "Handling request", "abc123");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [info] [trace=abc123] Handling request
+    pub fn log_with_trace_id(
+        &mut self,
+        level: log_level::LogLevel,
+        message: &str,
+        trace_id: &str,
+    ) -> bool {
+        self.log_lines(
+            level,
+            &[message],
+            MessageOptions {
+                trace_id: Some(trace_id),
+                ..MessageOptions::default()
+            },
+        )
+    }
+
+    /// Logs a message with the specified log level, followed by key-value pairs to correlate log lines,
+    /// like a user id or a request id. The pairs are appended to the message as `key=value`, separated by spaces;
+    /// values that are empty or contain spaces, `=` or quotes are quoted.
+    /// In the JSON output format, the pairs are written as the object `fields` instead.
+    /// Values longer than [`set_max_field_value_len`](#method.set_max_field_value_len) are truncated.
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `message` - The message.
+    /// * `fields` - The key-value pairs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.log_with_fields(LogLevel::Info, "Request handled", &[("user_id", "42"), ("path", "/my files")]);
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [info] Request handled user_id=42 path="/my files"
+    pub fn log_with_fields(
+        &mut self,
+        level: log_level::LogLevel,
+        message: &str,
+        fields: &[(&str, &str)],
+    ) -> bool {
+        self.log_lines(
+            level,
+            &[message],
+            MessageOptions {
+                fields,
+                ..MessageOptions::default()
+            },
+        )
+    }
+
+    /// Logs a message with the specified log level and the given time instead of the current time,
+    /// for example to keep the original times of events that are replayed.
+    /// The time is used for the timestamp in every output format, and for the date marker of the compact date mode.
+    /// With relative timestamps (see [`set_timestamp_style`](#method.set_timestamp_style)), the timestamp is the time
+    /// since the logger was created until the given time, which is negative, like `-1.500s`, for a time before that.
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `message` - The message.
+    /// * `when` - The time of the message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use chrono::{Local, TimeZone};
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    /// let when = Local.with_ymd_and_hms(2020, 12, 31, 23, 59, 59).unwrap();
+    ///
+    /// logger.log_at(LogLevel::Info, "Order received", when);
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.000] [example] [info] Order received
+    pub fn log_at(
+        &mut self,
+        level: log_level::LogLevel,
+        message: &str,
+        when: DateTime<Local>,
+    ) -> bool {
+        self.log_lines(
+            level,
+            &[message],
+            MessageOptions {
+                time: Some(when),
+                ..MessageOptions::default()
+            },
+        )
+    }
+
+    /// Logs a message with the specified log level, and returns the line that was logged,
+    /// without colors, like it's written to the console, for example to show it in a user interface too.
+    /// Returns `None` if the message wasn't logged because the log level was too low.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `message` - The message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Info);
+    ///
+    /// let line = logger.log_formatted(LogLevel::Warning, "Disk almost full").unwrap();
+    ///
+    /// assert!(line.ends_with("[example] [warning] Disk almost full"));
+    /// assert_eq!(logger.log_formatted(LogLevel::Debug, "Not logged"), None);
+    /// ```
+    pub fn log_formatted(&mut self, level: log_level::LogLevel, message: &str) -> Option<String> {
+        self.formatted = Some(Vec::new());
+        let logged = self.log(level, message);
+        let mut formatted = self.formatted.take().unwrap_or_default();
+
+        // Summaries of suppressed messages may be logged first, so the message is the last line.
+        if logged {
+            formatted.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Logs a message that may not be valid UTF-8, like a captured protocol frame, with the specified log level.
+    /// In the text format, file and writer sinks get the bytes verbatim after the usual prefix.
+    /// Everywhere else, like on the console and in the JSON and binary formats,
+    /// invalid UTF-8 is replaced by `U+FFFD REPLACEMENT CHARACTER`.
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `bytes` - The message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.log_bytes(LogLevel::Debug, b"frame \xff\x00");
+    /// ```
+    ///
+    /// This will log the following message on the console:
+    /// [2020-12-31 23:59:59.999] [example] [debug] frame \u{FFFD}\u{0}
+    pub fn log_bytes(&mut self, level: log_level::LogLevel, bytes: &[u8]) -> bool {
+        if !self.would_log(level) {
+            self.suppressed_count += 1;
+            return false;
+        }
+
+        self.log_lines(
+            level,
+            &[&String::from_utf8_lossy(bytes)],
+            MessageOptions {
+                raw: Some(bytes),
+                ..MessageOptions::default()
+            },
+        )
+    }
+
+    /// Logs a message with the specified log level, with the message in a custom color on the console.
+    /// Only the color of the message itself changes; the log level keeps its own color, and files are unaffected.
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `message` - The message.
+    /// * `color` - The color of the message on the console.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use colored::Color;
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.log_colored(LogLevel::Info, "All tests passed", Color::Green);
+    /// ```
+    ///
+    /// This will log "All tests passed" in green, with the log level "info" in its usual color.
+    pub fn log_colored(&mut self, level: log_level::LogLevel, message: &str, color: Color) -> bool {
+        self.log_lines(
+            level,
+            &[message],
+            MessageOptions {
+                color: Some(color),
+                ..MessageOptions::default()
+            },
+        )
+    }
+
+    /// Logs a `serde_json::Value` with the specified log level, as compact JSON.
+    /// Objects are logged as they are, other values are wrapped in an object with the key `value`.
+    /// In the JSON output format (see [`set_output_format`](#method.set_output_format)),
+    /// the object is also embedded in the record as the `payload` field.
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    ///
+    /// This is only available with the `serde` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `value` - The value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.log_json_value(LogLevel::Info, &json!({"user": "alice", "items": 3}));
+    /// logger.log_json_value(LogLevel::Info, &json!(42));
+    /// ```
+    ///
+    /// This will log the following messages:
+    /// [2020-12-31 23:59:59.999] [example] [info] {"items":3,"user":"alice"}
+    /// [2020-12-31 23:59:59.999] [example] [info] {"value":42}
+    #[cfg(feature = "serde")]
+    pub fn log_json_value(
+        &mut self,
+        level: log_level::LogLevel,
+        value: &serde_json::Value,
+    ) -> bool {
+        let message = if value.is_object() {
+            value.to_string()
+        } else {
+            serde_json::json!({ "value": value }).to_string()
+        };
+
+        self.log_lines(
+            level,
+            &[&message],
+            MessageOptions {
+                payload: Some(&message),
+                ..MessageOptions::default()
+            },
+        )
+    }
+
+    /// Logs a title followed by a table of key-value pairs with the specified log level.
+    /// The keys are padded to the length of the longest key, so the values line up.
+    /// Every line of the table gets the usual prefix, and the whole table is written at once,
+    /// so it can't be interleaved with other messages.
+    /// Long values are truncated, see [`set_max_field_value_len`](#method.set_max_field_value_len).
+    /// Returns `true` if the table was logged and `false` if the table wasn't logged because the log level was too low.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the table.
+    /// * `title` - The title, which is logged on the first line.
+    /// * `rows` - The key-value pairs, one per line.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.log_table(LogLevel::Info, "Configuration", &[("host", "localhost"), ("port", "8080"), ("workers", "4")]);
+    /// ```
+    ///
+    /// This will log the following messages:
+    /// ```text
+    /// [2020-12-31 23:59:59.999] [example] [info] Configuration
+    /// [2020-12-31 23:59:59.999] [example] [info]   host    : localhost
+    /// [2020-12-31 23:59:59.999] [example] [info]   port    : 8080
+    /// [2020-12-31 23:59:59.999] [example] [info]   workers : 4
+    /// ```
+    pub fn log_table(
+        &mut self,
+        level: log_level::LogLevel,
+        title: &str,
+        rows: &[(&str, &str)],
+    ) -> bool {
+        let lines = Logger::format_table(title, rows, self.max_field_value_len);
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+
+        self.log_lines(level, &lines, MessageOptions::default())
+    }
+
+    /// Logs a message with the log level "Trace".
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    /// This is a shortcut for [`log`](#method.log) with the log level "Trace".
+    /// See [`log`](#method.log) for more information.
+    ///
+    /// The message will only be logged if the minimum log level is "Trace".
+    /// This is the default minimum log level.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.trace("This is a trace message.");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [trace] This is a trace message.
+    pub fn trace(&mut self, message: &str) -> bool {
+        self.log(log_level::LogLevel::Trace, message)
+    }
+
+    /// Logs a message with the log level "Debug".
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    /// This is a shortcut for [`log`](#method.log) with the log level "Debug".
+    /// See [`log`](#method.log) for more information.
+    ///
+    /// The message will only be logged if the minimum log level is "Trace" or "Debug".
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.debug("This is a debug message.");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [debug] This is a debug message.
+    pub fn debug(&mut self, message: &str) -> bool {
+        self.log(log_level::LogLevel::Debug, message)
+    }
+
+    /// Logs a message with the log level "Info".
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    /// This is a shortcut for [`log`](#method.log) with the log level "Info".
+    /// See [`log`](#method.log) for more information.
+    ///
+    /// The message will only be logged if the minimum log level is "Trace", "Debug" or "Info".
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.info("This is an info message.");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [info] This is an info message.
+    pub fn info(&mut self, message: &str) -> bool {
+        self.log(log_level::LogLevel::Info, message)
+    }
+
+    /// Logs a message with the log level "Warning".
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    /// This is a shortcut for [`log`](#method.log) with the log level "Warning".
+    /// See [`log`](#method.log) for more information.
+    ///
+    /// The message will only be logged if the minimum log level is "Trace", "Debug", "Info" or "Warning".
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.warning("This is a warning message.");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [warning] This is a warning message.
+    pub fn warning(&mut self, message: &str) -> bool {
+        self.log(log_level::LogLevel::Warning, message)
+    }
+
+    /// Logs a message with the log level "Error".
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    /// This is a shortcut for [`log`](#method.log) with the log level "Error".
+    /// See [`log`](#method.log) for more information.
+    ///
+    /// The message will only be logged if the minimum log level is "Trace", "Debug", "Info", "Warning" or "Error".
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.error("This is an error message.");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [error] This is an error message.
+    pub fn error(&mut self, message: &str) -> bool {
+        self.log(log_level::LogLevel::Error, message)
+    }
+
+    /// Logs a message with the log level "Critical".
+    /// Returns `true` if the message was logged and `false` if the message wasn't logged because the log level was too low.
+    /// This is a shortcut for [`log`](#method.log) with the log level "Critical".
+    /// See [`log`](#method.log) for more information.
+    /// The message will always be logged, because this is the highest log level.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.critical("This is a critical message.");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [critical] This is a critical message.
+    pub fn critical(&mut self, message: &str) -> bool {
+        self.log(log_level::LogLevel::Critical, message)
+    }
+
+    /// Turns the logger off: nothing is logged until [`enable`](#method.enable) is called,
+    /// and every logging method returns `false`, whatever the log level, even "Critical".
+    /// This includes summaries of repeated messages and heartbeats. The settings and sinks are kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.disable();
+    /// assert!(!logger.critical("This critical message isn't logged."));
+    ///
+    /// logger.enable();
+    /// assert!(logger.critical("This critical message is logged."));
+    /// ```
+    pub fn disable(&mut self) {
+        self.enabled = false;
+    }
+
+    /// Turns the logger back on after [`disable`](#method.disable).
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    /// Returns `false` if the logger was turned off with [`disable`](#method.disable).
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Returns `true` if a message with the specified log level would be logged: if the logger is on,
+    /// and the log level is at least the minimum log level. Use this to skip expensive work for a message that
+    /// wouldn't be logged anyway. The message filter isn't checked, because it depends on the message,
+    /// see [`set_message_filter`](#method.set_message_filter).
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Info);
+    ///
+    /// if logger.would_log(LogLevel::Debug) {
+    ///     let report = (0..1000).map(|i| i.to_string()).collect::<Vec<_>>().join(", ");
+    ///     logger.debug(&report);
+    /// }
+    /// ```
+    pub fn would_log(&self, level: log_level::LogLevel) -> bool {
+        self.enabled && level.is_at_least(self.min_level)
+    }
+
+    /// Sets the minimum log level. The change applies to the next message.
+    /// The minimum log levels of the sinks still apply, see [`set_min_level_for_sink`](#method.set_min_level_for_sink).
+    ///
+    /// # Arguments
+    ///
+    /// * `min_level` - The minimum log level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Info);
+    ///
+    /// logger.set_min_level(LogLevel::Trace);
+    /// logger.trace("Verbose mode enabled");
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [trace] Verbose mode enabled
+    pub fn set_min_level(&mut self, min_level: log_level::LogLevel) {
+        self.min_level = min_level;
+    }
+
+    /// Returns the minimum log level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let logger = Logger::new(String::from("example"), LogLevel::Warning);
+    ///
+    /// assert_eq!(logger.min_level(), LogLevel::Warning);
+    /// ```
+    pub fn min_level(&self) -> log_level::LogLevel {
+        self.min_level
+    }
+
+    /// Runs a closure with a different minimum log level, and restores the previous minimum log level afterwards,
+    /// even if the closure panics. Returns whatever the closure returns.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_level` - The minimum log level while the closure runs.
+    /// * `f` - The closure, which gets the logger as argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Info);
+    ///
+    /// let result = logger.with_min_level(LogLevel::Trace, |logger| {
+    ///     logger.trace("Parsing the input");
+    ///     42
+    /// });
+    /// logger.trace("This message isn't logged.");
+    ///
+    /// assert_eq!(result, 42);
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [trace] Parsing the input
+    pub fn with_min_level<T>(
+        &mut self,
+        min_level: log_level::LogLevel,
+        f: impl FnOnce(&mut Logger) -> T,
+    ) -> T {
+        let previous_min_level = self.min_level;
+        self.min_level = min_level;
+
+        let guard = MinLevelGuard {
+            logger: self,
+            previous_min_level,
+        };
+
+        f(guard.logger)
+    }
+
+    /// Runs a closure with the logger turned off, so not even critical messages are logged,
+    /// and restores the previous minimum log level and on/off state afterwards, even if the closure panics.
+    /// Returns whatever the closure returns.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure, which gets the logger as argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// let logged = logger.with_suppressed(|logger| logger.critical("This critical message isn't logged."));
+    /// logger.info("Done");
+    ///
+    /// assert!(!logged);
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [info] Done
+    pub fn with_suppressed<T>(&mut self, f: impl FnOnce(&mut Logger) -> T) -> T {
+        let guard = SuppressGuard {
+            previous_min_level: self.min_level,
+            previous_enabled: self.enabled,
+            logger: self,
+        };
+        guard.logger.enabled = false;
+
+        f(guard.logger)
+    }
+
+    /// Runs a closure inside a named scope, and leaves the scope afterwards, even if the closure panics.
+    /// Returns whatever the closure returns.
+    /// Scopes can be nested. If [`set_show_scope`](#method.set_show_scope) is enabled,
+    /// messages logged inside scopes show the labels of all active scopes, for example `[load > parse]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label of the scope.
+    /// * `f` - The closure, which gets the logger as argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    /// logger.set_show_scope(true);
+    ///
+    /// logger.with_scope("load", |logger| {
+    ///     logger.with_scope("parse", |logger| logger.warning("Unknown key"));
+    /// });
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [warning] [load > parse] Unknown key
+    pub fn with_scope<T>(&mut self, label: &str, f: impl FnOnce(&mut Logger) -> T) -> T {
+        self.scopes.push(label.to_string());
+
+        let guard = ScopeGuard { logger: self };
+
+        f(guard.logger)
+    }
+
+    /// Runs a closure, and returns whatever it returns together with the records of all messages logged meanwhile.
+    /// The messages are still written to the sinks. A record is captured for every message that passes the minimum
+    /// log level of the logger, even if no sink accepts it.
+    /// Captures can be nested, and stop when the closure returns or panics.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The closure, which gets the logger as argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// let (sum, records) = logger.capture(|logger| {
+    ///     logger.info("Adding");
+    ///     1 + 2
+    /// });
+    ///
+    /// assert_eq!(sum, 3);
+    /// assert_eq!(records.len(), 1);
+    /// assert_eq!(records[0].level, LogLevel::Info);
+    /// assert_eq!(records[0].message, "Adding");
+    /// ```
+    pub fn capture<T>(
+        &mut self,
+        f: impl FnOnce(&mut Logger) -> T,
+    ) -> (T, Vec<log_record::LogRecord>) {
+        let depth = self.captures.len();
+        self.captures.push(Vec::new());
+
+        let guard = CaptureGuard {
+            logger: self,
+            depth,
+        };
+
+        let value = f(guard.logger);
+        let records = std::mem::take(&mut guard.logger.captures[depth]);
+
+        (value, records)
+    }
+
+    /// Sets the maximum length in bytes of field values, like the values of [`log_table`](#method.log_table)
+    /// and [`log_with_fields`](#method.log_with_fields).
+    /// Longer values are cut on a character boundary and followed by an ellipsis and their original length,
+    /// for example `abc… (12000 bytes)`. Pass `None` to never truncate values, which is the default.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_field_value_len` - The maximum length in bytes, or `None` for no maximum.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.set_max_field_value_len(Some(5));
+    /// logger.log_table(LogLevel::Info, "Request", &[("body", "{\"user\":\"alice\"}")]);
+    /// ```
+    ///
+    /// This will log the following messages:
+    /// ```text
+    /// [2020-12-31 23:59:59.999] [example] [info] Request
+    /// [2020-12-31 23:59:59.999] [example] [info]   body : {"use… (16 bytes)
+    /// ```
+    pub fn set_max_field_value_len(&mut self, max_field_value_len: Option<usize>) {
+        self.max_field_value_len = max_field_value_len;
+    }
+
+    /// Sets whether the labels of the active scopes (see [`with_scope`](#method.with_scope))
+    /// are written after the log level and the trace id, for example `[load > parse > validate]`.
+    /// Nothing is written outside of scopes. The default is `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `show_scope` - Whether the scopes should be written.
+    pub fn set_show_scope(&mut self, show_scope: bool) {
+        self.show_scope = show_scope;
+    }
+
+    /// Returns the number of messages with the specified log level that were logged since the logger was created.
+    /// Messages that weren't logged because their log level was too low,
+    /// or because they were suppressed as repeated messages, aren't counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Info);
+    ///
+    /// logger.warning("This is a warning message.");
+    /// logger.debug("This message isn't logged.");
+    ///
+    /// assert_eq!(logger.message_count(LogLevel::Warning), 1);
+    /// assert_eq!(logger.message_count(LogLevel::Debug), 0);
+    /// ```
+    pub fn message_count(&self, level: log_level::LogLevel) -> u64 {
+        self.message_counts[level as usize]
+    }
+
+    /// Returns the number of messages that weren't logged, so the logging methods returned `false`:
+    /// because their log level was below the minimum log level, the logger was turned off with [`disable`](#method.disable),
+    /// they didn't match the [message filter](#method.set_message_filter), or they were suppressed
+    /// as repeated messages (see [`set_dedup_window`](#method.set_dedup_window) and [`set_burst_summary`](#method.set_burst_summary)).
+    /// This shows how much a more verbose minimum log level would log.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new(String::from("example"), LogLevel::Info);
+    ///
+    /// logger.debug("This message isn't logged.");
+    /// logger.trace("This message isn't logged either.");
+    /// logger.info("This message is logged.");
+    ///
+    /// assert_eq!(logger.suppressed_count(), 2);
+    /// ```
+    pub fn suppressed_count(&self) -> u64 {
+        self.suppressed_count
+    }
+
+    /// Sets the counters of [`message_count`](#method.message_count) and [`suppressed_count`](#method.suppressed_count)
+    /// back to zero.
+    pub fn reset_counters(&mut self) {
+        self.message_counts = [0; 6];
+        self.suppressed_count = 0;
+    }
+
+    /// Starts logging the message "heartbeat" with the specified log level whenever the logger hasn't logged
+    /// anything for the specified interval, so log-based liveness checks don't flag a quiet service as dead.
+    /// Every message that is logged resets the interval.
+    /// Calling this again replaces the previous heartbeat.
+    ///
+    /// The heartbeat runs on a background thread that only holds a weak reference to the logger,
+    /// so it doesn't keep the logger alive. The thread stops within one interval after the logger is dropped,
+    /// or after [`disable_heartbeat`](#method.disable_heartbeat) is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `logger` - The logger to log the heartbeat to.
+    /// * `level` - The log level of the heartbeat.
+    /// * `interval` - How long the logger has to be idle before a heartbeat is logged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use std::time::Duration;
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let logger = Arc::new(Mutex::new(Logger::new_default(String::from("example"))));
+    ///
+    /// Logger::enable_heartbeat(&logger, LogLevel::Info, Duration::from_secs(60));
+    /// ```
+    ///
+    /// If nothing else is logged for a minute, this will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [info] heartbeat
+    pub fn enable_heartbeat(
+        logger: &Arc<Mutex<Logger>>,
+        level: log_level::LogLevel,
+        interval: Duration,
+    ) {
+        let generation = {
+            let mut logger = match logger.lock() {
+                Ok(logger) => logger,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            logger.heartbeat = Some((level, interval));
+            logger.heartbeat_generation += 1;
+            logger.heartbeat_generation
+        };
+
+        let weak_logger = Arc::downgrade(logger);
+        thread::spawn(move || Logger::run_heartbeat(weak_logger, generation));
+    }
+
+    /// Stops the heartbeat started with [`enable_heartbeat`](#method.enable_heartbeat).
+    pub fn disable_heartbeat(&mut self) {
+        self.heartbeat = None;
+        self.heartbeat_generation += 1;
+    }
+
+    fn run_heartbeat(weak_logger: Weak<Mutex<Logger>>, generation: u64) {
+        loop {
+            let sleep_for = {
+                let logger = match weak_logger.upgrade() {
+                    Some(logger) => logger,
+                    None => return,
+                };
+                let mut logger = match logger.lock() {
+                    Ok(logger) => logger,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+
+                match logger.heartbeat {
+                    Some((level, interval)) if logger.heartbeat_generation == generation => {
+                        let idle = logger.last_message_at.elapsed();
+
+                        if idle >= interval {
+                            logger.log_heartbeat(level);
+                            interval
+                        } else {
+                            interval - idle
+                        }
+                    }
+                    _ => return,
+                }
+            };
+
+            thread::sleep(sleep_for);
+        }
+    }
+
+    /// Logs a heartbeat, bypassing the suppression of repeated messages.
+    fn log_heartbeat(&mut self, level: log_level::LogLevel) {
+        self.last_message_at = Instant::now();
+
+        if level < self.min_level {
+            return;
+        }
+
+        self.message_counts[level as usize] += 1;
+        let result = self.write_lines(level, &["heartbeat"], MessageOptions::default());
+        Logger::report_write_error(result);
+    }
+
+    /// Reads lines from a reader until the end, and logs every line with the specified log level,
+    /// prefixed with a label in brackets. This is meant for the output of a child process.
+    /// Invalid UTF-8 is replaced by `U+FFFD REPLACEMENT CHARACTER`.
+    /// Returns the number of lines that were read.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader, for example the stderr of a child process.
+    /// * `level` - The log level of the lines.
+    /// * `label` - The label that marks the lines, for example the name of the child process.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// let output = "Compiling\nDone\n".as_bytes();
+    /// let lines = logger.pipe_reader(output, LogLevel::Info, "build").unwrap();
+    ///
+    /// assert_eq!(lines, 2);
+    /// ```
+    ///
+    /// This will log the following messages:
+    /// [2020-12-31 23:59:59.999] [example] [info] [build] Compiling
+    /// [2020-12-31 23:59:59.999] [example] [info] [build] Done
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if reading fails. The lines read before are logged.
+    pub fn pipe_reader<R: Read>(
+        &mut self,
+        reader: R,
+        level: log_level::LogLevel,
+        label: &str,
+    ) -> io::Result<u64> {
+        let mut lines = 0;
+
+        for line in BufReader::new(reader).split(b'\n') {
+            self.log_piped_line(&line?, level, label);
+            lines += 1;
+        }
+
+        Ok(lines)
+    }
+
+    /// Like [`pipe_reader`](#method.pipe_reader), but reads on a new thread, so the caller doesn't block.
+    /// The logger is only locked while a line is logged.
+    /// The thread returns the number of lines that were read.
+    ///
+    /// # Arguments
+    ///
+    /// * `logger` - The logger to log the lines to.
+    /// * `reader` - The reader, for example the stderr of a child process.
+    /// * `level` - The log level of the lines.
+    /// * `label` - The label that marks the lines, for example the name of the child process.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::process::{Command, Stdio};
+    /// use std::sync::{Arc, Mutex};
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let logger = Arc::new(Mutex::new(Logger::new_default(String::from("example"))));
+    ///
+    /// let mut child = Command::new("cargo").arg("build").stderr(Stdio::piped()).spawn().unwrap();
+    /// let stderr = child.stderr.take().unwrap();
+    /// let handle = Logger::spawn_pipe_reader(&logger, stderr, LogLevel::Warning, "cargo");
+    ///
+    /// child.wait().unwrap();
+    /// handle.join().unwrap().unwrap();
+    /// ```
+    pub fn spawn_pipe_reader<R: Read + Send + 'static>(
+        logger: &Arc<Mutex<Logger>>,
+        reader: R,
+        level: log_level::LogLevel,
+        label: &str,
+    ) -> thread::JoinHandle<io::Result<u64>> {
+        let logger = Arc::clone(logger);
+        let label = label.to_string();
+
+        thread::spawn(move || {
+            let mut lines = 0;
+
+            for line in BufReader::new(reader).split(b'\n') {
+                let line = line?;
+                let mut logger = match logger.lock() {
+                    Ok(logger) => logger,
+                    Err(poisoned) => poisoned.into_inner(),
+                };
+                logger.log_piped_line(&line, level, &label);
+                lines += 1;
+            }
+
+            Ok(lines)
+        })
+    }
+
+    fn log_piped_line(&mut self, line: &[u8], level: log_level::LogLevel, label: &str) {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let message = format!("[{}] {}", label, String::from_utf8_lossy(line));
+
+        self.log(level, &message);
+    }
+
+    /// Logs a standardized startup line with the log level "Info", with the name of the logger,
+    /// the process id, the time in RFC 3339 format, and the time since the logger was created in seconds,
+    /// as `key=value` pairs.
+    /// The last two are measured at the same moment, with the wall clock and the monotonic clock,
+    /// so times relative to the creation of the logger can be mapped to absolute times afterwards:
+    /// the logger was created at `time` minus `since_start`.
+    /// Returns `true` if the line was logged and `false` if it wasn't logged because the log level was too low.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.log_startup();
+    /// ```
+    ///
+    /// This will log the following message:
+    /// [2020-12-31 23:59:59.999] [example] [info] startup name=example pid=1234 time=2020-12-31T23:59:59.999+01:00 since_start=0.000125s
+    pub fn log_startup(&mut self) -> bool {
+        let since_start = self.created_at.elapsed();
+        let message = format!(
+            "startup name={} pid={} time={} since_start={:.6}s",
+            self.escaped_name,
+            std::process::id(),
+            offset::Local::now().format(timestamp::TimestampPreset::Rfc3339.format_str()),
+            since_start.as_secs_f64()
+        );
+
+        self.log(log_level::LogLevel::Info, &message)
+    }
+
+    /// Logs a standardized shutdown line with the log level "Info", with the name of the logger,
+    /// the process id, the uptime since the logger was created in seconds,
+    /// and the number of messages per log level (see [`message_count`](#method.message_count)),
+    /// as `key=value` pairs.
+    /// Returns `true` if the line was logged and `false` if it wasn't logged because the log level was too low.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    ///
+    /// logger.warning("Low disk space");
+    /// logger.log_shutdown();
+    /// ```
+    ///
+    /// This will log the following messages:
+    /// [2020-12-31 23:59:59.999] [example] [warning] Low disk space
+    /// [2020-12-31 23:59:59.999] [example] [info] shutdown name=example pid=1234 uptime=0.002s trace=0 debug=0 info=0 warning=1 error=0 critical=0
+    pub fn log_shutdown(&mut self) -> bool {
+        let counts: Vec<String> = log_level::LogLevel::ALL
+            .iter()
+            .map(|level| format!("{}={}", level, self.message_count(*level)))
+            .collect();
+        let message = format!(
+            "shutdown name={} pid={} uptime={:.3}s {}",
+            self.escaped_name,
+            std::process::id(),
+            self.created_at.elapsed().as_secs_f64(),
+            counts.join(" ")
+        );
+
+        self.log(log_level::LogLevel::Info, &message)
+    }
+
+    /// Installs a panic hook that logs the panic with the log level "Critical",
+    /// followed by a summary of the number of messages per log level that were logged before the panic.
+    /// Pending summaries of repeated messages are written first, and all files are flushed afterwards,
+    /// before the previous panic hook runs.
+    ///
+    /// If the panicking thread holds the lock of the logger, nothing is logged, because that would deadlock.
+    ///
+    /// # Arguments
+    ///
+    /// * `logger` - The logger to log the panic to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use ultimate_logger::Logger;
+    ///
+    /// let logger = Arc::new(Mutex::new(Logger::new_default(String::from("example"))));
+    ///
+    /// Logger::install_panic_hook(Arc::clone(&logger));
+    /// logger.lock().unwrap().error("Something went wrong.");
+    /// ```
+    ///
+    /// If the program panics afterwards, this will log the following messages:
+    /// [2020-12-31 23:59:59.999] [example] [critical] panicked at src/main.rs:8:5:
+    /// [2020-12-31 23:59:59.999] [example] [critical] explicit panic
+    /// [2020-12-31 23:59:59.999] [example] [critical] trace: 0, debug: 0, info: 0, warning: 0, error: 1, critical: 1 before panic
+    pub fn install_panic_hook(logger: Arc<Mutex<Logger>>) {
+        let previous_hook = std::panic::take_hook();
+
+        std::panic::set_hook(Box::new(move |info| {
+            let guard = match logger.try_lock() {
+                Ok(guard) => Some(guard),
+                Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+                Err(TryLockError::WouldBlock) => None,
+            };
+
+            if let Some(mut logger) = guard {
+                logger.log_panic(&info.to_string());
+            }
+
+            previous_hook(info);
+        }));
+    }
+
+    fn log_panic(&mut self, panic_message: &str) {
+        let lines: Vec<&str> = panic_message.lines().collect();
+        self.message_counts[log_level::LogLevel::Critical as usize] += 1;
+        let result = self.write_lines(
+            log_level::LogLevel::Critical,
+            &lines,
+            MessageOptions::default(),
+        );
+        Logger::report_write_error(result);
+
+        self.flush_burst_summaries();
+
+        let summary = format!("{} before panic", self.get_message_counts_summary());
+        let result = self.write_lines(
+            log_level::LogLevel::Critical,
+            &[&summary],
+            MessageOptions::default(),
+        );
+        Logger::report_write_error(result);
+
+        Logger::report_write_error(self.flush());
+    }
+
+    /// Installs handlers for SIGTERM and SIGINT that flush the logger before the process terminates,
+    /// so no messages are lost when a container orchestrator stops the process.
+    /// If `log_shutdown` is `true`, a shutdown line is logged first (see [`log_shutdown`](#method.log_shutdown)).
+    /// Afterwards, the default action of the signal runs, which terminates the process.
+    ///
+    /// Only very few functions may be called inside a signal handler, and locking a mutex or writing a file
+    /// isn't one of them. So the signal handler itself only wakes up a background thread,
+    /// which locks the logger, writes and flushes it, and then terminates the process.
+    /// If another thread holds the lock of the logger forever, the process isn't terminated.
+    ///
+    /// This is only available on Unix, with the `signals` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `logger` - The logger to flush.
+    /// * `log_shutdown` - Whether to log a shutdown line before flushing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use ultimate_logger::Logger;
+    ///
+    /// let logger = Arc::new(Mutex::new(Logger::new_default(String::from("example"))));
+    ///
+    /// Logger::install_flush_on_termination(Arc::clone(&logger), true).unwrap();
+    /// ```
+    ///
+    /// If the process gets SIGTERM afterwards, this will log the following message before it terminates:
+    /// [2020-12-31 23:59:59.999] [example] [info] shutdown name=example pid=1234 uptime=12.345s trace=0 debug=0 info=0 warning=0 error=0 critical=0
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the signal handlers can't be registered.
+    #[cfg(all(unix, feature = "signals"))]
+    pub fn install_flush_on_termination(
+        logger: Arc<Mutex<Logger>>,
+        log_shutdown: bool,
+    ) -> io::Result<()> {
+        use signal_hook::consts::signal::{SIGINT, SIGTERM};
+
+        let mut signals = signal_hook::iterator::Signals::new([SIGTERM, SIGINT])?;
+
+        thread::spawn(move || {
+            if let Some(signal) = signals.forever().next() {
+                {
+                    let mut logger = match logger.lock() {
+                        Ok(guard) => guard,
+                        Err(poisoned) => poisoned.into_inner(),
+                    };
+                    logger.log_termination(log_shutdown);
+                }
+
+                if signal_hook::low_level::emulate_default_handler(signal).is_err() {
+                    std::process::exit(128 + signal);
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    #[cfg(all(unix, feature = "signals"))]
+    fn log_termination(&mut self, log_shutdown: bool) {
+        if log_shutdown {
+            self.log_shutdown();
+        }
+
+        self.flush_burst_summaries();
+        Logger::report_write_error(self.flush());
+    }
+
+    fn get_message_counts_summary(&self) -> String {
+        let counts: Vec<String> = log_level::LogLevel::ALL
+            .iter()
+            .map(|level| format!("{}: {}", level, self.message_count(*level)))
+            .collect();
+
+        counts.join(", ")
+    }
+}
+
+impl Drop for Logger {
+    fn drop(&mut self) {
+        self.flush_burst_summaries();
+        Logger::report_write_error(self.flush());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use colored::Colorize;
+
+    use super::*;
+    use sink::SinkKind;
+
+    fn writes_to_console(logger: &Logger) -> bool {
+        logger
+            .sinks()
+            .iter()
+            .any(|sink| sink.kind == SinkKind::Console)
+    }
+
+    fn writes_to_file(logger: &Logger) -> bool {
+        logger
+            .sinks()
+            .iter()
+            .any(|sink| sink.kind == SinkKind::File)
+    }
+
+    // Logger::new()
+
+    #[test]
+    fn new_logger_should_have_correct_min_level() {
+        let logger = Logger::new(String::from("test"), log_level::LogLevel::Warning);
+
+        assert_eq!(logger.min_level, log_level::LogLevel::Warning);
+    }
+
+    #[test]
+    fn new_logger_should_write_to_console() {
         let logger = Logger::new(String::from("test"), log_level::LogLevel::Warning);
 
-        assert_eq!(logger.min_level, log_level::LogLevel::Warning);
+        assert!(writes_to_console(&logger));
+    }
+
+    #[test]
+    fn new_logger_should_not_write_to_file() {
+        let logger = Logger::new(String::from("test"), log_level::LogLevel::Warning);
+
+        assert!(!writes_to_file(&logger));
+    }
+
+    // Logger::new_default()
+
+    #[test]
+    fn new_default_logger_should_have_level_trace() {
+        let logger = Logger::new_default(String::from("test"));
+
+        assert_eq!(logger.min_level, log_level::LogLevel::Trace);
+    }
+
+    // Logger::from_env()
+
+    #[test]
+    fn from_env_should_read_min_level_or_fall_back_to_info() {
+        let var = "ULTIMATE_LOGGER_TEST_FROM_ENV";
+
+        std::env::set_var(var, "DEBUG");
+        let logger = Logger::from_env(String::from("test"), var);
+        assert_eq!(logger.min_level(), log_level::LogLevel::Debug);
+
+        std::env::set_var(var, "critical");
+        let logger = Logger::from_env(String::from("test"), var);
+        assert_eq!(logger.min_level(), log_level::LogLevel::Critical);
+
+        std::env::set_var(var, "loud");
+        let logger = Logger::from_env(String::from("test"), var);
+        assert_eq!(logger.min_level(), log_level::LogLevel::Info);
+        assert_eq!(logger.message_count(log_level::LogLevel::Warning), 1);
+
+        std::env::remove_var(var);
+        let logger = Logger::from_env(String::from("test"), var);
+        assert_eq!(logger.min_level(), log_level::LogLevel::Info);
+        assert_eq!(logger.message_count(log_level::LogLevel::Warning), 0);
+    }
+
+    // Logger::new_null()
+
+    #[test]
+    fn new_null_logger_should_log_nothing() {
+        let mut logger = Logger::new_null(String::from("test"));
+
+        assert!(logger.sinks().is_empty());
+        assert!(!logger.would_log(log_level::LogLevel::Critical));
+        assert!(!logger.critical("message"));
+        assert_eq!(logger.message_count(log_level::LogLevel::Critical), 0);
+        assert!(logger.flush().is_ok());
+    }
+
+    // Logger::new_to_file()
+
+    #[test]
+    fn new_logger_to_file_should_enable_write_to_file() {
+        let logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Warning,
+            String::from("test.log"),
+            false,
+        );
+
+        assert!(writes_to_file(&logger));
+    }
+
+    #[test]
+    fn new_logger_to_file_which_also_writes_to_console_should_enable_write_to_console() {
+        let logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Warning,
+            String::from("test.log"),
+            true,
+        );
+
+        assert!(writes_to_console(&logger));
+    }
+
+    #[test]
+    fn new_logger_to_file_which_does_not_write_to_console_should_disable_write_to_console() {
+        let logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Warning,
+            String::from("test.log"),
+            false,
+        );
+
+        assert!(!writes_to_console(&logger));
+    }
+
+    #[test]
+    fn new_logger_to_file_should_have_correct_min_level() {
+        let logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Warning,
+            String::from("test.log"),
+            false,
+        );
+
+        assert_eq!(logger.min_level, log_level::LogLevel::Warning);
+    }
+
+    // Logger::try_new_to_file()
+
+    #[test]
+    fn try_new_to_file_should_return_error_for_directory() {
+        let result = Logger::try_new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Warning,
+            &std::env::temp_dir().to_string_lossy(),
+            false,
+        );
+
+        assert!(matches!(
+            result.err().unwrap(),
+            error::LoggerError::FileIsDirectory(_)
+        ));
+    }
+
+    #[test]
+    fn try_new_to_file_should_write_to_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_try_new_to_file.log");
+        let logger = Logger::try_new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Warning,
+            &path.to_string_lossy(),
+            true,
+        )
+        .unwrap();
+
+        assert!(writes_to_file(&logger));
+        assert!(writes_to_console(&logger));
+    }
+
+    // Logger::new_to_writer()
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn new_to_writer_should_write_lines_to_writer() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(buffer.clone()),
+        );
+        logger.set_show_line_number(true);
+
+        logger.debug("debug");
+        logger.info("first");
+        logger.error("second");
+
+        let sinks = logger.sinks();
+        assert_eq!(sinks.len(), 1);
+        assert_eq!(sinks[0].kind, SinkKind::Writer);
+        assert!(logger.snapshot_config().sinks.is_empty());
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("1: ["));
+        assert!(lines[0].ends_with("] [test] [info] first"));
+        assert!(lines[1].ends_with("] [test] [error] second"));
+    }
+
+    // Logger::set_name()
+
+    #[test]
+    fn set_name_should_apply_to_next_line() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("before"),
+            log_level::LogLevel::Info,
+            Box::new(buffer.clone()),
+        );
+        logger.set_source(Some(String::from("part")));
+
+        logger.info("first");
+        logger.set_name(String::from("after\n"));
+        logger.info("second");
+
+        assert_eq!(logger.name(), "after\n");
+        assert_eq!(logger.snapshot_config().name, "after\n");
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert!(lines[0].ends_with("] [before/part] [info] first"));
+        assert!(lines[1].ends_with("] [after\\n/part] [info] second"));
+    }
+
+    struct FailingWriter;
+
+    impl io::Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn new_to_writer_should_return_write_errors() {
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(FailingWriter),
+        );
+
+        let error = logger
+            .try_log(log_level::LogLevel::Info, "lost")
+            .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(error.to_string(), "Error writing to log writer: closed");
+    }
+
+    // Logger::try_log()
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn try_log_should_return_write_error() {
+        let mut logger = Logger::try_new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            "/dev/full",
+            false,
+        )
+        .unwrap();
+
+        let error = logger
+            .try_log(log_level::LogLevel::Info, "message")
+            .unwrap_err();
+
+        assert!(error.to_string().contains("/dev/full"));
+        assert!(!logger
+            .try_log(log_level::LogLevel::Debug, "message")
+            .unwrap());
+        assert!(logger.log(log_level::LogLevel::Info, "message"));
+    }
+
+    // Logger::get_colored_level_name()
+
+    #[test]
+    fn get_colored_level_name_should_return_correct_string_for_critical() {
+        let level_name = Logger::get_colored_level_name(
+            color::Theme::Default.style(log_level::LogLevel::Critical),
+            log_level::LogLevel::Critical,
+        );
+
+        assert_eq!(level_name, "critical".red().bold());
+    }
+
+    // Logger::get_colored_message()
+
+    #[test]
+    fn get_colored_message_should_return_correct_string_for_critical() {
+        let message = Logger::get_colored_message(
+            color::Theme::Default.style(log_level::LogLevel::Critical),
+            "test",
+        );
+
+        assert_eq!(message, "test".red().bold());
+    }
+
+    // Logger::log()
+
+    #[test]
+    fn log_should_return_false_if_level_is_below_min_level() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Error);
+
+        let result = logger.log(log_level::LogLevel::Debug, "test");
+
+        assert!(!result);
+    }
+
+    #[test]
+    fn log_should_return_true_if_level_is_above_min_level() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Debug);
+
+        let result = logger.log(log_level::LogLevel::Error, "test");
+
+        assert!(result);
+    }
+
+    #[test]
+    fn log_should_return_true_if_level_is_equal_to_min_level() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Error);
+
+        let result = logger.log(log_level::LogLevel::Error, "test");
+
+        assert!(result);
+    }
+
+    // Logger::set_max_bytes_per_sec()
+
+    #[test]
+    fn lines_over_max_bytes_per_sec_should_be_counted_as_suppressed() {
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            String::from("test.log"),
+            false,
+        );
+        logger.set_max_bytes_per_sec(Some(10));
+
+        logger.info("this line is longer than ten bytes");
+
+        assert!(logger.suppressed_bytes() > 10);
+    }
+
+    // Logger::set_compact_date()
+
+    #[test]
+    fn compact_date_should_write_date_marker_once_and_only_time_on_lines() {
+        let path = std::env::temp_dir().join("ultimate_logger_compact_date.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        logger.set_compact_date(true);
+
+        logger.info("first");
+        logger.info("second");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("----- ") && lines[0].ends_with(" -----"));
+        assert_eq!(lines[1].find(']'), Some("[00:00:00.000]".len() - 1));
+        assert!(lines[2].ends_with("[test] [info] second"));
+    }
+
+    // Logger::log_table()
+
+    #[test]
+    fn format_table_should_pad_keys_to_longest_key() {
+        let lines = Logger::format_table("title", &[("a", "1"), ("long", "2")], None);
+
+        assert_eq!(lines, vec!["title", "  a    : 1", "  long : 2"]);
+    }
+
+    #[test]
+    fn format_table_should_truncate_long_values() {
+        let lines = Logger::format_table("title", &[("a", "héllo"), ("b", "hi")], Some(2));
+
+        assert_eq!(lines, vec!["title", "  a : h… (6 bytes)", "  b : hi"]);
+    }
+
+    #[test]
+    fn log_table_should_write_every_row_with_prefix() {
+        let path = std::env::temp_dir().join("ultimate_logger_log_table.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        let result = logger.log_table(log_level::LogLevel::Info, "title", &[("key", "value")]);
+
+        assert!(result);
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("[test] [info] title"));
+        assert!(lines[1].ends_with("[test] [info]   key : value"));
+    }
+
+    // Logger::set_dedup_window()
+
+    #[test]
+    fn log_should_return_false_for_duplicate_within_dedup_window() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+        logger.set_dedup_window(Some(Duration::from_secs(60)));
+
+        assert!(logger.info("test"));
+        assert!(logger.info("other"));
+        assert!(!logger.info("test"));
+    }
+
+    // Logger::set_show_line_number()
+
+    #[test]
+    fn show_line_number_should_number_lines_in_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_line_number.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.info("not numbered");
+        logger.set_show_line_number(true);
+        logger.log_table(log_level::LogLevel::Info, "title", &[("key", "value")]);
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(lines[0].starts_with('['));
+        assert!(lines[1].starts_with("2: ["));
+        assert!(lines[2].starts_with("3: ["));
+    }
+
+    // Logger::set_context()
+
+    #[test]
+    fn set_context_should_be_written_after_name() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            Box::new(buffer.clone()),
+        );
+
+        logger.set_context(Some(String::from("worker-1")));
+        logger.info("first");
+        let mut clone = logger.clone_with_name(String::from("clone"));
+        clone.set_context(Some(String::from("worker-2")));
+        clone.info("second");
+        logger.set_context(None);
+        logger.info("third");
+        logger.flush().unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("] [test] [worker-1] [info] first"));
+        assert!(lines[1].ends_with("] [clone] [worker-2] [info] second"));
+        assert!(lines[2].ends_with("] [test] [info] third"));
+    }
+
+    // Logger::set_trace_id() and Logger::log_with_trace_id()
+
+    #[test]
+    fn trace_id_should_be_written_after_level_and_be_overridable_per_call() {
+        let path = std::env::temp_dir().join("ultimate_logger_trace_id.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.info("none");
+        logger.set_trace_id(Some(String::from("abc")));
+        logger.info("context");
+        logger.log_with_trace_id(log_level::LogLevel::Info, "call", "def");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(lines[0].ends_with("[test] [info] none"));
+        assert!(lines[1].ends_with("[test] [info] [trace=abc] context"));
+        assert!(lines[2].ends_with("[test] [info] [trace=def] call"));
+    }
+
+    #[test]
+    fn trace_id_and_scope_should_be_escaped() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            Box::new(buffer.clone()),
+        );
+        logger.set_show_scope(true);
+
+        logger.set_trace_id(Some(String::from("a] [critical] b\nc")));
+        logger.with_scope("lo]ad\n☃", |logger| logger.info("message"));
+        logger.log_with_trace_id(log_level::LogLevel::Info, "call", "☃]");
+        logger.flush().unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0]
+            .ends_with("[test] [info] [trace=a\\] \\[critical\\] b\\nc] [lo\\]ad\\n☃] message"));
+        assert!(lines[1].ends_with("[test] [info] [trace=☃\\]] call"));
+
+        let record = log_record::parse_line(lines[1]).unwrap();
+        assert_eq!(record.level, log_level::LogLevel::Info);
+        assert_eq!(record.trace_id.as_deref(), Some("☃\\]"));
+        assert_eq!(record.message, "call");
+        let record = log_record::parse_line(lines[0]).unwrap();
+        assert_eq!(record.level, log_level::LogLevel::Info);
+        assert_eq!(
+            record.trace_id.as_deref(),
+            Some("a\\] \\[critical\\] b\\nc")
+        );
+    }
+
+    // Logger::set_source()
+
+    #[test]
+    fn source_should_be_written_after_name_and_escaped() {
+        let path = std::env::temp_dir().join("ultimate_logger_source.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("worker"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.set_source(Some(String::from("image-resize")));
+        logger.info("resized");
+        logger.set_source(Some(String::from("a]b")));
+        logger.info("escaped");
+        logger.set_source(None);
+        logger.info("none");
+
+        assert_eq!(logger.snapshot_config().source, None);
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(lines[0].ends_with("[worker/image-resize] [info] resized"));
+        assert!(lines[1].ends_with("[worker/a\\]b] [info] escaped"));
+        assert!(lines[2].ends_with("[worker] [info] none"));
+    }
+
+    // Logger::console_only_mirror()
+
+    #[test]
+    fn console_only_mirror_should_read_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_console_only_mirror.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        logger.info("test");
+
+        assert!(logger.console_only_mirror(5).is_ok());
+    }
+
+    #[test]
+    fn console_only_mirror_should_write_to_console_writer() {
+        let path = std::env::temp_dir().join("ultimate_logger_console_only_mirror_writer.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        let buffer = SharedBuffer::default();
+        logger.set_console_writer(Box::new(buffer.clone()));
+        logger.set_color_choice(color::ColorChoice::Never);
+        logger.info("first");
+        logger.info("second");
+        logger.info("third");
+
+        logger.console_only_mirror(2).unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("] [test] [info] second"));
+        assert!(lines[1].ends_with("] [test] [info] third"));
+
+        logger.console_only_mirror(0).unwrap();
+        assert_eq!(buffer.0.lock().unwrap().len(), output.len());
+    }
+
+    #[test]
+    fn console_only_mirror_without_file_should_do_nothing() {
+        let mut logger = Logger::new_default(String::from("test"));
+
+        assert!(logger.console_only_mirror(5).is_ok());
+    }
+
+    // Logger::set_trim_trailing_newline()
+
+    #[test]
+    fn trim_trailing_newline_should_remove_only_one_newline() {
+        assert_eq!(Logger::trim_trailing_newline("test\n"), "test");
+        assert_eq!(Logger::trim_trailing_newline("test\r\n"), "test");
+        assert_eq!(Logger::trim_trailing_newline("test\n\n"), "test\n");
+        assert_eq!(Logger::trim_trailing_newline("test"), "test");
+    }
+
+    #[test]
+    fn trailing_newline_should_not_cause_empty_line_in_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_trim_trailing_newline.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.info("first\n");
+        logger.info("second");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+    }
+
+    // Logger::add_file_sink() and Logger::sinks()
+
+    #[test]
+    fn add_file_sink_should_only_write_messages_at_its_level() {
+        let path = std::env::temp_dir().join("ultimate_logger_add_file_sink.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+
+        logger
+            .add_file_sink(&path.to_string_lossy(), log_level::LogLevel::Error)
+            .unwrap();
+        logger.info("info");
+        logger.error("error");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.ends_with("[test] [error] error\n"));
+    }
+
+    #[test]
+    fn sinks_should_have_distinct_ids_and_effective_min_level() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+        let console_id = logger.sinks()[0].id;
+        let path = std::env::temp_dir().join("ultimate_logger_sinks_ids.log");
+        let path = path.to_str().unwrap();
+
+        let trace_id = logger
+            .add_file_sink(path, log_level::LogLevel::Trace)
+            .unwrap();
+        let error_id = logger
+            .add_file_sink(path, log_level::LogLevel::Error)
+            .unwrap();
+
+        let sinks = logger.sinks();
+        assert_eq!(sinks.len(), 3);
+        assert_eq!(sinks[1].id, trace_id);
+        assert_eq!(sinks[2].id, error_id);
+        assert_ne!(console_id, trace_id);
+        assert_ne!(trace_id, error_id);
+        assert_eq!(sinks[1].path, Some(String::from(path)));
+        assert_eq!(sinks[1].min_level, log_level::LogLevel::Info);
+        assert_eq!(sinks[2].min_level, log_level::LogLevel::Error);
+    }
+
+    #[test]
+    fn add_file_sink_should_return_error_for_directory() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+
+        let result = logger.add_file_sink(
+            &std::env::temp_dir().to_string_lossy(),
+            log_level::LogLevel::Info,
+        );
+
+        assert!(matches!(
+            result,
+            Err(error::LoggerError::FileIsDirectory(_))
+        ));
+    }
+
+    // Logger::remove_sink()
+
+    #[test]
+    fn remove_sink_should_stop_writing_to_sink() {
+        let path = std::env::temp_dir().join("ultimate_logger_remove_sink.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+        let id = logger
+            .add_file_sink(&path.to_string_lossy(), log_level::LogLevel::Trace)
+            .unwrap();
+
+        logger.info("before");
+        let removed = logger.remove_sink(id);
+        logger.info("after");
+
+        assert!(removed);
+        assert_eq!(logger.sinks().len(), 1);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+    }
+
+    #[test]
+    fn remove_sink_should_return_false_for_unknown_id() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+        let id = logger.sinks()[0].id;
+
+        assert!(logger.remove_sink(id));
+        assert!(!logger.remove_sink(id));
+    }
+
+    // Logger::set_min_level_for_sink()
+
+    #[test]
+    fn set_min_level_for_sink_should_only_change_that_sink() {
+        let path = std::env::temp_dir().join("ultimate_logger_min_level_for_sink.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+        let console = logger.sinks()[0].id;
+        let file = logger
+            .add_file_sink(&path.to_string_lossy(), log_level::LogLevel::Error)
+            .unwrap();
+
+        logger.debug("before");
+        assert!(logger.set_min_level_for_sink(file, log_level::LogLevel::Debug));
+        logger.debug("after");
+
+        let sinks = logger.sinks();
+        assert_eq!(sinks[0].id, console);
+        assert_eq!(sinks[0].min_level, log_level::LogLevel::Trace);
+        assert_eq!(sinks[1].min_level, log_level::LogLevel::Debug);
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.contains("after"));
+
+        logger.remove_sink(file);
+        assert!(!logger.set_min_level_for_sink(file, log_level::LogLevel::Trace));
+    }
+
+    // Logger::set_timestamp_preset()
+
+    #[test]
+    fn set_timestamp_preset_should_set_format_and_timezone() {
+        let mut logger = Logger::new_default(String::from("test"));
+
+        logger.set_timestamp_preset(timestamp::TimestampPreset::HttpDate);
+
+        assert_eq!(logger.timestamp_format, "%a, %d %b %Y %H:%M:%S GMT");
+        assert!(logger.use_utc);
+        assert!(logger
+            .get_date_time(&offset::Local::now())
+            .ends_with(" GMT"));
+    }
+
+    // Logger::set_timestamp_format()
+
+    #[test]
+    fn set_timestamp_format_should_be_used_for_lines() {
+        let path = std::env::temp_dir().join("ultimate_logger_timestamp_format.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.set_timestamp_format(String::from("%Y-%m-%dT%H:%M:%S%.6fZ"));
+        logger.set_timestamp_format(String::from("%Q"));
+        logger.info("message");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let line = contents.lines().next().unwrap();
+        let pattern = "[dddd-dd-ddTdd:dd:dd.ddddddZ] [test] [info] message";
+        assert_eq!(line.len(), pattern.len());
+        for (c, expected) in line.chars().zip(pattern.chars()) {
+            if expected == 'd' {
+                assert!(c.is_ascii_digit(), "{}", line);
+            } else {
+                assert_eq!(c, expected, "{}", line);
+            }
+        }
+    }
+
+    // Logger::set_utc()
+
+    #[test]
+    fn set_utc_should_format_time_in_utc() {
+        let mut logger = Logger::new_default(String::from("test"));
+        logger.set_timestamp_format(String::from("%H:%M %:z"));
+        let now = offset::Local::now();
+
+        logger.set_utc(true);
+        assert_eq!(
+            logger.get_date_time(&now),
+            now.with_timezone(&Utc).format("%H:%M +00:00").to_string()
+        );
+
+        logger.set_utc(false);
+        assert_eq!(
+            logger.get_date_time(&now),
+            now.format("%H:%M %:z").to_string()
+        );
+        assert!(!logger.snapshot_config().use_utc);
+    }
+
+    // Logger::set_timestamp_style()
+
+    #[test]
+    fn set_timestamp_style_should_show_seconds_since_creation() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            Box::new(buffer.clone()),
+        );
+
+        logger.set_timestamp_style(timestamp::TimestampStyle::Relative);
+        logger.set_compact_date(true);
+        logger.info("message");
+        logger.flush().unwrap();
+
+        let contents = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let line = contents.lines().next().unwrap();
+        let (timestamp, rest) = line.split_once(']').unwrap();
+        assert_eq!(rest, " [test] [info] message");
+        let seconds = timestamp
+            .strip_prefix("[+")
+            .and_then(|timestamp| timestamp.strip_suffix('s'))
+            .unwrap();
+        assert_eq!(seconds.split_once('.').unwrap().1.len(), 3, "{}", line);
+        assert!(seconds.parse::<f64>().unwrap() < 60.0, "{}", line);
+        assert_eq!(
+            logger.snapshot_config().timestamp_style,
+            timestamp::TimestampStyle::Relative
+        );
+    }
+
+    // Logger::set_burst_summary()
+
+    #[test]
+    fn burst_summary_should_suppress_after_first_k_and_summarize_on_drop() {
+        let path = std::env::temp_dir().join("ultimate_logger_burst_summary.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        logger.set_burst_summary(2, Duration::from_secs(60));
+
+        assert!(logger.error("test"));
+        assert!(logger.error("test"));
+        assert!(!logger.error("test"));
+        assert!(!logger.error("test"));
+        drop(logger);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[2].contains("occurred 2 more times"));
+    }
+
+    // Logger::reconfigure()
+
+    #[test]
+    fn reconfigure_should_replace_sinks_and_settings() {
+        let path = std::env::temp_dir().join("ultimate_logger_reconfigure.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+        let mut config = config::LoggerConfig::new(String::from("new"), log_level::LogLevel::Error);
+        config.sinks = vec![config::SinkConfig::File {
+            path: path.to_string_lossy().into_owned(),
+            min_level: None,
+        }];
+
+        logger.reconfigure(config).unwrap();
+        logger.info("info");
+        logger.error("error");
+
+        assert_eq!(logger.sinks().len(), 1);
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.ends_with("[new] [error] error\n"));
+    }
+
+    #[test]
+    fn reconfigure_with_invalid_file_should_keep_old_config() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+        let old_sinks = logger.sinks();
+        let mut config = config::LoggerConfig::new(String::from("new"), log_level::LogLevel::Error);
+        let directory = std::env::temp_dir().to_string_lossy().into_owned();
+        config.sinks.push(config::SinkConfig::File {
+            path: directory.clone(),
+            min_level: None,
+        });
+
+        let result = logger.reconfigure(config);
+
+        assert!(result.unwrap_err().to_string().contains(&directory));
+        assert_eq!(logger.sinks(), old_sinks);
+        assert_eq!(logger.name(), "test");
+        assert_eq!(logger.min_level, log_level::LogLevel::Trace);
+    }
+
+    #[test]
+    fn reconfigure_with_invalid_config_should_keep_old_config() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+        let mut config = config::LoggerConfig::new(String::new(), log_level::LogLevel::Error);
+        config.timestamp_format = String::from("%Q");
+
+        let error = logger.reconfigure(config).unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(logger.name(), "test");
+        assert_eq!(logger.timestamp_format, "%F %T%.3f");
+    }
+
+    // Logger::clone_with_name()
+
+    #[test]
+    fn clone_with_name_should_share_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_clone_with_name.log");
+        let _ = std::fs::remove_file(&path);
+        let mut database = Logger::new_to_file(
+            String::from("database"),
+            log_level::LogLevel::Info,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        database.set_show_line_number(true);
+        let mut server = database.clone_with_name(String::from("server"));
+
+        let worker = thread::spawn(move || {
+            for i in 0..100 {
+                server.info(&format!("server {}", i));
+            }
+            server
+        });
+        for i in 0..100 {
+            database.info(&format!("database {}", i));
+        }
+        let server = worker.join().unwrap();
+
+        let mut config = server.snapshot_config();
+        assert_eq!(server.sinks().len(), 1);
+        assert_eq!(config.name, "server");
+        config.name = String::from("database");
+        assert_eq!(config, database.snapshot_config());
+
+        database.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 200);
+        for (i, line) in lines.iter().enumerate() {
+            assert!(line.starts_with(&format!("{}: [", i + 1)));
+        }
+        assert!(contents.contains("] [server] [info] server 99\n"));
+        assert!(contents.contains("] [database] [info] database 99\n"));
+    }
+
+    // Logger::snapshot_config() and Logger::restore_config()
+
+    #[test]
+    fn restore_config_should_restore_snapshot_exactly() {
+        let path = std::env::temp_dir().join("ultimate_logger_snapshot_config.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new(String::from("te]st"), log_level::LogLevel::Info);
+        logger.set_append(false);
+        logger
+            .add_file_sink(&path.to_string_lossy(), log_level::LogLevel::Debug)
+            .unwrap();
+        logger.set_burst_summary(3, Duration::from_secs(1));
+        logger.set_output_format(OutputFormat::Binary);
+        logger.set_output_format(OutputFormat::Text);
+        let snapshot = logger.snapshot_config();
+
+        assert_eq!(snapshot.name, "te]st");
+        assert_eq!(snapshot.burst_summary, Some((3, Duration::from_secs(1))));
+
+        logger.info("before");
+        logger.set_show_line_number(true);
+        logger.set_color_choice(color::ColorChoice::Never);
+        logger.disable_burst_summary();
+        let console = logger.sinks()[0].id;
+        logger.remove_sink(console);
+        logger.restore_config(snapshot.clone()).unwrap();
+        logger.info("after");
+
+        assert_eq!(logger.snapshot_config(), snapshot);
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let messages: Vec<String> = contents
+            .lines()
+            .map(|line| log_record::parse_line(line).unwrap().message)
+            .collect();
+        assert_eq!(messages, vec!["before", "after"]);
+    }
+
+    #[test]
+    fn restore_config_should_keep_writer_sinks() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(buffer.clone()),
+        );
+        let snapshot = logger.snapshot_config();
+
+        assert!(snapshot.sinks.is_empty());
+
+        logger.set_show_line_number(true);
+        logger.restore_config(snapshot.clone()).unwrap();
+        logger.info("restored");
+
+        let mut config = snapshot.clone();
+        config.sinks = vec![config::SinkConfig::Console { min_level: None }];
+        logger.reconfigure(config).unwrap();
+        logger.restore_config(snapshot.clone()).unwrap();
+        logger.info("reconfigured");
+
+        assert_eq!(logger.snapshot_config(), snapshot);
+        assert_eq!(logger.sinks().len(), 1);
+        assert_eq!(logger.sinks()[0].kind, SinkKind::Writer);
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("[test] [info] restored"));
+        assert!(lines[1].ends_with("[test] [info] reconfigured"));
+    }
+
+    // Logger::log_with_fields()
+
+    #[test]
+    fn log_with_fields_should_append_quoted_pairs() {
+        let path = std::env::temp_dir().join("ultimate_logger_log_with_fields.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        logger.set_max_field_value_len(Some(9));
+
+        logger.log_with_fields(
+            log_level::LogLevel::Info,
+            "handled",
+            &[
+                ("user_id", "42"),
+                ("path", "/my files"),
+                ("long", "abcdefghijk"),
+            ],
+        );
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.ends_with(
+            "[info] handled user_id=42 path=\"/my files\" long=\"abcdefghi… (11 bytes)\"\n"
+        ));
+    }
+
+    #[test]
+    fn log_with_fields_should_write_fields_object_in_json_format() {
+        let path = std::env::temp_dir().join("ultimate_logger_log_with_fields_json.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        logger.set_output_format(OutputFormat::Json);
+
+        logger.log_with_fields(
+            log_level::LogLevel::Info,
+            "handled",
+            &[("request_id", "abc")],
+        );
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.ends_with(
+            ",\"level\":\"info\",\"fields\":{\"request_id\":\"abc\"},\"message\":\"handled\"}\n"
+        ));
+    }
+
+    // Logger::log_at()
+
+    #[test]
+    fn log_at_should_use_given_time() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(buffer.clone()),
+        );
+        let when = Local.with_ymd_and_hms(2001, 2, 3, 4, 5, 6).unwrap();
+
+        assert!(logger.log_at(log_level::LogLevel::Info, "replayed", when));
+        assert!(!logger.log_at(log_level::LogLevel::Debug, "hidden", when));
+        logger.info("now");
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "[2001-02-03 04:05:06.000] [test] [info] replayed");
+        assert!(!lines[1].starts_with("[2001-02-03"));
+    }
+
+    #[test]
+    fn log_at_should_use_given_time_for_relative_timestamps() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(buffer.clone()),
+        );
+        logger.set_timestamp_style(timestamp::TimestampStyle::Relative);
+        let now = offset::Local::now();
+
+        logger.log_at(
+            log_level::LogLevel::Info,
+            "later",
+            now + chrono::Duration::seconds(90),
+        );
+        logger.log_at(
+            log_level::LogLevel::Info,
+            "earlier",
+            now - chrono::Duration::seconds(90),
+        );
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        let seconds = |line: &str| {
+            let (timestamp, _) = line.split_once("s]").unwrap();
+            timestamp
+                .trim_start_matches("[+")
+                .trim_start_matches('[')
+                .parse::<f64>()
+                .unwrap()
+        };
+        assert!(lines[0].starts_with("[+"), "{}", lines[0]);
+        assert!(lines[0].ends_with("s] [test] [info] later"));
+        assert!((seconds(lines[0]) - 90.0).abs() < 1.0, "{}", lines[0]);
+        assert!(lines[1].starts_with("[-"), "{}", lines[1]);
+        assert!(lines[1].ends_with("s] [test] [info] earlier"));
+        assert!((seconds(lines[1]) + 90.0).abs() < 1.0, "{}", lines[1]);
+    }
+
+    // Logger::log_formatted()
+
+    #[test]
+    fn log_formatted_should_return_plain_line() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(buffer.clone()),
+        );
+
+        let line = logger.log_formatted(log_level::LogLevel::Error, "failed");
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(line.as_deref(), Some(output.trim_end()));
+        assert_eq!(
+            logger.log_formatted(log_level::LogLevel::Debug, "hidden"),
+            None
+        );
+        assert!(logger.formatted.is_none());
+    }
+
+    // Logger::log_bytes()
+
+    #[test]
+    fn log_bytes_should_write_bytes_verbatim_to_files() {
+        let path = std::env::temp_dir().join("ultimate_logger_log_bytes.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        let (_, records) = logger.capture(|logger| {
+            assert!(logger.log_bytes(log_level::LogLevel::Info, b"frame \xff\x01"));
+            assert!(!logger.log_bytes(log_level::LogLevel::Debug, b"hidden"));
+        });
+
+        logger.flush().unwrap();
+        let contents = std::fs::read(&path).unwrap();
+        assert!(contents.ends_with(b"] [test] [info] frame \xff\x01\n"));
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message, "frame \u{FFFD}\u{1}");
+    }
+
+    // Logger::log_colored()
+
+    #[test]
+    fn format_console_line_should_use_color_override_for_message() {
+        let line = Logger::format_console_line(
+            "time",
+            "test",
+            log_level::LogLevel::Info,
+            color::Theme::Default.style(log_level::LogLevel::Info),
+            MessageOptions {
+                color: Some(Color::Green),
+                ..MessageOptions::default()
+            },
+            "message",
+            color::ColorTargets::Both,
+            " ",
+        );
+
+        assert!(line.ends_with("\x1b[32mmessage\x1b[0m"));
+    }
+
+    // Logger::set_print_legend()
+
+    #[test]
+    fn legend_should_list_all_levels() {
+        let legend = Logger::get_legend(true, &color::Theme::Default.styles());
+
+        for level in log_level::LogLevel::ALL.iter() {
+            assert!(legend.contains(&format!(
+                "{}={}",
+                level,
+                color::Theme::Default.style(*level).description()
+            )));
+        }
+    }
+
+    #[test]
+    fn legend_should_be_printed_once() {
+        let mut logger = Logger::new_default(String::from("test"));
+        logger.set_print_legend(true);
+
+        logger.info("first");
+        assert!(logger.legend_printed);
+
+        logger.set_print_legend(false);
+        logger.info("second");
+        assert!(logger.legend_printed);
+    }
+
+    #[test]
+    fn legend_should_not_be_printed_without_console() {
+        let path = std::env::temp_dir().join("ultimate_logger_legend.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        logger.set_print_legend(true);
+
+        logger.info("message");
+
+        assert!(!logger.legend_printed);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(!contents.contains("Legend"));
+    }
+
+    // Logger::message_count()
+
+    #[test]
+    fn message_count_should_count_logged_messages_per_level() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+
+        logger.error("error");
+        logger.error("error");
+        logger.warning("warning");
+        logger.debug("debug");
+
+        assert_eq!(logger.message_count(log_level::LogLevel::Error), 2);
+        assert_eq!(logger.message_count(log_level::LogLevel::Warning), 1);
+        assert_eq!(logger.message_count(log_level::LogLevel::Debug), 0);
+        assert_eq!(
+            logger.get_message_counts_summary(),
+            "trace: 0, debug: 0, info: 0, warning: 1, error: 2, critical: 0"
+        );
+    }
+
+    // Logger::suppressed_count()
+
+    #[test]
+    fn suppressed_count_should_count_messages_below_min_level_until_reset() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+
+        logger.debug("debug");
+        logger.log_fmt(log_level::LogLevel::Trace, format_args!("{}", 1));
+        logger.info("info");
+        logger.disable();
+        logger.critical("critical");
+
+        assert_eq!(logger.suppressed_count(), 3);
+        assert_eq!(logger.message_count(log_level::LogLevel::Info), 1);
+
+        logger.reset_counters();
+
+        assert_eq!(logger.suppressed_count(), 0);
+        assert_eq!(logger.message_count(log_level::LogLevel::Info), 0);
+    }
+
+    #[test]
+    fn suppressed_count_should_count_filtered_messages() {
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(SharedBuffer::default()),
+        );
+        logger.set_message_filter(Some(String::from("db")));
+
+        assert!(!logger.info("http: listening"));
+        assert_eq!(logger.suppressed_count(), 1);
+    }
+
+    #[test]
+    fn suppressed_count_should_count_deduplicated_messages() {
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(SharedBuffer::default()),
+        );
+        logger.set_dedup_window(Some(Duration::from_secs(60)));
+
+        assert!(logger.info("x"));
+        assert!(!logger.info("x"));
+        assert_eq!(logger.suppressed_count(), 1);
+    }
+
+    #[test]
+    fn suppressed_count_should_count_messages_over_burst_limit() {
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(SharedBuffer::default()),
+        );
+        logger.set_burst_summary(1, Duration::from_secs(60));
+
+        assert!(logger.info("x"));
+        assert!(!logger.info("x"));
+        assert!(!logger.info("x"));
+        assert_eq!(logger.suppressed_count(), 2);
+    }
+
+    // Logger::install_panic_hook()
+
+    #[test]
+    fn log_panic_should_log_panic_and_counts() {
+        let path = std::env::temp_dir().join("ultimate_logger_panic.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        logger.error("error");
+
+        logger.log_panic("panicked at src/main.rs:1:1:\nboom");
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].ends_with("[critical] panicked at src/main.rs:1:1:"));
+        assert!(lines[2].ends_with("[critical] boom"));
+        assert!(lines[3].ends_with(
+            "[critical] trace: 0, debug: 0, info: 0, warning: 0, error: 1, critical: 1 before panic"
+        ));
+    }
+
+    // Logger::install_flush_on_termination()
+
+    #[cfg(all(unix, feature = "signals"))]
+    #[test]
+    fn log_termination_should_log_shutdown_and_flush() {
+        let path = std::env::temp_dir().join("ultimate_logger_termination.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        logger.warning("warning");
+
+        logger.log_termination(true);
+        logger.log_termination(false);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains("[info] shutdown name=test"));
+    }
+
+    #[cfg(all(unix, feature = "signals"))]
+    #[test]
+    fn install_flush_on_termination_should_register_handlers() {
+        let logger = Arc::new(Mutex::new(Logger::new_default(String::from("test"))));
+
+        assert!(Logger::install_flush_on_termination(logger, false).is_ok());
+    }
+
+    // Logger::would_log()
+
+    #[test]
+    fn would_log_should_check_boundary_levels() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Warning);
+
+        assert!(!logger.would_log(log_level::LogLevel::Info));
+        assert!(logger.would_log(log_level::LogLevel::Warning));
+        assert!(logger.would_log(log_level::LogLevel::Critical));
+
+        logger.set_min_level(log_level::LogLevel::Trace);
+        assert!(logger.would_log(log_level::LogLevel::Trace));
+
+        logger.set_min_level(log_level::LogLevel::Critical);
+        assert!(!logger.would_log(log_level::LogLevel::Error));
+        assert!(logger.would_log(log_level::LogLevel::Critical));
+
+        logger.disable();
+        assert!(!logger.would_log(log_level::LogLevel::Critical));
+    }
+
+    // Logger::set_min_level() and Logger::min_level()
+
+    #[test]
+    fn set_min_level_should_apply_to_next_message() {
+        let path = std::env::temp_dir().join("ultimate_logger_set_min_level.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        assert!(logger.debug("before"));
+        logger.set_min_level(log_level::LogLevel::Warning);
+        assert_eq!(logger.min_level(), log_level::LogLevel::Warning);
+        assert!(!logger.info("suppressed"));
+        assert!(logger.warning("logged"));
+        logger.set_min_level(log_level::LogLevel::Trace);
+        assert!(logger.trace("restored"));
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with("[debug] before"));
+        assert!(lines[1].ends_with("[warning] logged"));
+        assert!(lines[2].ends_with("[trace] restored"));
+    }
+
+    // Logger::with_min_level()
+
+    #[test]
+    fn with_min_level_should_restore_min_level() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+
+        let logged =
+            logger.with_min_level(log_level::LogLevel::Trace, |logger| logger.trace("trace"));
+
+        assert!(logged);
+        assert_eq!(logger.min_level, log_level::LogLevel::Info);
+        assert!(!logger.trace("trace"));
+    }
+
+    #[test]
+    fn with_min_level_should_restore_min_level_on_panic() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            logger.with_min_level(log_level::LogLevel::Critical, |_| panic!("test"))
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(logger.min_level, log_level::LogLevel::Info);
+    }
+
+    // Logger::with_suppressed()
+
+    #[test]
+    fn with_suppressed_should_log_nothing_and_restore_afterwards() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(buffer.clone()),
+        );
+
+        let logged = logger.with_suppressed(|logger| {
+            logger.set_min_level(log_level::LogLevel::Trace);
+            logger.critical("hidden")
+        });
+        logger.info("shown");
+
+        assert!(!logged);
+        assert!(logger.is_enabled());
+        assert_eq!(logger.min_level, log_level::LogLevel::Info);
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.ends_with("[info] shown\n"));
+    }
+
+    #[test]
+    fn with_suppressed_should_restore_on_panic() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            logger.with_suppressed(|_| panic!("test"))
+        }));
+
+        assert!(result.is_err());
+        assert!(logger.is_enabled());
+        assert_eq!(logger.min_level, log_level::LogLevel::Info);
+    }
+
+    // Logger::with_scope()
+
+    #[test]
+    fn with_scope_should_write_nested_scopes() {
+        let path = std::env::temp_dir().join("ultimate_logger_scope.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        logger.set_show_scope(true);
+
+        logger.with_scope("load", |logger| {
+            logger.with_scope("parse", |logger| logger.info("inner"));
+            logger.info("outer");
+        });
+        logger.info("none");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(lines[0].ends_with("[info] [load > parse] inner"));
+        assert!(lines[1].ends_with("[info] [load] outer"));
+        assert!(lines[2].ends_with("[info] none"));
+        assert!(logger.scopes.is_empty());
+    }
+
+    #[test]
+    fn with_scope_should_leave_scope_on_panic() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            logger.with_scope("scope", |_| panic!("test"))
+        }));
+
+        assert!(result.is_err());
+        assert!(logger.scopes.is_empty());
+    }
+
+    // Logger::capture()
+
+    #[test]
+    fn capture_should_record_messages_and_still_write_them() {
+        let path = std::env::temp_dir().join("ultimate_logger_capture.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.info("before");
+        let ((value, inner), outer) = logger.capture(|logger| {
+            logger.debug("too low");
+            logger.warning("outer");
+            logger.capture(|logger| {
+                logger.log_with_trace_id(log_level::LogLevel::Error, "inner", "abc");
+                7
+            })
+        });
+        logger.info("after");
+
+        assert_eq!(value, 7);
+        assert_eq!(inner.len(), 1);
+        assert_eq!(inner[0].logger, "test");
+        assert_eq!(inner[0].level, log_level::LogLevel::Error);
+        assert_eq!(inner[0].trace_id.as_deref(), Some("abc"));
+        assert_eq!(inner[0].message, "inner");
+        let messages: Vec<&str> = outer.iter().map(|record| record.message.as_str()).collect();
+        assert_eq!(messages, vec!["outer", "inner"]);
+        assert!(logger.captures.is_empty());
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 4);
+    }
+
+    #[test]
+    fn capture_should_stop_on_panic() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            logger.capture(|logger| logger.capture(|_| panic!("test")))
+        }));
+
+        assert!(result.is_err());
+        assert!(logger.captures.is_empty());
+    }
+
+    // Logger::log_json_value()
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn log_json_value_should_wrap_scalars() {
+        let path = std::env::temp_dir().join("ultimate_logger_json_value.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.log_json_value(log_level::LogLevel::Info, &serde_json::json!({"a": [1, 2]}));
+        logger.log_json_value(log_level::LogLevel::Info, &serde_json::json!("text"));
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(lines[0].ends_with(r#"[info] {"a":[1,2]}"#));
+        assert!(lines[1].ends_with(r#"[info] {"value":"text"}"#));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn log_json_value_should_embed_payload_in_json_format() {
+        let path = std::env::temp_dir().join("ultimate_logger_json_value_payload.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.set_output_format(OutputFormat::Json);
+        logger.log_json_value(log_level::LogLevel::Info, &serde_json::json!({"a": [1, 2]}));
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let record: serde_json::Value = serde_json::from_str(contents.trim_end()).unwrap();
+        assert_eq!(record["payload"], serde_json::json!({"a": [1, 2]}));
+        assert_eq!(record["message"], r#"{"a":[1,2]}"#);
+    }
+
+    // Logger::set_line_number_reset_on_rotation()
+
+    #[test]
+    fn line_numbers_should_reset_or_continue_on_rotation() {
+        for reset in [true, false] {
+            let path = std::env::temp_dir()
+                .join(format!("ultimate_logger_line_number_reset_{}.log", reset));
+            let rotated = std::env::temp_dir()
+                .join(format!("ultimate_logger_line_number_reset_{}.1.log", reset));
+            let _ = std::fs::remove_file(&path);
+            let _ = std::fs::remove_file(&rotated);
+            let mut logger = Logger::new_to_file(
+                String::from("test"),
+                log_level::LogLevel::Trace,
+                path.to_string_lossy().into_owned(),
+                false,
+            );
+            logger.set_show_line_number(true);
+            logger.set_line_number_reset_on_rotation(reset);
+            // Every line is about 50 bytes long, so the third line goes to a new file.
+            logger.set_size_rotation(120, 1);
+
+            logger.info("first");
+            logger.info("second");
+            logger.info("third");
+            logger.flush().unwrap();
+
+            let rotated_contents = std::fs::read_to_string(&rotated).unwrap();
+            let rotated_lines: Vec<&str> = rotated_contents.lines().collect();
+            assert_eq!(rotated_lines.len(), 2);
+            assert!(rotated_lines[1].starts_with("2: "));
+            let contents = std::fs::read_to_string(&path).unwrap();
+            let expected = if reset { "1: " } else { "3: " };
+            assert!(contents.starts_with(expected), "{}", contents);
+            assert!(contents.ends_with("[info] third\n"));
+            assert_eq!(
+                logger.snapshot_config().line_number_reset_on_rotation,
+                reset
+            );
+        }
+    }
+
+    // Logger::set_size_rotation()
+
+    #[test]
+    fn set_size_rotation_with_zero_files_should_empty_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_size_rotation.log");
+        let rotated = std::env::temp_dir().join("ultimate_logger_size_rotation.1.log");
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&rotated);
+
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        logger.set_size_rotation(60, 0);
+        logger.info("first");
+        logger.info("second");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.ends_with("[info] second\n"));
+        assert!(!rotated.exists());
+
+        logger.disable_size_rotation();
+        logger.info("third");
+        logger.flush().unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+    }
+
+    // Logger::set_start_banner()
+
+    #[test]
+    fn start_banner_should_be_written_once_per_opened_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_start_banner.log");
+        std::fs::write(&path, "old line\n").unwrap();
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+
+        logger.set_start_banner(true);
+        logger
+            .add_file_sink(&path.to_string_lossy(), log_level::LogLevel::Trace)
+            .unwrap();
+        logger.reconfigure(logger.snapshot_config()).unwrap();
+        logger.info("new line");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "old line");
+        assert!(lines[1].starts_with("===== test started at "));
+        assert!(lines[1].ends_with(&format!(" pid={} =====", std::process::id())));
+        assert!(lines[2].ends_with("[info] new line"));
+    }
+
+    // Logger::set_append()
+
+    #[test]
+    fn set_append_false_should_truncate_next_opened_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_set_append.log");
+        std::fs::write(&path, "old line\n").unwrap();
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+
+        logger.set_append(false);
+        logger
+            .add_file_sink(&path.to_string_lossy(), log_level::LogLevel::Trace)
+            .unwrap();
+        logger.info("new line");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.ends_with("[info] new line\n"));
+    }
+
+    #[test]
+    fn set_append_true_should_keep_existing_lines() {
+        let path = std::env::temp_dir().join("ultimate_logger_set_append_true.log");
+        std::fs::write(&path, "old line\n").unwrap();
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+
+        logger
+            .add_file_sink(&path.to_string_lossy(), log_level::LogLevel::Trace)
+            .unwrap();
+        logger.info("new line");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("old line\n"));
+        assert_eq!(contents.lines().count(), 2);
+    }
+
+    // Logger::disable()
+
+    #[test]
+    fn disable_should_suppress_every_message() {
+        let path = std::env::temp_dir().join("ultimate_logger_disable.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.disable();
+        assert!(!logger.critical("critical"));
+        assert!(!logger.log_fmt(log_level::LogLevel::Error, format_args!("{}", 1)));
+        assert!(!logger.snapshot_config().enabled);
+
+        logger.enable();
+        assert!(logger.is_enabled());
+        assert!(logger.info("info"));
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.ends_with("[info] info\n"));
+        assert_eq!(logger.message_count(log_level::LogLevel::Critical), 0);
+    }
+
+    // Logger::set_split_streams()
+
+    #[test]
+    fn warnings_and_errors_should_go_to_stderr_unless_disabled() {
+        let mut logger = Logger::new_default(String::from("test"));
+
+        assert!(!logger.console_to_stderr(log_level::LogLevel::Info));
+        assert!(logger.console_to_stderr(log_level::LogLevel::Warning));
+        assert!(logger.console_to_stderr(log_level::LogLevel::Critical));
+
+        logger.set_split_streams(false);
+
+        assert!(!logger.console_to_stderr(log_level::LogLevel::Critical));
+        assert!(!logger.snapshot_config().split_streams);
+    }
+
+    // Logger::set_console_writer()
+
+    #[test]
+    fn console_writer_should_get_console_output() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_default(String::from("test"));
+        logger.set_color(false);
+        logger.set_console_writer(Box::new(buffer.clone()));
+
+        logger.info("first");
+        logger.error("second");
+        logger.reset_console_writer();
+        logger.info("third");
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("] [test] [info] first"));
+        assert!(lines[1].ends_with("] [test] [error] second"));
+    }
+
+    // Logger::set_color_scheme()
+
+    #[test]
+    fn color_scheme_should_style_level_and_message() {
+        let mut logger = Logger::new_default(String::from("test"));
+        logger.set_color_scheme(
+            color::ColorScheme::default().with_color(log_level::LogLevel::Trace, Color::Cyan),
+        );
+
+        let style = logger.color_scheme().style(log_level::LogLevel::Trace);
+        assert_eq!(style.description(), "cyan");
+        assert_eq!(
+            Logger::get_colored_level_name(style, log_level::LogLevel::Trace).to_string(),
+            "trace".cyan().to_string()
+        );
+        assert_eq!(
+            Logger::get_colored_message(style, "message").to_string(),
+            "message".cyan().to_string()
+        );
+        assert_eq!(
+            logger.color_scheme().style(log_level::LogLevel::Error),
+            color::Theme::Default.style(log_level::LogLevel::Error)
+        );
+    }
+
+    // Logger::set_color()
+
+    #[test]
+    fn set_color_false_should_disable_color() {
+        let mut logger = Logger::new_default(String::from("test"));
+        logger.set_color(true);
+        assert!(logger.use_color());
+
+        logger.set_color(false);
+
+        assert!(!logger.use_color());
+        assert_eq!(
+            logger.get_color_targets(log_level::LogLevel::Error, MessageOptions::default()),
+            color::ColorTargets::Neither
+        );
+        assert_eq!(
+            logger.snapshot_config().color_choice,
+            color::ColorChoice::Never
+        );
+    }
+
+    // Logger::set_color_choice()
+
+    #[test]
+    fn color_choice_should_only_apply_to_its_logger() {
+        let colored_buffer = SharedBuffer::default();
+        let plain_buffer = SharedBuffer::default();
+        let mut colored_logger = Logger::new_default(String::from("colored"));
+        let mut plain_logger = Logger::new_default(String::from("plain"));
+        colored_logger.set_console_writer(Box::new(colored_buffer.clone()));
+        plain_logger.set_console_writer(Box::new(plain_buffer.clone()));
+
+        colored_logger.set_color_choice(color::ColorChoice::Always);
+        plain_logger.set_color_choice(color::ColorChoice::Never);
+        colored_logger.error("colored");
+        plain_logger.error("plain");
+
+        let colored_output = String::from_utf8(colored_buffer.0.lock().unwrap().clone()).unwrap();
+        let plain_output = String::from_utf8(plain_buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(
+            colored_output.ends_with("] [colored] [\x1b[31merror\x1b[0m] \x1b[31mcolored\x1b[0m\n")
+        );
+        assert!(plain_output.ends_with("] [plain] [error] plain\n"));
+    }
+
+    #[test]
+    fn format_console_line_without_color_should_be_plain() {
+        let mut logger = Logger::new_default(String::from("test"));
+        logger.set_color_choice(color::ColorChoice::Never);
+
+        let line = Logger::format_console_line(
+            "2020-05-01 12:00:00.000",
+            "test",
+            log_level::LogLevel::Critical,
+            color::Theme::Default.style(log_level::LogLevel::Critical),
+            MessageOptions {
+                trace_id: Some("abc"),
+                color: Some(Color::Green),
+                ..MessageOptions::default()
+            },
+            "message",
+            logger.get_color_targets(log_level::LogLevel::Critical, MessageOptions::default()),
+            " ",
+        );
+
+        assert_eq!(
+            line,
+            "[2020-05-01 12:00:00.000] [test] [critical] [trace=abc] message"
+        );
+        assert!(!Logger::get_legend(logger.use_color(), &logger.level_styles).contains('\x1b'));
+    }
+
+    // Escaping of the name
+
+    #[test]
+    fn escaped_name_should_not_forge_log_level() {
+        let path = std::env::temp_dir().join("ultimate_logger_escape_name.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("evil] [critical"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.info("message");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let record = log_record::parse_line(&contents).unwrap();
+        assert_eq!(record.level, log_level::LogLevel::Info);
+        assert_eq!(record.logger, "evil\\] \\[critical");
     }
 
+    // Logger::enable_heartbeat()
+
     #[test]
-    fn new_logger_should_write_to_console() {
-        let logger = Logger::new(String::from("test"), log_level::LogLevel::Warning);
+    fn heartbeat_should_be_logged_when_idle() {
+        let path = std::env::temp_dir().join("ultimate_logger_heartbeat.log");
+        let _ = std::fs::remove_file(&path);
+        let logger = Arc::new(Mutex::new(Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        )));
 
-        assert_eq!(logger.write_to_console, true);
+        Logger::enable_heartbeat(
+            &logger,
+            log_level::LogLevel::Info,
+            Duration::from_millis(20),
+        );
+        thread::sleep(Duration::from_millis(100));
+        logger.lock().unwrap().disable_heartbeat();
+        let heartbeats = logger
+            .lock()
+            .unwrap()
+            .message_count(log_level::LogLevel::Info);
+
+        assert!(heartbeats >= 1);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents
+            .lines()
+            .all(|line| line.ends_with("[info] heartbeat")));
     }
 
     #[test]
-    fn new_logger_should_not_write_to_file() {
-        let logger = Logger::new(String::from("test"), log_level::LogLevel::Warning);
+    fn heartbeat_should_not_keep_logger_alive() {
+        let logger = Arc::new(Mutex::new(Logger::new(
+            String::from("test"),
+            log_level::LogLevel::Critical,
+        )));
+
+        Logger::enable_heartbeat(
+            &logger,
+            log_level::LogLevel::Info,
+            Duration::from_millis(10),
+        );
+        let weak_logger = Arc::downgrade(&logger);
+        drop(logger);
+
+        // The heartbeat thread may briefly hold a strong reference while it checks the logger.
+        let start = Instant::now();
+        while weak_logger.strong_count() > 0 && start.elapsed() < Duration::from_secs(1) {
+            thread::sleep(Duration::from_millis(1));
+        }
 
-        assert_eq!(logger.write_to_file, false);
+        assert_eq!(weak_logger.strong_count(), 0);
     }
 
-    // Logger::new_default()
+    // Logger::log_fmt()
 
     #[test]
-    fn new_default_logger_should_have_level_trace() {
-        let logger = Logger::new_default(String::from("test"));
+    fn log_fmt_should_keep_format_specs() {
+        let path = std::env::temp_dir().join("ultimate_logger_log_fmt.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
 
-        assert_eq!(logger.min_level, log_level::LogLevel::Trace);
+        logger.log_fmt(log_level::LogLevel::Info, format_args!("{:>8.2}", 1.23456));
+        logger.log_fmt(
+            log_level::LogLevel::Info,
+            format_args!("{:*<6}|{:#06x}|{:+}", "ab", 255, 3),
+        );
+        let logged = logger.log_fmt(log_level::LogLevel::Debug, format_args!("{}", 1));
+
+        assert!(!logged);
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("[info]     1.23"));
+        assert!(lines[1].ends_with("[info] ab****|0x00ff|+3"));
     }
 
-    // Logger::new_to_file()
+    // Logger::log_error() and Logger::log_error_with_level()
 
     #[test]
-    fn new_logger_to_file_should_enable_write_to_file() {
-        let logger = Logger::new_to_file(
+    fn log_error_should_log_source_chain() {
+        #[derive(Debug)]
+        struct Wrapped(&'static str, Option<Box<Wrapped>>);
+
+        impl fmt::Display for Wrapped {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl std::error::Error for Wrapped {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                self.1
+                    .as_deref()
+                    .map(|source| source as &(dyn std::error::Error + 'static))
+            }
+        }
+
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
             String::from("test"),
-            log_level::LogLevel::Warning,
-            String::from("test.log"),
+            log_level::LogLevel::Info,
+            Box::new(buffer.clone()),
+        );
+        let error = Wrapped(
+            "loading config",
+            Some(Box::new(Wrapped(
+                "reading file",
+                Some(Box::new(Wrapped("permission denied", None))),
+            ))),
+        );
+
+        assert!(logger.log_error(&error));
+        assert!(
+            logger.log_error_with_level(log_level::LogLevel::Warning, &Wrapped("retrying", None))
+        );
+        assert!(!logger.log_error_with_level(log_level::LogLevel::Debug, &error));
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].ends_with("] [test] [error] loading config"));
+        assert!(lines[1].ends_with("] [test] [error] caused by: reading file"));
+        assert!(lines[2].ends_with("] [test] [error] caused by: permission denied"));
+        assert!(lines[3].ends_with("] [test] [warning] retrying"));
+    }
+
+    // Logger::log_and_return()
+
+    #[test]
+    fn log_and_return_should_log_and_return_value() {
+        let path = std::env::temp_dir().join("ultimate_logger_log_and_return.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            path.to_string_lossy().into_owned(),
             false,
         );
 
-        assert_eq!(logger.write_to_file, true);
+        let value = logger.log_and_return(log_level::LogLevel::Info, String::from("value"));
+        let hidden = logger.log_and_return(log_level::LogLevel::Debug, 7);
+
+        assert_eq!(value, "value");
+        assert_eq!(hidden, 7);
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.ends_with("[info] value\n"));
     }
 
+    // Logger::set_prefix_message_separator()
+
     #[test]
-    fn new_logger_to_file_which_also_writes_to_console_should_enable_write_to_console() {
-        let logger = Logger::new_to_file(
+    fn set_prefix_message_separator_should_change_separator() {
+        let path = std::env::temp_dir().join("ultimate_logger_separator.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
             String::from("test"),
-            log_level::LogLevel::Warning,
-            String::from("test.log"),
-            true,
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
         );
 
-        assert_eq!(logger.write_to_console, true);
+        logger.set_prefix_message_separator("\t");
+        logger.info("message");
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.ends_with("[test] [info]\tmessage\n"));
+        assert_eq!(
+            Logger::format_console_line(
+                "time",
+                "test",
+                log_level::LogLevel::Info,
+                color::Theme::Default.style(log_level::LogLevel::Info),
+                MessageOptions::default(),
+                "message",
+                color::ColorTargets::Neither,
+                ": ",
+            ),
+            "[time] [test] [info]: message"
+        );
     }
 
+    // Logger::new_to_sqlite()
+
+    #[cfg(feature = "sqlite")]
     #[test]
-    fn new_logger_to_file_which_does_not_write_to_console_should_disable_write_to_console() {
-        let logger = Logger::new_to_file(
+    fn new_to_sqlite_should_insert_rows() {
+        let connection = rusqlite::Connection::open_in_memory().unwrap();
+        let mut logger = Logger::new_to_sqlite(
             String::from("test"),
-            log_level::LogLevel::Warning,
-            String::from("test.log"),
+            log_level::LogLevel::Info,
+            connection,
+            "logs",
+        )
+        .unwrap();
+
+        logger.debug("debug");
+        logger.log_with_trace_id(log_level::LogLevel::Warning, "first\nsecond", "abc");
+
+        let connection = logger.sqlite_connection().unwrap();
+        let (logger_name, level, trace_id, message): (String, String, Option<String>, String) =
+            connection
+                .query_row(
+                    "SELECT logger, level, trace_id, message FROM logs",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )
+                .unwrap();
+        assert_eq!(logger_name, "test");
+        assert_eq!(level, "warning");
+        assert_eq!(trace_id, Some(String::from("abc")));
+        assert_eq!(message, "first\nsecond");
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn new_to_sqlite_should_store_fields_as_json() {
+        let connection = rusqlite::Connection::open_in_memory().unwrap();
+        let mut logger = Logger::new_to_sqlite(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            connection,
+            "logs",
+        )
+        .unwrap();
+
+        logger.log_with_fields(log_level::LogLevel::Info, "handled", &[("user_id", "42")]);
+        logger.info("plain");
+
+        let connection = logger.sqlite_connection().unwrap();
+        let mut statement = connection
+            .prepare("SELECT message, fields FROM logs ORDER BY id")
+            .unwrap();
+        let rows: Vec<(String, Option<String>)> = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    String::from("handled"),
+                    Some(String::from("{\"user_id\":\"42\"}"))
+                ),
+                (String::from("plain"), None),
+            ]
+        );
+    }
+
+    // Logger::pipe_reader()
+
+    #[test]
+    fn pipe_reader_should_log_every_line_with_label() {
+        let path = std::env::temp_dir().join("ultimate_logger_pipe_reader.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
             false,
         );
 
-        assert_eq!(logger.write_to_console, false);
+        let lines = logger
+            .pipe_reader(
+                &b"first\r\nsec\xffond\nthird"[..],
+                log_level::LogLevel::Warning,
+                "child",
+            )
+            .unwrap();
+
+        assert_eq!(lines, 3);
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert!(lines[0].ends_with("[warning] [child] first"));
+        assert!(lines[1].ends_with("[warning] [child] sec\u{fffd}ond"));
+        assert!(lines[2].ends_with("[warning] [child] third"));
     }
 
     #[test]
-    fn new_logger_to_file_should_have_correct_min_level() {
-        let logger = Logger::new_to_file(
+    fn spawn_pipe_reader_should_log_on_thread() {
+        let logger = Arc::new(Mutex::new(Logger::new(
             String::from("test"),
-            log_level::LogLevel::Warning,
-            String::from("test.log"),
+            log_level::LogLevel::Critical,
+        )));
+
+        let handle = Logger::spawn_pipe_reader(
+            &logger,
+            io::Cursor::new(b"a\nb\n".to_vec()),
+            log_level::LogLevel::Critical,
+            "child",
+        );
+
+        assert_eq!(handle.join().unwrap().unwrap(), 2);
+        assert_eq!(
+            logger
+                .lock()
+                .unwrap()
+                .message_count(log_level::LogLevel::Critical),
+            2
+        );
+    }
+
+    // Logger::log_startup() and Logger::log_shutdown()
+
+    #[test]
+    fn log_startup_and_shutdown_should_write_bookend_lines() {
+        let path = std::env::temp_dir().join("ultimate_logger_lifecycle.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
             false,
         );
 
-        assert_eq!(logger.min_level, log_level::LogLevel::Warning);
+        logger.log_startup();
+        logger.error("error");
+        logger.log_shutdown();
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        let pid = format!("pid={}", std::process::id());
+        assert!(lines[0].contains(&format!("[info] startup name=test {} time=", pid)));
+        assert!(lines[0].contains(" since_start=0."));
+        assert!(lines[0].ends_with('s'));
+        assert!(lines[2].contains(&format!("[info] shutdown name=test {} uptime=", pid)));
+        assert!(lines[2].ends_with("s trace=0 debug=0 info=1 warning=0 error=1 critical=0"));
     }
 
-    // Logger::get_colored_level_name()
+    // Logger::set_color_targets()
 
     #[test]
-    fn get_colored_level_name_should_return_correct_string_for_critical() {
-        let level_name = Logger::get_colored_level_name(log_level::LogLevel::Critical);
+    fn color_targets_should_choose_colored_parts() {
+        let mut logger = Logger::new_default(String::from("test"));
+        logger.set_color_choice(color::ColorChoice::Always);
+        logger.set_color_targets(log_level::LogLevel::Error, color::ColorTargets::Message);
+        let format = |color_targets| {
+            Logger::format_console_line(
+                "time",
+                "test",
+                log_level::LogLevel::Error,
+                color::Theme::Default.style(log_level::LogLevel::Error),
+                MessageOptions::default(),
+                "message",
+                color_targets,
+                " ",
+            )
+        };
 
-        assert_eq!(level_name, "critical".red().bold());
+        let line =
+            format(logger.get_color_targets(log_level::LogLevel::Error, MessageOptions::default()));
+
+        assert_eq!(line, "[time] [test] [error] \x1b[31mmessage\x1b[0m");
+        assert_eq!(
+            format(color::ColorTargets::Level),
+            "[time] [test] [\x1b[31merror\x1b[0m] message"
+        );
     }
 
-    // Logger::get_colored_message()
+    #[test]
+    fn color_override_should_color_message_even_if_targets_exclude_it() {
+        let mut logger = Logger::new_default(String::from("test"));
+        logger.set_color_choice(color::ColorChoice::Always);
+        logger.set_color_targets(log_level::LogLevel::Info, color::ColorTargets::Neither);
+
+        let color_targets = logger.get_color_targets(
+            log_level::LogLevel::Info,
+            MessageOptions {
+                color: Some(Color::Green),
+                ..MessageOptions::default()
+            },
+        );
+
+        assert_eq!(color_targets, color::ColorTargets::Message);
+    }
+
+    // Logger::set_theme() and Logger::set_level_style()
 
     #[test]
-    fn get_colored_message_should_return_correct_string_for_critical() {
-        let message = Logger::get_colored_message(log_level::LogLevel::Critical, "test");
+    fn theme_should_be_overridable_per_level() {
+        let mut logger = Logger::new_default(String::from("test"));
+        logger.set_theme(color::Theme::Monochrome);
+        logger.set_level_style(
+            log_level::LogLevel::Error,
+            color::LevelStyle {
+                color: Some(Color::Cyan),
+                ..color::LevelStyle::default()
+            },
+        );
+        let format = |level| {
+            Logger::format_console_line(
+                "time",
+                "test",
+                level,
+                logger.level_styles[level as usize],
+                MessageOptions::default(),
+                "message",
+                color::ColorTargets::Level,
+                " ",
+            )
+        };
 
-        assert_eq!(message, "test".red().bold());
+        assert_eq!(
+            format(log_level::LogLevel::Warning),
+            "[time] [test] [\x1b[4mwarning\x1b[0m] message"
+        );
+        assert_eq!(
+            format(log_level::LogLevel::Error),
+            "[time] [test] [\x1b[36merror\x1b[0m] message"
+        );
+        assert_eq!(
+            logger.snapshot_config().level_styles[log_level::LogLevel::Critical as usize],
+            color::Theme::Monochrome.style(log_level::LogLevel::Critical)
+        );
     }
 
-    // Logger::log()
+    // Logger::set_pipe_atomic_policy()
 
+    #[cfg(unix)]
     #[test]
-    fn log_should_return_false_if_level_is_below_min_level() {
-        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Error);
+    fn discard_policy_should_drop_oversized_pipe_writes() {
+        use std::io::Read;
 
-        let result = logger.log(log_level::LogLevel::Debug, "test");
+        let path = std::env::temp_dir().join("ultimate_logger_pipe_policy.fifo");
+        let _ = std::fs::remove_file(&path);
+        let status = std::process::Command::new("mkfifo")
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let reader_path = path.clone();
+        let reader = thread::spawn(move || {
+            let mut contents = String::new();
+            File::open(reader_path)
+                .unwrap()
+                .read_to_string(&mut contents)
+                .unwrap();
+            contents
+        });
+
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
+        logger.set_pipe_atomic_policy(PipeAtomicPolicy::Discard);
+        logger
+            .add_file_sink(&path.to_string_lossy(), log_level::LogLevel::Trace)
+            .unwrap();
+        logger.critical(&"x".repeat(log_file::PIPE_BUF));
+        logger.critical("small");
+        let discarded = logger.discarded_pipe_writes();
+        drop(logger);
 
-        assert_eq!(result, false);
+        let contents = reader.join().unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(discarded, 1);
+        assert_eq!(contents.lines().count(), 1);
+        assert!(contents.ends_with("[critical] small\n"));
     }
 
+    // Logger::set_special_file_policy()
+
+    #[cfg(unix)]
     #[test]
-    fn log_should_return_true_if_level_is_above_min_level() {
-        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Debug);
+    fn special_file_policy_should_reject_devices_only_when_asked() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Trace);
 
-        let result = logger.log(log_level::LogLevel::Error, "test");
+        assert!(logger
+            .add_file_sink("/dev/null", log_level::LogLevel::Trace)
+            .is_ok());
 
-        assert_eq!(result, true);
+        logger.set_special_file_policy(SpecialFilePolicy::Reject);
+        let error = logger
+            .add_file_sink("/dev/null", log_level::LogLevel::Trace)
+            .unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(error.to_string().contains("character device"));
+        assert_eq!(logger.sinks().len(), 2);
+
+        let path = std::env::temp_dir().join("ultimate_logger_special_file_policy.log");
+        assert!(logger
+            .add_file_sink(&path.to_string_lossy(), log_level::LogLevel::Trace)
+            .is_ok());
     }
 
+    // Logger::set_flush_on_critical()
+
     #[test]
-    fn log_should_return_true_if_level_is_equal_to_min_level() {
-        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Error);
+    fn critical_message_should_be_persisted_without_explicit_flush() {
+        let path = std::env::temp_dir().join("ultimate_logger_flush_on_critical.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
 
-        let result = logger.log(log_level::LogLevel::Error, "test");
+        logger.critical("crashing");
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.ends_with("[critical] crashing\n"));
+        assert!(logger.snapshot_config().flush_on_critical);
+        drop(logger);
+    }
+
+    // Logger::set_message_filter()
+
+    #[test]
+    fn message_filter_should_only_log_matching_messages() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Info,
+            Box::new(buffer.clone()),
+        );
+
+        logger.set_message_filter(Some(String::from("db")));
+        assert!(logger.info("db: connected"));
+        assert!(!logger.info("http: listening"));
+        assert!(!logger.debug("db: query"));
+        assert_eq!(logger.suppressed_count(), 2);
+
+        let config = logger.snapshot_config();
+        assert_eq!(
+            config.message_filter,
+            Some(filter::MessageFilter::Contains(String::from("db")))
+        );
+
+        logger.set_message_filter(None);
+        assert!(logger.info("http: listening"));
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("[info] db: connected"));
+        assert!(lines[1].ends_with("[info] http: listening"));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn message_filter_regex_should_only_log_matching_messages() {
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+
+        logger.set_message_filter_regex(Some(r"^user \d+")).unwrap();
+        assert!(!logger.info("user x"));
+        assert!(logger.set_message_filter_regex(Some("(")).is_err());
+        assert_eq!(
+            logger.message_filter.as_ref().unwrap().pattern(),
+            r"^user \d+"
+        );
+    }
+
+    // Logger::set_hook()
+
+    #[test]
+    fn hook_should_get_logged_messages_and_survive_panics() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut logger = Logger::new(String::from("test"), log_level::LogLevel::Info);
+        let hook_seen = Arc::clone(&seen);
+        logger.set_hook(Box::new(move |level, message| {
+            hook_seen.lock().unwrap().push((level, message.to_string()));
+
+            if message == "panic" {
+                panic!("hook panicked");
+            }
+        }));
+
+        assert!(logger.info("first"));
+        assert!(!logger.debug("hidden"));
+        assert!(logger.error("panic"));
+        assert!(logger.warning("last"));
+        logger.remove_hook();
+        logger.info("after");
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                (log_level::LogLevel::Info, String::from("first")),
+                (log_level::LogLevel::Error, String::from("panic")),
+                (log_level::LogLevel::Warning, String::from("last")),
+            ]
+        );
+    }
+
+    // Logger::set_flush_each()
+
+    #[test]
+    fn flush_each_should_persist_every_message() {
+        let path = std::env::temp_dir().join("ultimate_logger_flush_each.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.set_flush_each(true);
+        logger.info("first");
+        logger.debug("second");
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+        assert!(contents.ends_with("[debug] second\n"));
+        assert!(logger.snapshot_config().flush_each);
+        drop(logger);
+    }
+
+    // Logger::flush()
+
+    #[test]
+    fn file_writes_should_be_buffered_until_flush_or_drop() {
+        let path = std::env::temp_dir().join("ultimate_logger_flush.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.debug("first");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "");
+
+        logger.flush().unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .ends_with("[debug] first\n"));
+
+        logger.debug("second");
+        drop(logger);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .ends_with("[debug] second\n"));
+    }
+
+    // Logger::set_colorize_name_by_hash()
+
+    #[test]
+    fn console_name_should_only_be_colored_when_enabled() {
+        let mut logger = Logger::new_default(String::from("test"));
+
+        logger.set_color_choice(color::ColorChoice::Always);
+        assert_eq!(logger.get_console_name("test"), "test");
+
+        logger.set_colorize_name_by_hash(true);
+        assert_eq!(
+            logger.get_console_name("test"),
+            color::paint(&"test".color(color::name_color("test")))
+        );
+
+        logger.set_color_choice(color::ColorChoice::Never);
+        assert_eq!(logger.get_console_name("test"), "test");
+    }
+
+    // Logger::set_duplicate_sink_policy()
+
+    #[test]
+    fn duplicate_file_sinks_should_be_merged() {
+        let path = std::env::temp_dir().join("ultimate_logger_duplicate_sinks.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        let first = logger
+            .add_file_sink(&path.to_string_lossy(), log_level::LogLevel::Error)
+            .unwrap();
+
+        logger.set_duplicate_sink_policy(DuplicateSinkPolicy::Merge);
+        let dotted_path = path
+            .parent()
+            .unwrap()
+            .join(".")
+            .join("ultimate_logger_duplicate_sinks.log");
+        let second = logger
+            .add_file_sink(&dotted_path.to_string_lossy(), log_level::LogLevel::Info)
+            .unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(second, logger.sinks()[0].id);
+        assert_eq!(logger.sinks().len(), 2);
+
+        logger.remove_sink(first);
+        logger.info("once");
+        drop(logger);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.matches("once").count(), 1);
+    }
+
+    // Logger::set_output_format()
+
+    #[test]
+    fn binary_output_format_should_round_trip() {
+        let path = std::env::temp_dir().join("ultimate_logger_binary.bin");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.set_output_format(OutputFormat::Binary);
+        logger.set_trace_id(Some(String::from("abc")));
+        logger.warning("first\nsecond");
+        drop(logger);
+
+        let records: Vec<log_record::LogRecord> =
+            binary::decode_binary_log(&path).unwrap().collect();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].logger, "test");
+        assert_eq!(records[0].level, log_level::LogLevel::Warning);
+        assert_eq!(records[0].trace_id.as_deref(), Some("abc"));
+        assert_eq!(records[0].message, "first\nsecond");
+    }
+
+    #[test]
+    fn binary_output_format_should_start_rotated_files_with_header() {
+        let path = std::env::temp_dir().join("ultimate_logger_binary_rotation.bin");
+        let path_string = path.to_string_lossy().into_owned();
+        let _ = std::fs::remove_file(&path);
+        for index in 1..=3 {
+            let _ = std::fs::remove_file(log_file::rotated_path(&path_string, index));
+        }
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path_string.clone(),
+            false,
+        );
+
+        logger.set_output_format(OutputFormat::Binary);
+        logger.set_size_rotation(60, 3);
+        for message in [
+            "first message",
+            "second message",
+            "third message",
+            "fourth message",
+        ] {
+            logger.info(message);
+        }
+        drop(logger);
+
+        let mut messages = Vec::new();
+        for file in [
+            log_file::rotated_path(&path_string, 2),
+            log_file::rotated_path(&path_string, 1),
+            path.clone(),
+        ] {
+            let records: Vec<log_record::LogRecord> =
+                binary::decode_binary_log(&file).unwrap().collect();
+            assert!(!records.is_empty());
+            assert!(records.iter().all(|record| record.logger == "test"));
+            messages.extend(records.into_iter().map(|record| record.message));
+        }
+
+        assert_eq!(messages.last().map(String::as_str), Some("fourth message"));
+    }
+
+    #[test]
+    fn binary_output_format_should_start_daily_files_with_header() {
+        let pattern = std::env::temp_dir()
+            .join("ultimate_logger_binary_daily_{date}.bin")
+            .to_string_lossy()
+            .into_owned();
+        let yesterday = pattern.replace(log_file::DATE_PLACEHOLDER, "2000-01-01");
+        let today = pattern.replace(
+            log_file::DATE_PLACEHOLDER,
+            &Local::now().format("%Y-%m-%d").to_string(),
+        );
+        let _ = std::fs::remove_file(&yesterday);
+        let _ = std::fs::remove_file(&today);
+        let mut log_file = LogFile::new_daily(&pattern, false, String::from("2000-01-01")).unwrap();
+        let mut rate_limiter = ByteRateLimiter::new(None);
+        let mut discarded_pipe_writes = 0;
+
+        // Written without switching the date, as if it had been logged yesterday.
+        let (yesterday_bytes, _) = Logger::encode_binary(
+            &log_file,
+            0,
+            "test",
+            log_level::LogLevel::Info,
+            None,
+            &["yesterday"],
+        );
+        log_file.write_prepared_bytes(&yesterday_bytes).unwrap();
+        log_file.define_binary_name("test");
+
+        Logger::log_binary_to_file(
+            &mut log_file,
+            &mut rate_limiter,
+            0,
+            "test",
+            log_level::LogLevel::Info,
+            None,
+            &["today"],
+            PipeAtomicPolicy::Split,
+            &mut discarded_pipe_writes,
+        )
+        .unwrap();
+        log_file.flush().unwrap();
+
+        for (file, message) in [(&yesterday, "yesterday"), (&today, "today")] {
+            let records: Vec<log_record::LogRecord> =
+                binary::decode_binary_log(file).unwrap().collect();
+            assert_eq!(records.len(), 1);
+            assert_eq!(records[0].logger, "test");
+            assert_eq!(records[0].message, message);
+        }
+    }
+
+    #[test]
+    fn json_output_format_should_write_one_object_per_line() {
+        let path = std::env::temp_dir().join("ultimate_logger_json.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("te\"st"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.set_output_format(OutputFormat::Json);
+        logger.set_timestamp_preset(timestamp::TimestampPreset::Iso8601);
+        logger.set_compact_date(true);
+        logger.set_show_line_number(true);
+        logger.set_show_scope(true);
+        logger.set_source(Some(String::from("resize")));
+        logger.info("say \"hi\"");
+        logger.with_scope("load", |logger| {
+            logger.log_with_trace_id(log_level::LogLevel::Error, "first\nsecond", "abc")
+        });
+
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("{\"timestamp\":\""));
+        assert!(lines[0].ends_with(
+            "Z\",\"logger\":\"te\\\"st\",\"source\":\"resize\",\"level\":\"info\",\"message\":\"say \\\"hi\\\"\"}"
+        ));
+        assert!(lines[1].ends_with(
+            "\"level\":\"error\",\"trace_id\":\"abc\",\"scopes\":[\"load\"],\"message\":\"first\\nsecond\"}"
+        ));
+    }
+
+    #[test]
+    fn dual_output_format_should_apply_min_level_per_format() {
+        let buffer = SharedBuffer::default();
+        let mut logger = Logger::new_to_writer(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            Box::new(buffer.clone()),
+        );
+
+        logger.set_output_format(OutputFormat::Dual);
+        logger.set_dual_min_levels(log_level::LogLevel::Warning, log_level::LogLevel::Debug);
+        logger.trace("hidden");
+        logger.info("routine");
+        logger.error("broken");
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("{\"timestamp\":\""));
+        assert!(lines[0].ends_with("\"level\":\"info\",\"message\":\"routine\"}"));
+        assert!(lines[1].ends_with("[test] [error] broken"));
+        assert!(lines[2].ends_with("\"level\":\"error\",\"message\":\"broken\"}"));
+
+        let config = logger.snapshot_config();
+        assert_eq!(config.output_format, OutputFormat::Dual);
+        assert_eq!(config.dual_text_min_level, log_level::LogLevel::Warning);
+        assert_eq!(config.dual_json_min_level, log_level::LogLevel::Debug);
+    }
+
+    #[test]
+    fn json_schema_should_be_written_next_to_json_files() {
+        let path = std::env::temp_dir().join("ultimate_logger_json_schema.log");
+        let schema_path = std::env::temp_dir().join("ultimate_logger_json_schema.log.schema.json");
+        let _ = std::fs::remove_file(&schema_path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.set_json_schema(true);
+        assert!(!schema_path.exists());
+
+        logger.set_output_format(OutputFormat::Json);
+        assert_eq!(std::fs::read_to_string(&schema_path).unwrap(), json::SCHEMA);
+        assert!(logger.snapshot_config().json_schema);
+    }
+
+    #[test]
+    fn json_schema_should_be_written_next_to_dual_format_files() {
+        let path = std::env::temp_dir().join("ultimate_logger_json_schema_dual.log");
+        let schema_path =
+            std::env::temp_dir().join("ultimate_logger_json_schema_dual.log.schema.json");
+        let _ = std::fs::remove_file(&schema_path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            log_level::LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+
+        logger.set_output_format(OutputFormat::Dual);
+        logger.set_json_schema(true);
 
-        assert_eq!(result, true);
+        assert_eq!(std::fs::read_to_string(&schema_path).unwrap(), json::SCHEMA);
     }
 }
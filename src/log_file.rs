@@ -1,35 +1,687 @@
 use std::fs::{File, OpenOptions};
-use std::io::prelude::*;
+use std::io::{self, prelude::*, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+#[cfg(feature = "gzip")]
+use std::thread;
+
+use chrono::Local;
+
+/// The placeholder in the path of a log file that is replaced by the local date, to start a new file every day.
+pub(crate) const DATE_PLACEHOLDER: &str = "{date}";
+
+/// The format of the date that replaces the placeholder, for example `2024-01-15`.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The number of bytes that can be written to a pipe at once, without interleaving with other writers.
+/// This is `PIPE_BUF` on Linux, and the minimum POSIX guarantees elsewhere.
+#[cfg(target_os = "linux")]
+pub(crate) const PIPE_BUF: usize = 4096;
+#[cfg(not(target_os = "linux"))]
+pub(crate) const PIPE_BUF: usize = 512;
+
+/// The type of file a log file turned out to be after opening it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(not(unix), allow(dead_code))]
+pub(crate) enum FileKind {
+    Regular,
+    Pipe,
+    Socket,
+    CharDevice,
+    BlockDevice,
+    Other,
+    /// Not a file, but a writer passed to `Logger::new_to_writer`.
+    Writer,
+}
+
+impl FileKind {
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            FileKind::Regular => "regular file",
+            FileKind::Pipe => "pipe",
+            FileKind::Socket => "socket",
+            FileKind::CharDevice => "character device",
+            FileKind::BlockDevice => "block device",
+            FileKind::Other => "special file",
+            FileKind::Writer => "writer",
+        }
+    }
+}
+
+#[cfg(unix)]
+fn file_kind(file: &File) -> io::Result<FileKind> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let file_type = file.metadata()?.file_type();
+
+    Ok(if file_type.is_file() {
+        FileKind::Regular
+    } else if file_type.is_fifo() {
+        FileKind::Pipe
+    } else if file_type.is_socket() {
+        FileKind::Socket
+    } else if file_type.is_char_device() {
+        FileKind::CharDevice
+    } else if file_type.is_block_device() {
+        FileKind::BlockDevice
+    } else {
+        FileKind::Other
+    })
+}
+
+#[cfg(not(unix))]
+fn file_kind(file: &File) -> io::Result<FileKind> {
+    // Devices like NUL may not have metadata, so only a file that reports not being a file counts as special.
+    Ok(match file.metadata() {
+        Ok(metadata) if !metadata.is_file() => FileKind::Other,
+        _ => FileKind::Regular,
+    })
+}
 
 pub(crate) struct LogFile {
-    file: File,
+    file: Box<dyn Write + Send>,
+    path: String,
+    canonical_path: Option<PathBuf>,
+    kind: FileKind,
+    needs_binary_header: bool,
+    binary_names: Vec<String>,
+    lines_written: u64,
+    last_date: Option<String>,
+    size: u64,
+    size_rotation: Option<(u64, usize)>,
+    /// Whether the line numbers start at 1 again when a new file is opened by rotation or because the date changed.
+    reset_line_numbers: bool,
+    /// The path with the date placeholder and the date the file was opened for, if a new file is started every day.
+    daily: Option<(String, String)>,
+    #[cfg(feature = "gzip")]
+    compress_rotated: bool,
+    /// The thread that compresses the file that was rotated out last, until it's waited for.
+    #[cfg(feature = "gzip")]
+    compression: Option<thread::JoinHandle<io::Result<()>>>,
+}
+
+/// Returns the path of a rotated log file: the index is inserted before the extension,
+/// so `logs/app.txt` becomes `logs/app.1.txt`, and `app` becomes `app.1`.
+pub(crate) fn rotated_path(path: &str, index: usize) -> PathBuf {
+    let path = Path::new(path);
+    let stem = path
+        .file_stem()
+        .map_or_else(Default::default, |stem| stem.to_os_string());
+    let mut file_name = stem;
+    file_name.push(format!(".{}", index));
+
+    if let Some(extension) = path.extension() {
+        file_name.push(".");
+        file_name.push(extension);
+    }
+
+    path.with_file_name(file_name)
+}
+
+/// Returns the path of a compressed rotated log file: the path with `.gz` added, so `app.1.txt` becomes `app.1.txt.gz`.
+pub(crate) fn compressed_path(path: &Path) -> PathBuf {
+    let mut compressed = path.as_os_str().to_os_string();
+    compressed.push(".gz");
+
+    PathBuf::from(compressed)
+}
+
+/// Compresses the file into a `.gz` file next to it, and removes the file.
+#[cfg(feature = "gzip")]
+fn compress(path: &Path) -> io::Result<()> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let mut input = File::open(path)?;
+    let output = File::create(compressed_path(path))?;
+    let mut encoder = GzEncoder::new(BufWriter::new(output), Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.flush()?;
+
+    std::fs::remove_file(path)
+}
+
+/// The writer of a log file while it's closed to be rotated, and after it couldn't be opened again.
+/// Writing to it fails, so lines are never discarded silently.
+struct ClosedFile;
+
+impl Write for ClosedFile {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "the file couldn't be opened again after rotating it",
+        ))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Removes the file, but doesn't fail if it doesn't exist.
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
 }
 
 impl LogFile {
-    pub(crate) fn new(path: &str) -> Self {
-        let file = OpenOptions::new()
-            .write(true)
-            .append(true)
-            .create(true)
-            .open(path)
-            .unwrap_or_else(|e| {
-                panic!(
-                    "Error opening log file: {}\nPath to log file was: {}",
-                    e, path
-                );
-            });
-
-        Self { file }
-    }
-
-    pub(crate) fn write(&mut self, to_write: &str) {
+    /// Opens the file, and appends to it or truncates it.
+    /// Writes to regular files are buffered until [`flush`](#method.flush) is called.
+    /// If the path contains the date placeholder, the file of the current local date is opened,
+    /// and a new file is opened when the date changes.
+    pub(crate) fn new(path: &str, append: bool) -> io::Result<Self> {
+        if path.contains(DATE_PLACEHOLDER) {
+            Self::new_daily(path, append, Local::now().format(DATE_FORMAT).to_string())
+        } else {
+            Self::open(path, append)
+        }
+    }
+
+    pub(crate) fn new_daily(pattern: &str, append: bool, date: String) -> io::Result<Self> {
+        let mut log_file = Self::open(&pattern.replace(DATE_PLACEHOLDER, &date), append)?;
+        log_file.daily = Some((pattern.to_string(), date));
+
+        Ok(log_file)
+    }
+
+    fn open(path: &str, append: bool) -> io::Result<Self> {
+        let file = if append {
+            OpenOptions::new().append(true).create(true).open(path)?
+        } else {
+            OpenOptions::new()
+                .write(true)
+                .truncate(true)
+                .create(true)
+                .open(path)?
+        };
+
+        let kind = file_kind(&file)?;
+        let size = file.metadata().map_or(0, |metadata| metadata.len());
+        // Writes to pipes and other special files are passed through as they are,
+        // so a write that fits in a pipe isn't split or merged with others.
+        let file: Box<dyn Write + Send> = if kind == FileKind::Regular {
+            Box::new(BufWriter::new(file))
+        } else {
+            Box::new(file)
+        };
+
+        Ok(Self {
+            file,
+            path: path.to_string(),
+            canonical_path: std::fs::canonicalize(path).ok(),
+            kind,
+            needs_binary_header: size == 0 || kind != FileKind::Regular,
+            binary_names: Vec::new(),
+            lines_written: 0,
+            last_date: None,
+            size,
+            size_rotation: None,
+            reset_line_numbers: true,
+            daily: None,
+            #[cfg(feature = "gzip")]
+            compress_rotated: false,
+            #[cfg(feature = "gzip")]
+            compression: None,
+        })
+    }
+
+    /// Wraps a writer, to write to it like to a new, empty file.
+    pub(crate) fn from_writer(writer: Box<dyn Write + Send>) -> Self {
+        Self {
+            file: writer,
+            path: String::new(),
+            canonical_path: None,
+            kind: FileKind::Writer,
+            needs_binary_header: true,
+            binary_names: Vec::new(),
+            lines_written: 0,
+            last_date: None,
+            size: 0,
+            size_rotation: None,
+            reset_line_numbers: true,
+            daily: None,
+            #[cfg(feature = "gzip")]
+            compress_rotated: false,
+            #[cfg(feature = "gzip")]
+            compression: None,
+        }
+    }
+
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the path the file was opened with, which still contains the date placeholder if there is one.
+    pub(crate) fn configured_path(&self) -> &str {
+        match &self.daily {
+            Some((pattern, _)) => pattern,
+            None => &self.path,
+        }
+    }
+
+    /// Returns the absolute path of the file with all symbolic links resolved,
+    /// or `None` if it couldn't be determined when the file was opened.
+    pub(crate) fn canonical_path(&self) -> Option<&Path> {
+        self.canonical_path.as_deref()
+    }
+
+    pub(crate) fn kind(&self) -> FileKind {
+        self.kind
+    }
+
+    /// Returns `true` if the file is a pipe (a FIFO) instead of a regular file.
+    pub(crate) fn is_pipe(&self) -> bool {
+        self.kind == FileKind::Pipe
+    }
+
+    /// Returns `true` if the header of the binary format still has to be written,
+    /// because the file was empty when it was opened (or is a pipe), and nothing was written to it yet.
+    pub(crate) fn needs_binary_header(&self) -> bool {
+        self.needs_binary_header
+    }
+
+    /// Returns the index of the name in the binary format, and whether it still has to be defined in the file.
+    pub(crate) fn binary_name_index(&self, name: &str) -> (u32, bool) {
+        match self.binary_names.iter().position(|other| other == name) {
+            Some(index) => (index as u32, false),
+            None => (self.binary_names.len() as u32, true),
+        }
+    }
+
+    /// Remembers that the name was defined in the file, with the next index.
+    pub(crate) fn define_binary_name(&mut self, name: &str) {
+        self.binary_names.push(name.to_string());
+    }
+
+    pub(crate) fn lines_written(&self) -> u64 {
+        self.lines_written
+    }
+
+    pub(crate) fn last_date(&self) -> Option<&str> {
+        self.last_date.as_deref()
+    }
+
+    pub(crate) fn set_last_date(&mut self, last_date: Option<String>) {
+        self.last_date = last_date;
+    }
+
+    /// Sets the maximum size of the file in bytes and the number of rotated files to keep,
+    /// or `None` to let the file grow without bound. Only regular files are rotated.
+    pub(crate) fn set_size_rotation(&mut self, size_rotation: Option<(u64, usize)>) {
+        self.size_rotation = size_rotation;
+    }
+
+    /// Sets whether files that are rotated out, by size or because the date changed, are compressed with gzip.
+    /// The compression runs in a background thread, see [`wait_for_compression`](#method.wait_for_compression).
+    #[cfg(feature = "gzip")]
+    pub(crate) fn set_compress_rotated(&mut self, compress_rotated: bool) {
+        self.compress_rotated = compress_rotated;
+    }
+
+    /// Starts compressing a file that was rotated out in a background thread, if compression is enabled,
+    /// after waiting for the previous one. Files that are already compressed are left as they are.
+    #[cfg(feature = "gzip")]
+    fn start_compression(&mut self, path: PathBuf) {
+        if !self.compress_rotated
+            || path
+                .extension()
+                .map_or(false, |extension| extension == "gz")
+        {
+            return;
+        }
+
+        if let Err(e) = self.wait_for_compression() {
+            eprintln!("{}", e);
+        }
+
+        self.compression = Some(thread::spawn(move || compress(&path)));
+    }
+
+    /// Waits until the file that was rotated out last is compressed, and returns the error if that failed.
+    #[cfg(feature = "gzip")]
+    fn wait_for_compression(&mut self) -> io::Result<()> {
+        let result = match self.compression.take() {
+            Some(compression) => compression.join().unwrap_or_else(|_| {
+                Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    "the compression thread panicked",
+                ))
+            }),
+            None => Ok(()),
+        };
+
+        result.map_err(|e| self.error("compressing the rotated file of", e))
+    }
+
+    /// Sets whether the line numbers start at 1 again when a new file is opened by rotation
+    /// or because the date changed, or continue from the previous file.
+    pub(crate) fn set_reset_line_numbers(&mut self, reset_line_numbers: bool) {
+        self.reset_line_numbers = reset_line_numbers;
+    }
+
+    /// Rotates the file first if writing this many bytes would make it larger than its maximum size.
+    /// A single write is never split, so a write larger than the maximum size goes to a fresh file on its own.
+    fn rotate_if_needed(&mut self, len: usize) -> io::Result<()> {
+        let (max_bytes, max_files) = match self.size_rotation {
+            Some(size_rotation) if self.kind == FileKind::Regular => size_rotation,
+            _ => return Ok(()),
+        };
+
+        if self.size == 0 || self.size.saturating_add(len as u64) <= max_bytes {
+            return Ok(());
+        }
+
+        self.rotate(max_files)
+            .map_err(|e| self.error("rotating", e))
+    }
+
+    /// Rotates the file like [`rotate_if_needed`](#method.rotate_if_needed), but if rotating fails,
+    /// the file is opened again in append mode, so the write still goes to it, and the next write tries again.
+    /// The error of the rotation is still returned.
+    fn rotate_or_restore(&mut self, len: usize) -> io::Result<()> {
+        let result = self.rotate_if_needed(len);
+
+        if result.is_err() && self.kind == FileKind::Regular {
+            let path = self.path.clone();
+            let lines_written = self.lines_written;
+            let binary_names = self.binary_names.clone();
+            let last_date = self.last_date.clone();
+
+            if let Err(e) = self.reopen(&path, true) {
+                return Err(self.error("opening again after rotating", e));
+            }
+
+            // It's the same file as before, so it continues where it left off.
+            self.lines_written = lines_written;
+            self.binary_names = binary_names;
+            self.last_date = last_date;
+        }
+
+        result
+    }
+
+    /// Renames the file to `<name>.1.<extension>`, after shifting the older rotated files up by one
+    /// and removing the one that would exceed `max_files`, and opens a fresh file at the path.
+    /// Everything written before is flushed to the renamed file, so no lines are lost.
+    /// Rotated files that were compressed count as well, and keep their `.gz` extension when they're shifted.
+    /// If this fails, the file stays closed, see [`rotate_or_restore`](#method.rotate_or_restore).
+    fn rotate(&mut self, max_files: usize) -> io::Result<()> {
+        self.file.flush()?;
+        // Some platforms can't rename an open file, so the old file is closed first.
+        drop(std::mem::replace(&mut self.file, Box::new(ClosedFile)));
+
+        // The file that was rotated out last may still be compressed, and is about to be shifted.
+        #[cfg(feature = "gzip")]
+        if let Err(e) = self.wait_for_compression() {
+            eprintln!("{}", e);
+        }
+
+        if max_files == 0 {
+            remove_if_exists(Path::new(&self.path))?;
+        } else {
+            let oldest = rotated_path(&self.path, max_files);
+            remove_if_exists(&oldest)?;
+            remove_if_exists(&compressed_path(&oldest))?;
+
+            for index in (1..max_files).rev() {
+                let from = rotated_path(&self.path, index);
+                let to = rotated_path(&self.path, index + 1);
+
+                for (from, to) in [(compressed_path(&from), compressed_path(&to)), (from, to)] {
+                    if from.exists() {
+                        std::fs::rename(&from, to)?;
+                    }
+                }
+            }
+
+            std::fs::rename(&self.path, rotated_path(&self.path, 1))?;
+
+            #[cfg(feature = "gzip")]
+            self.start_compression(rotated_path(&self.path, 1));
+        }
+
+        let path = self.path.clone();
+        self.reopen(&path, false)
+    }
+
+    /// Opens the file of the current local date instead, if the date changed since the file was opened.
+    /// This is checked on every write, so a process that was idle at midnight switches files on its next write.
+    fn switch_date_if_needed(&mut self) -> io::Result<()> {
+        let today = match &self.daily {
+            Some((_, date)) => {
+                let today = Local::now().format(DATE_FORMAT).to_string();
+
+                if *date == today {
+                    return Ok(());
+                }
+
+                today
+            }
+            None => return Ok(()),
+        };
+
+        self.file.flush().map_err(|e| self.error("flushing", e))?;
+
+        let (pattern, _) = self.daily.as_ref().unwrap();
+        let path = pattern.replace(DATE_PLACEHOLDER, &today);
+        #[cfg(feature = "gzip")]
+        let previous_path = PathBuf::from(&self.path);
+
+        // If the file of the new date can't be opened, the old file and date are kept, so the next write tries again.
+        self.reopen(&path, true).map_err(|e| {
+            io::Error::new(e.kind(), format!("Error opening log file {}: {}", path, e))
+        })?;
+
+        if let Some((_, date)) = &mut self.daily {
+            *date = today;
+        }
+
+        #[cfg(feature = "gzip")]
+        self.start_compression(previous_path);
+
+        Ok(())
+    }
+
+    /// Replaces the file by a newly opened one, as if the log file was created anew, but keeps the rotation settings,
+    /// and the line number unless it's reset. If the file can't be opened, nothing is changed.
+    fn reopen(&mut self, path: &str, append: bool) -> io::Result<()> {
+        let opened = Self::open(path, append)?;
+        let lines_written = if self.reset_line_numbers {
+            opened.lines_written
+        } else {
+            self.lines_written
+        };
+
+        *self = Self {
+            lines_written,
+            size_rotation: self.size_rotation,
+            reset_line_numbers: self.reset_line_numbers,
+            daily: self.daily.take(),
+            #[cfg(feature = "gzip")]
+            compress_rotated: self.compress_rotated,
+            #[cfg(feature = "gzip")]
+            compression: self.compression.take(),
+            ..opened
+        };
+
+        Ok(())
+    }
+
+    /// Flushes the file, and waits until the file that was rotated out last is compressed.
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.file.flush().map_err(|e| self.error("flushing", e))?;
+
+        #[cfg(feature = "gzip")]
+        self.wait_for_compression()?;
+
+        Ok(())
+    }
+
+    /// Writes binary records without switching the date or rotating, after `prepare_write`.
+    pub(crate) fn write_prepared_bytes(&mut self, to_write: &[u8]) -> io::Result<()> {
+        self.file
+            .write_all(to_write)
+            .map_err(|e| self.error("writing to", e))?;
+        self.size += to_write.len() as u64;
+        self.needs_binary_header = false;
+
+        Ok(())
+    }
+
+    pub(crate) fn write(&mut self, to_write: &[u8]) -> io::Result<()> {
+        self.switch_date_if_needed()?;
+        let rotated = self.rotate_or_restore(to_write.len());
+        self.write_prepared(to_write)?;
+
+        rotated
+    }
+
+    /// Switches to the file of today and rotates the file if writing `len` bytes needs it,
+    /// so the caller can format the bytes for the file they'll end up in.
+    /// Like `write`, the file can still be written to after a failed rotation.
+    pub(crate) fn prepare_write(&mut self, len: usize) -> io::Result<()> {
+        self.switch_date_if_needed()?;
+        self.rotate_or_restore(len)
+    }
+
+    /// Writes without switching the date or rotating, after `prepare_write`.
+    pub(crate) fn write_prepared(&mut self, to_write: &[u8]) -> io::Result<()> {
         self.file
-            .write_all(to_write.as_bytes())
-            .unwrap_or_else(|e| {
-                panic!(
-                    "Error writing to log file: {}\nText to be written was:\n{}",
-                    e, to_write
-                );
-            })
+            .write_all(to_write)
+            .map_err(|e| self.error("writing to", e))?;
+        self.size += to_write.len() as u64;
+        self.lines_written += to_write.iter().filter(|&&byte| byte == b'\n').count() as u64;
+
+        Ok(())
+    }
+
+    fn error(&self, action: &str, e: io::Error) -> io::Error {
+        let message = if self.kind == FileKind::Writer {
+            format!("Error {} log writer: {}", action, e)
+        } else {
+            format!("Error {} log file {}: {}", action, self.path, e)
+        };
+
+        io::Error::new(e.kind(), message)
+    }
+}
+
+/// A log file that several loggers can write to, see `Logger::clone_with_name`.
+/// Every write locks the file, so the lines of different loggers are never interleaved.
+#[derive(Clone)]
+pub(crate) struct SharedLogFile(Arc<Mutex<LogFile>>);
+
+impl SharedLogFile {
+    pub(crate) fn new(log_file: LogFile) -> Self {
+        Self(Arc::new(Mutex::new(log_file)))
+    }
+
+    /// Locks the file. If a thread panicked while holding the lock, the file is still used.
+    pub(crate) fn lock(&self) -> MutexGuard<'_, LogFile> {
+        match self.0.lock() {
+            Ok(log_file) => log_file,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn daily_file_should_switch_to_new_date_on_next_write() {
+        let pattern = std::env::temp_dir()
+            .join("ultimate_logger_daily_{date}.log")
+            .to_string_lossy()
+            .into_owned();
+        let yesterday = pattern.replace(DATE_PLACEHOLDER, "2000-01-01");
+        let today = pattern.replace(
+            DATE_PLACEHOLDER,
+            &Local::now().format(DATE_FORMAT).to_string(),
+        );
+        let _ = std::fs::remove_file(&yesterday);
+        let _ = std::fs::remove_file(&today);
+
+        // Simulates a file that was opened before midnight, and is written to for the first time after midnight.
+        let mut log_file = LogFile::new_daily(&pattern, false, String::from("2000-01-01")).unwrap();
+        assert_eq!(log_file.path(), yesterday);
+
+        log_file.write(b"after midnight\n").unwrap();
+        log_file.flush().unwrap();
+
+        assert_eq!(log_file.path(), today);
+        assert_eq!(log_file.configured_path(), pattern);
+        assert_eq!(std::fs::read_to_string(&yesterday).unwrap(), "");
+        assert_eq!(std::fs::read_to_string(&today).unwrap(), "after midnight\n");
+    }
+
+    #[test]
+    fn daily_file_should_keep_switching_after_failed_open() {
+        let pattern = std::env::temp_dir()
+            .join("ultimate_logger_daily_retry_{date}.log")
+            .to_string_lossy()
+            .into_owned();
+        let yesterday = pattern.replace(DATE_PLACEHOLDER, "2000-01-01");
+        let today = pattern.replace(
+            DATE_PLACEHOLDER,
+            &Local::now().format(DATE_FORMAT).to_string(),
+        );
+        let _ = std::fs::remove_file(&yesterday);
+        let _ = std::fs::remove_file(&today);
+        let _ = std::fs::remove_dir(&today);
+        // A directory in the place of the file of today makes opening it fail.
+        std::fs::create_dir(&today).unwrap();
+
+        let mut log_file = LogFile::new_daily(&pattern, false, String::from("2000-01-01")).unwrap();
+        assert!(log_file.write(b"failed\n").is_err());
+        assert_eq!(log_file.path(), yesterday);
+        assert_eq!(log_file.configured_path(), pattern);
+
+        std::fs::remove_dir(&today).unwrap();
+        log_file.write(b"retried\n").unwrap();
+        log_file.flush().unwrap();
+
+        assert_eq!(log_file.path(), today);
+        assert_eq!(std::fs::read_to_string(&today).unwrap(), "retried\n");
+    }
+
+    #[test]
+    fn failed_rotation_should_keep_writing_to_file() {
+        let path = std::env::temp_dir().join("ultimate_logger_failed_rotation.log");
+        let rotated = rotated_path(&path.to_string_lossy(), 1);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&rotated);
+        let _ = std::fs::remove_dir_all(&rotated);
+        // A directory that isn't empty in the place of the rotated file makes removing it fail.
+        std::fs::create_dir_all(rotated.join("blocker")).unwrap();
+
+        let mut log_file = LogFile::new(&path.to_string_lossy(), false).unwrap();
+        log_file.set_size_rotation(Some((30, 1)));
+        log_file.write(b"first line of 20 b\n").unwrap();
+        assert!(log_file.write(b"second line of 20 \n").is_err());
+        assert!(log_file.write(b"third line of 20 b\n").is_err());
+        log_file.flush().unwrap();
+
+        assert_eq!(log_file.lines_written(), 3);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "first line of 20 b\nsecond line of 20 \nthird line of 20 b\n"
+        );
+
+        std::fs::remove_dir_all(&rotated).unwrap();
+        log_file.write(b"fourth line of 20 \n").unwrap();
+        log_file.flush().unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&rotated).unwrap(),
+            "first line of 20 b\nsecond line of 20 \nthird line of 20 b\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "fourth line of 20 \n"
+        );
     }
 }
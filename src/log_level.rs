@@ -1,6 +1,7 @@
 //! Log level enum and functions
 
-use colored::{ColoredString, Colorize};
+use std::fmt;
+use std::str::FromStr;
 
 /// Log level enum
 ///
@@ -16,7 +17,7 @@ use colored::{ColoredString, Colorize};
 /// let log_level_error = log_level::LogLevel::Error;
 /// let log_level_critical = log_level::LogLevel::Critical;
 /// ```
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum LogLevel {
     Trace,
     Debug,
@@ -26,8 +27,81 @@ pub enum LogLevel {
     Critical,
 }
 
+/// The most verbose log level the logging macros, like [`info!`](crate::info!), compile in,
+/// set with the Cargo features `max_level_trace` to `max_level_critical`, or `None` with `max_level_off`.
+/// Macro calls below it compile to nothing, so their messages aren't even formatted.
+/// If several of these features are enabled, the least verbose one applies; without any, it's "Trace".
+/// The methods of `Logger`, like `Logger::debug`, aren't affected.
+pub const STATIC_MAX_LEVEL: Option<LogLevel> = if cfg!(feature = "max_level_off") {
+    None
+} else if cfg!(feature = "max_level_critical") {
+    Some(LogLevel::Critical)
+} else if cfg!(feature = "max_level_error") {
+    Some(LogLevel::Error)
+} else if cfg!(feature = "max_level_warning") {
+    Some(LogLevel::Warning)
+} else if cfg!(feature = "max_level_info") {
+    Some(LogLevel::Info)
+} else if cfg!(feature = "max_level_debug") {
+    Some(LogLevel::Debug)
+} else {
+    Some(LogLevel::Trace)
+};
+
 impl LogLevel {
-    pub(crate) fn to_string(&self) -> &str {
+    pub(crate) const ALL: [LogLevel; 6] = [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warning,
+        LogLevel::Error,
+        LogLevel::Critical,
+    ];
+
+    /// Returns all log levels, from the least to the most severe.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let names: Vec<String> = LogLevel::all().iter().map(|level| level.to_string()).collect();
+    ///
+    /// assert_eq!(names, ["trace", "debug", "info", "warning", "error", "critical"]);
+    /// ```
+    pub fn all() -> [LogLevel; 6] {
+        LogLevel::ALL
+    }
+
+    /// Returns `true` if this log level is at least as severe as the other one.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The log level to compare with, like the minimum log level of a logger.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// assert!(LogLevel::Error.is_at_least(LogLevel::Warning));
+    /// assert!(LogLevel::Warning.is_at_least(LogLevel::Warning));
+    /// assert!(!LogLevel::Debug.is_at_least(LogLevel::Warning));
+    /// ```
+    pub fn is_at_least(self, other: LogLevel) -> bool {
+        self >= other
+    }
+
+    /// Returns `true` if the logging macros compile in messages with this log level, see [`STATIC_MAX_LEVEL`].
+    #[inline]
+    pub fn is_statically_enabled(self) -> bool {
+        match STATIC_MAX_LEVEL {
+            Some(max_level) => self.is_at_least(max_level),
+            None => false,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
         match self {
             LogLevel::Trace => "trace",
             LogLevel::Debug => "debug",
@@ -38,18 +112,96 @@ impl LogLevel {
         }
     }
 
-    pub(crate) fn color_string(&self, str: &str) -> ColoredString {
-        match self {
-            LogLevel::Trace => str.dimmed(),
-            LogLevel::Debug => str.dimmed().underline(),
-            LogLevel::Info => str.clear(),
-            LogLevel::Warning => str.yellow(),
-            LogLevel::Error => str.red(),
-            LogLevel::Critical => str.red().bold(),
+    pub(crate) fn from_name(name: &str) -> Option<LogLevel> {
+        match name {
+            "trace" => Some(LogLevel::Trace),
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warning" => Some(LogLevel::Warning),
+            "error" => Some(LogLevel::Error),
+            "critical" => Some(LogLevel::Critical),
+            _ => None,
         }
     }
 }
 
+impl fmt::Display for LogLevel {
+    /// Writes the lowercase name of the log level, like `warning`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+/// The error returned when parsing a [`LogLevel`] from a string that isn't the name of a log level.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseLogLevelError {
+    name: String,
+}
+
+impl fmt::Display for ParseLogLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid log level \"{}\", expected trace, debug, info, warning, error or critical",
+            self.name
+        )
+    }
+}
+
+impl std::error::Error for ParseLogLevelError {}
+
+impl FromStr for LogLevel {
+    type Err = ParseLogLevelError;
+
+    /// Parses the name of a log level, ignoring case, like `info` or `WARNING`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::log_level::LogLevel;
+    ///
+    /// let level: LogLevel = "Warning".parse().unwrap();
+    ///
+    /// assert_eq!(level, LogLevel::Warning);
+    /// assert_eq!(level.to_string(), "warning");
+    /// assert!("fatal".parse::<LogLevel>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LogLevel::from_name(&s.to_ascii_lowercase()).ok_or_else(|| ParseLogLevelError {
+            name: s.to_string(),
+        })
+    }
+}
+
+/// Serializes the log level as its lowercase name, like `"info"`. Only available with the `serde` feature.
+#[cfg(feature = "serde")]
+impl serde_crate::Serialize for LogLevel {
+    fn serialize<S: serde_crate::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Deserializes the name of a log level, ignoring case, like [`FromStr`]. Only available with the `serde` feature.
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::log_level::LogLevel;
+///
+/// let level: LogLevel = serde_json::from_str("\"debug\"").unwrap();
+///
+/// assert_eq!(level, LogLevel::Debug);
+/// assert_eq!(serde_json::to_string(&level).unwrap(), "\"debug\"");
+/// ```
+#[cfg(feature = "serde")]
+impl<'de> serde_crate::Deserialize<'de> for LogLevel {
+    fn deserialize<D: serde_crate::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        name.parse().map_err(serde_crate::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,6 +215,33 @@ mod tests {
         assert!(LogLevel::Error < LogLevel::Critical);
     }
 
+    #[test]
+    fn test_all_should_be_in_ascending_order() {
+        let all = LogLevel::all();
+
+        assert!(all.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(all
+            .iter()
+            .all(|level| level.is_at_least(LogLevel::Trace)
+                && LogLevel::Critical.is_at_least(*level)));
+    }
+
+    #[test]
+    fn test_is_statically_enabled_should_follow_static_max_level() {
+        for level in LogLevel::all() {
+            let expected = match STATIC_MAX_LEVEL {
+                Some(max_level) => level >= max_level,
+                None => false,
+            };
+
+            assert_eq!(level.is_statically_enabled(), expected);
+        }
+
+        if cfg!(not(feature = "max_level_off")) {
+            assert!(LogLevel::Critical.is_statically_enabled());
+        }
+    }
+
     #[test]
     fn test_to_string() {
         assert_eq!(LogLevel::Trace.to_string(), "trace");
@@ -74,21 +253,48 @@ mod tests {
     }
 
     #[test]
-    fn test_color_string() {
-        assert_eq!(LogLevel::Trace.color_string("trace"), "trace".dimmed());
-        assert_eq!(
-            LogLevel::Debug.color_string("debug"),
-            "debug".dimmed().underline()
-        );
-        assert_eq!(LogLevel::Info.color_string("info"), "info".clear());
+    fn test_from_name() {
+        assert_eq!(LogLevel::from_name("trace"), Some(LogLevel::Trace));
+        assert_eq!(LogLevel::from_name("critical"), Some(LogLevel::Critical));
+        assert_eq!(LogLevel::from_name("fatal"), None);
+    }
+
+    #[test]
+    fn test_display_should_respect_width() {
+        assert_eq!(format!("[{:<8}]", LogLevel::Info), "[info    ]");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("trace".parse(), Ok(LogLevel::Trace));
+        assert_eq!("DEBUG".parse(), Ok(LogLevel::Debug));
+        assert_eq!("Info".parse(), Ok(LogLevel::Info));
+        assert_eq!("wArNiNg".parse(), Ok(LogLevel::Warning));
+        assert_eq!("error".parse(), Ok(LogLevel::Error));
+        assert_eq!("CRITICAL".parse(), Ok(LogLevel::Critical));
+        assert!("".parse::<LogLevel>().is_err());
+        assert!(" info".parse::<LogLevel>().is_err());
+        assert!("warn".parse::<LogLevel>().is_err());
         assert_eq!(
-            LogLevel::Warning.color_string("warning"),
-            "warning".yellow()
+            "fatal".parse::<LogLevel>().unwrap_err().to_string(),
+            "invalid log level \"fatal\", expected trace, debug, info, warning, error or critical"
         );
-        assert_eq!(LogLevel::Error.color_string("error"), "error".red());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_should_round_trip_lowercase_names() {
+        for level in LogLevel::all() {
+            let json = serde_json::to_string(&level).unwrap();
+
+            assert_eq!(json, format!("\"{}\"", level));
+            assert_eq!(serde_json::from_str::<LogLevel>(&json).unwrap(), level);
+        }
+
         assert_eq!(
-            LogLevel::Critical.color_string("critical"),
-            "critical".red().bold()
+            serde_json::from_str::<LogLevel>("\"WARNING\"").unwrap(),
+            LogLevel::Warning
         );
+        assert!(serde_json::from_str::<LogLevel>("\"fatal\"").is_err());
     }
 }
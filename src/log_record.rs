@@ -0,0 +1,457 @@
+//! Log record struct and functions to read back lines written by a logger
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::log_level::LogLevel;
+
+/// A single message, as written to a log file.
+///
+/// # Examples
+///
+/// ```
+/// use ultimate_logger::log_level::LogLevel;
+/// use ultimate_logger::log_record;
+///
+/// let record = log_record::parse_line("[2020-05-01 12:00:00.000] [example] [info] This is an info message").unwrap();
+///
+/// assert_eq!(record.timestamp, "2020-05-01 12:00:00.000");
+/// assert_eq!(record.logger, "example");
+/// assert_eq!(record.level, LogLevel::Info);
+/// assert_eq!(record.trace_id, None);
+/// assert_eq!(record.message, "This is an info message");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LogRecord {
+    /// The timestamp, as it was written.
+    pub timestamp: String,
+    /// The name of the logger.
+    pub logger: String,
+    /// The log level.
+    pub level: LogLevel,
+    /// The trace id, if the message had one.
+    pub trace_id: Option<String>,
+    /// The message.
+    pub message: String,
+}
+
+/// Parses a line written by a logger in the plain format back into a [`LogRecord`].
+/// A line number prefix (see `Logger::set_show_line_number`) is skipped.
+/// Returns `None` if the line isn't a log line, for example the date marker lines of the compact date mode.
+///
+/// This function never panics and runs in linear time, whatever the input,
+/// so it's safe to use on untrusted log files.
+///
+/// # Arguments
+///
+/// * `line` - The line, with or without the trailing newline.
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::log_level::LogLevel;
+/// use ultimate_logger::log_record;
+///
+/// let record = log_record::parse_line("12: [2020-05-01 12:00:00.000] [example] [error] [trace=abc123] Oops").unwrap();
+///
+/// assert_eq!(record.level, LogLevel::Error);
+/// assert_eq!(record.trace_id, Some(String::from("abc123")));
+/// assert_eq!(record.message, "Oops");
+///
+/// assert_eq!(log_record::parse_line("----- 2020-05-01 -----"), None);
+/// ```
+pub fn parse_line(line: &str) -> Option<LogRecord> {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let line = skip_line_number(line);
+
+    let rest = line.strip_prefix('[')?;
+    let timestamp_end = rest.find(']')?;
+    let timestamp = &rest[..timestamp_end];
+    let rest = rest[timestamp_end + 1..].strip_prefix(" [")?;
+
+    // The name is followed by the first "] [<level>]" in the line.
+    let (name_end, level, level_len) = find_level(rest)?;
+    let logger = &rest[..name_end];
+    let rest = &rest[name_end + level_len..];
+
+    let (trace_id, rest) = match rest.strip_prefix(" [trace=") {
+        Some(after_tag) => {
+            let trace_id_end = find_unescaped_bracket(after_tag)?;
+            (
+                Some(after_tag[..trace_id_end].to_string()),
+                &after_tag[trace_id_end + 1..],
+            )
+        }
+        None => (None, rest),
+    };
+
+    let message = match rest.strip_prefix(' ') {
+        Some(message) => message,
+        None if rest.is_empty() => rest,
+        None => return None,
+    };
+
+    Some(LogRecord {
+        timestamp: timestamp.to_string(),
+        logger: logger.to_string(),
+        level,
+        trace_id,
+        message: message.to_string(),
+    })
+}
+
+/// Parses a line of a log file that may not be valid UTF-8, like [`parse_line`].
+/// Invalid UTF-8 sequences are replaced by `U+FFFD REPLACEMENT CHARACTER`.
+///
+/// # Arguments
+///
+/// * `line` - The bytes of the line, with or without the trailing newline.
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::log_record;
+///
+/// let record = log_record::parse_line_bytes(b"[12:00:00.000] [example] [info] caf\xe9").unwrap();
+///
+/// assert_eq!(record.message, "caf\u{fffd}");
+/// ```
+pub fn parse_line_bytes(line: &[u8]) -> Option<LogRecord> {
+    parse_line(&String::from_utf8_lossy(line))
+}
+
+/// An error while reading the records of a log file with [`iter_records`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The line isn't a log line, for example because it's a continuation of a message with line breaks.
+    Malformed {
+        /// The number of the line in the file, starting at 1.
+        line_number: u64,
+        /// The line, with invalid UTF-8 replaced.
+        line: String,
+    },
+    /// The file couldn't be read. This is the last item of the iteration.
+    Io(io::Error),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Malformed { line_number, line } => {
+                write!(f, "line {} is not a log line: {}", line_number, line)
+            }
+            ParseError::Io(e) => write!(f, "error reading the log file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Malformed { .. } => None,
+            ParseError::Io(e) => Some(e),
+        }
+    }
+}
+
+struct Records<R> {
+    lines: io::Split<R>,
+    line_number: u64,
+    failed: bool,
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<LogRecord, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.failed {
+                return None;
+            }
+
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(ParseError::Io(e)));
+                }
+            };
+            self.line_number += 1;
+
+            if let Some(record) = parse_line_bytes(&line) {
+                return Some(Ok(record));
+            }
+
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(|c| c == '\r' || c == '\n');
+
+            if !is_date_marker(line) {
+                return Some(Err(ParseError::Malformed {
+                    line_number: self.line_number,
+                    line: line.to_string(),
+                }));
+            }
+        }
+    }
+}
+
+fn is_date_marker(line: &str) -> bool {
+    line.starts_with("----- ") && line.ends_with(" -----")
+}
+
+/// Reads a log file in the plain format line by line, and parses every line into a [`LogRecord`], like [`parse_line`].
+/// The file is streamed, so it may be larger than the available memory.
+/// Lines that aren't log lines are returned as errors, and the iteration continues after them.
+/// The date marker lines of the compact date mode are skipped.
+///
+/// # Arguments
+///
+/// * `path` - The path to the log file.
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::log_level::LogLevel;
+/// use ultimate_logger::log_record;
+///
+/// let path = std::env::temp_dir().join("replay.txt");
+/// let mut logger = Logger::new_default(String::from("example"));
+/// logger.set_append(false);
+/// logger.add_file_sink(path.to_str().unwrap(), LogLevel::Trace).unwrap();
+/// logger.info("This is an info message");
+/// logger.error("This is an error message");
+/// drop(logger);
+///
+/// let errors = log_record::iter_records(&path)
+///     .unwrap()
+///     .filter_map(Result::ok)
+///     .filter(|record| record.level >= LogLevel::Error)
+///     .count();
+///
+/// assert_eq!(errors, 1);
+/// ```
+///
+/// # Errors
+///
+/// This function will return an error if the file can't be opened.
+pub fn iter_records<P: AsRef<Path>>(
+    path: P,
+) -> io::Result<impl Iterator<Item = Result<LogRecord, ParseError>>> {
+    Ok(Records {
+        lines: BufReader::new(File::open(path)?).split(b'\n'),
+        line_number: 0,
+        failed: false,
+    })
+}
+
+fn skip_line_number(line: &str) -> &str {
+    let digits = line.bytes().take_while(u8::is_ascii_digit).count();
+
+    if digits == 0 {
+        return line;
+    }
+
+    line[digits..].strip_prefix(": ").unwrap_or(line)
+}
+
+/// The length of the longest level name, so a search for the level never looks further than that.
+const MAX_LEVEL_LEN: usize = 8;
+
+/// Returns the index of the first `]` that isn't escaped with a backslash, see `escape::text`.
+fn find_unescaped_bracket(text: &str) -> Option<usize> {
+    let mut escaped = false;
+
+    for (index, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ']' => return Some(index),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn find_level(rest: &str) -> Option<(usize, LogLevel, usize)> {
+    let mut search_from = 0;
+
+    while let Some(offset) = rest[search_from..].find("] [") {
+        let name_end = search_from + offset;
+        let after = &rest.as_bytes()[name_end + 3..];
+        let candidate = &after[..after.len().min(MAX_LEVEL_LEN + 1)];
+
+        if let Some(level_end) = candidate.iter().position(|&byte| byte == b']') {
+            // The candidate only contains bytes before an ASCII ']', so it ends on a char boundary.
+            let name = &rest[name_end + 3..name_end + 3 + level_end];
+
+            if let Some(level) = LogLevel::from_name(name) {
+                return Some((name_end, level, 3 + level_end + 1));
+            }
+        }
+
+        search_from = name_end + 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_should_parse_plain_line() {
+        let record =
+            parse_line("[2020-05-01 12:00:00.000] [example] [warning] Disk almost full\n").unwrap();
+
+        assert_eq!(
+            record,
+            LogRecord {
+                timestamp: String::from("2020-05-01 12:00:00.000"),
+                logger: String::from("example"),
+                level: LogLevel::Warning,
+                trace_id: None,
+                message: String::from("Disk almost full"),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_line_should_keep_brackets_in_message() {
+        let record = parse_line("[12:00:00.000] [example] [info] a] [b [debug] c").unwrap();
+
+        assert_eq!(record.logger, "example");
+        assert_eq!(record.level, LogLevel::Info);
+        assert_eq!(record.message, "a] [b [debug] c");
+    }
+
+    #[test]
+    fn parse_line_should_skip_line_number() {
+        let record = parse_line("3: [12:00:00.000] [example] [info] message").unwrap();
+
+        assert_eq!(record.message, "message");
+    }
+
+    #[test]
+    fn parse_line_should_return_none_for_other_lines() {
+        assert_eq!(parse_line(""), None);
+        assert_eq!(parse_line("----- 2020-05-01 -----"), None);
+        assert_eq!(
+            parse_line("[12:00:00.000] [example] [nonsense] message"),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_line_should_handle_missing_fields() {
+        assert_eq!(parse_line("["), None);
+        assert_eq!(parse_line("[12:00:00.000]"), None);
+        assert_eq!(parse_line("[12:00:00.000] ["), None);
+        assert_eq!(parse_line("[12:00:00.000] [example] ["), None);
+        assert_eq!(
+            parse_line("[12:00:00.000] [example] [info] [trace=abc"),
+            None
+        );
+        assert_eq!(parse_line("[12:00:00.000] [example] [info]x"), None);
+        assert_eq!(parse_line("123: "), None);
+        assert_eq!(
+            parse_line("[12:00:00.000] [example] [info]")
+                .unwrap()
+                .message,
+            ""
+        );
+    }
+
+    #[test]
+    fn parse_line_should_handle_multibyte_characters() {
+        let record = parse_line("[12:00:00.000] [é] [€] [info] ü]").unwrap();
+
+        assert_eq!(record.logger, "é] [€");
+        assert_eq!(record.message, "ü]");
+        assert_eq!(parse_line("[é] [é] [éé"), None);
+    }
+
+    #[test]
+    fn parse_line_should_handle_long_lines() {
+        let line = format!("[12:00:00.000] [{}", "] [".repeat(100_000));
+
+        assert_eq!(parse_line(&line), None);
+    }
+
+    #[test]
+    fn parse_line_bytes_should_replace_invalid_utf8() {
+        let record = parse_line_bytes(b"[12:00:00.000] [ex\xffample] [info] \xc3").unwrap();
+
+        assert_eq!(record.logger, "ex\u{fffd}ample");
+        assert_eq!(record.message, "\u{fffd}");
+        assert_eq!(parse_line_bytes(b"\xff\xfe"), None);
+    }
+
+    #[test]
+    fn records_should_yield_errors_and_skip_date_markers() {
+        let contents = "----- 2020-05-01 -----\n[12:00:00.000] [example] [info] first\ncontinued\n\n3: [12:00:00.000] [example] [error] second\r\n";
+        let records: Vec<Result<LogRecord, ParseError>> = Records {
+            lines: BufRead::split(contents.as_bytes(), b'\n'),
+            line_number: 0,
+            failed: false,
+        }
+        .collect();
+
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].as_ref().unwrap().message, "first");
+        assert!(matches!(
+            &records[1],
+            Err(ParseError::Malformed { line_number: 3, line }) if line == "continued"
+        ));
+        assert!(matches!(
+            &records[2],
+            Err(ParseError::Malformed { line_number: 4, .. })
+        ));
+        assert_eq!(records[3].as_ref().unwrap().level, LogLevel::Error);
+    }
+
+    /// Builds pseudo-random lines from fragments of log lines, and checks that parsing them never panics
+    /// and that every parsed record can be written and parsed again to the same record.
+    #[test]
+    fn parse_line_should_not_panic_on_random_input() {
+        const FRAGMENTS: [&str; 14] = [
+            "[", "]", " ", "] [", "info", "critical", "[trace=", "12: ", ":", "\n", "\r", "é", "x",
+            "",
+        ];
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+
+        for _ in 0..20_000 {
+            let mut line = String::new();
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let length = (state % 24) as usize;
+
+            for _ in 0..length {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                line.push_str(FRAGMENTS[(state % FRAGMENTS.len() as u64) as usize]);
+            }
+
+            if let Some(record) = parse_line(&line) {
+                let trace_tag = match &record.trace_id {
+                    Some(trace_id) => format!(" [trace={}]", trace_id),
+                    None => String::new(),
+                };
+                let written = format!(
+                    "[{}] [{}] [{}]{} {}",
+                    record.timestamp, record.logger, record.level, trace_tag, record.message
+                );
+
+                assert_eq!(parse_line(&written).as_ref(), Some(&record), "{:?}", line);
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! A trait for loggers, so code can log without depending on a concrete logger
+
+use crate::log_level::LogLevel;
+use crate::shared::SharedLogger;
+use crate::Logger;
+
+/// Something that messages can be logged to.
+/// Write functions that take `&mut impl Log` to accept a [`Logger`], a [`NullLogger`] or a fake in tests.
+/// Only [`log`](#tymethod.log) has to be implemented, the methods for the log levels call it.
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::logging::{Log, NullLogger};
+///
+/// fn connect(logger: &mut impl Log) {
+///     logger.info("Connecting");
+/// }
+///
+/// connect(&mut Logger::new_default(String::from("example")));
+/// connect(&mut NullLogger);
+/// ```
+///
+/// This will log the following message once, for the first call:
+/// [2020-12-31 23:59:59.999] [example] [info] Connecting
+pub trait Log {
+    /// Logs a message with the specified log level.
+    /// Returns `true` if the message was logged and `false` if it wasn't.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `message` - The message.
+    fn log(&mut self, level: LogLevel, message: &str) -> bool;
+
+    /// Logs a message with the log level "Trace".
+    fn trace(&mut self, message: &str) -> bool {
+        self.log(LogLevel::Trace, message)
+    }
+
+    /// Logs a message with the log level "Debug".
+    fn debug(&mut self, message: &str) -> bool {
+        self.log(LogLevel::Debug, message)
+    }
+
+    /// Logs a message with the log level "Info".
+    fn info(&mut self, message: &str) -> bool {
+        self.log(LogLevel::Info, message)
+    }
+
+    /// Logs a message with the log level "Warning".
+    fn warning(&mut self, message: &str) -> bool {
+        self.log(LogLevel::Warning, message)
+    }
+
+    /// Logs a message with the log level "Error".
+    fn error(&mut self, message: &str) -> bool {
+        self.log(LogLevel::Error, message)
+    }
+
+    /// Logs a message with the log level "Critical".
+    fn critical(&mut self, message: &str) -> bool {
+        self.log(LogLevel::Critical, message)
+    }
+}
+
+impl Log for Logger {
+    fn log(&mut self, level: LogLevel, message: &str) -> bool {
+        Logger::log(self, level, message)
+    }
+}
+
+impl Log for SharedLogger {
+    fn log(&mut self, level: LogLevel, message: &str) -> bool {
+        SharedLogger::log(self, level, message)
+    }
+}
+
+/// A logger that logs nothing, as the default of a library that accepts an optional logger.
+/// Every method returns `false` without doing anything. See also `Logger::new_null`,
+/// for a [`Logger`] that logs nothing.
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::logging::{Log, NullLogger};
+///
+/// let mut logger = NullLogger;
+///
+/// assert!(!logger.critical("This critical message isn't logged."));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NullLogger;
+
+impl Log for NullLogger {
+    fn log(&mut self, _level: LogLevel, _message: &str) -> bool {
+        false
+    }
+}
+
+/// A logger that keeps the messages in memory instead of writing them, to check what code logs in tests.
+/// Every message is kept, whatever its log level. Only available with the `test-util` feature.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "test-util")]
+/// # {
+/// use ultimate_logger::log_level::LogLevel;
+/// use ultimate_logger::logging::{Log, TestLogger};
+///
+/// fn connect(logger: &mut impl Log) {
+///     logger.error("Connection refused");
+/// }
+///
+/// let mut logger = TestLogger::default();
+/// connect(&mut logger);
+///
+/// assert!(logger
+///     .records()
+///     .iter()
+///     .any(|(level, message)| *level == LogLevel::Error && message.contains("refused")));
+/// # }
+/// ```
+#[cfg(feature = "test-util")]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TestLogger {
+    records: Vec<(LogLevel, String)>,
+}
+
+#[cfg(feature = "test-util")]
+impl TestLogger {
+    /// Creates a test logger without messages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the log levels and messages that were logged, oldest first.
+    pub fn records(&self) -> &[(LogLevel, String)] {
+        &self.records
+    }
+
+    /// Removes all messages.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+}
+
+#[cfg(feature = "test-util")]
+impl Log for TestLogger {
+    fn log(&mut self, level: LogLevel, message: &str) -> bool {
+        self.records.push((level, message.to_string()));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeLogger {
+        messages: Vec<(LogLevel, String)>,
+    }
+
+    impl Log for FakeLogger {
+        fn log(&mut self, level: LogLevel, message: &str) -> bool {
+            self.messages.push((level, message.to_string()));
+            true
+        }
+    }
+
+    fn log_every_level(logger: &mut impl Log) -> usize {
+        [
+            logger.trace("trace"),
+            logger.debug("debug"),
+            logger.info("info"),
+            logger.warning("warning"),
+            logger.error("error"),
+            logger.critical("critical"),
+        ]
+        .iter()
+        .filter(|logged| **logged)
+        .count()
+    }
+
+    #[test]
+    fn level_methods_should_call_log() {
+        let mut logger = FakeLogger::default();
+
+        assert_eq!(log_every_level(&mut logger), 6);
+        assert_eq!(
+            logger.messages,
+            LogLevel::all()
+                .iter()
+                .map(|level| (*level, level.to_string()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn logger_should_respect_min_level() {
+        let mut logger = Logger::new_null(String::from("test"));
+        logger.set_min_level(LogLevel::Error);
+        logger.enable();
+
+        assert_eq!(log_every_level(&mut logger), 2);
+    }
+
+    #[test]
+    fn null_logger_should_log_nothing() {
+        assert_eq!(log_every_level(&mut NullLogger), 0);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn test_logger_should_keep_records_until_cleared() {
+        let mut logger = TestLogger::new();
+
+        assert_eq!(log_every_level(&mut logger), 6);
+        assert_eq!(logger.records().len(), 6);
+        assert_eq!(
+            logger.records()[4],
+            (LogLevel::Error, String::from("error"))
+        );
+
+        logger.clear();
+        assert!(logger.records().is_empty());
+    }
+}
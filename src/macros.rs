@@ -0,0 +1,227 @@
+//! Macros to log formatted messages, like `info!(logger, "x = {}", x)`
+
+/// Logs a formatted message with the specified log level, like [`Logger::log_fmt`](crate::Logger::log_fmt).
+/// The log level is checked first, so the message isn't formatted if it wouldn't be logged.
+/// Returns `true` if the message was logged.
+///
+/// Calls with a log level below [`STATIC_MAX_LEVEL`](crate::log_level::STATIC_MAX_LEVEL),
+/// which is set with the `max_level_*` Cargo features, compile to nothing and return `false`.
+///
+/// The logger can be a `Logger`, a `&mut Logger`, or anything that dereferences to it mutably,
+/// like the guard of a `Mutex<Logger>`.
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::log;
+/// use ultimate_logger::log_level::LogLevel;
+///
+/// let mut logger = Logger::new_default(String::from("example"));
+/// let retries = 3;
+///
+/// log!(logger, LogLevel::Warning, "Retrying after {} attempts", retries);
+/// ```
+///
+/// This will log the following message:
+/// [2020-12-31 23:59:59.999] [example] [warning] Retrying after 3 attempts
+#[macro_export]
+macro_rules! log {
+    ($logger:expr, $level:expr, $($arg:tt)+) => {{
+        let logger: &mut $crate::Logger = &mut $logger;
+        let level: $crate::log_level::LogLevel = $level;
+
+        if level.is_statically_enabled() {
+            logger.log_fmt(level, format_args!($($arg)+))
+        } else {
+            false
+        }
+    }};
+}
+
+/// Logs a formatted message with the log level "Trace", see [`log!`](crate::log!).
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::trace;
+/// use ultimate_logger::log_level::LogLevel;
+///
+/// let mut logger = Logger::new(String::from("example"), LogLevel::Trace);
+///
+/// trace!(logger, "Entering {} with {:?}", "parse", [1, 2]);
+/// ```
+#[macro_export]
+macro_rules! trace {
+    ($logger:expr, $($arg:tt)+) => {
+        $crate::log!($logger, $crate::log_level::LogLevel::Trace, $($arg)+)
+    };
+}
+
+/// Logs a formatted message with the log level "Debug", see [`log!`](crate::log!).
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::debug;
+/// use ultimate_logger::log_level::LogLevel;
+///
+/// let mut logger = Logger::new(String::from("example"), LogLevel::Debug);
+///
+/// debug!(logger, "Cache size: {} entries", 128);
+/// ```
+#[macro_export]
+macro_rules! debug {
+    ($logger:expr, $($arg:tt)+) => {
+        $crate::log!($logger, $crate::log_level::LogLevel::Debug, $($arg)+)
+    };
+}
+
+/// Logs a formatted message with the log level "Info", see [`log!`](crate::log!).
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::info;
+///
+/// let mut logger = Logger::new_default(String::from("example"));
+///
+/// info!(logger, "Progress: {:>6.2}%", 42.5);
+/// ```
+///
+/// This will log the following message:
+/// [2020-12-31 23:59:59.999] [example] [info] Progress:  42.50%
+#[macro_export]
+macro_rules! info {
+    ($logger:expr, $($arg:tt)+) => {
+        $crate::log!($logger, $crate::log_level::LogLevel::Info, $($arg)+)
+    };
+}
+
+/// Logs a formatted message with the log level "Warning", see [`log!`](crate::log!).
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::warning;
+///
+/// let mut logger = Logger::new_default(String::from("example"));
+///
+/// warning!(logger, "Disk usage is at {}%", 91);
+/// ```
+#[macro_export]
+macro_rules! warning {
+    ($logger:expr, $($arg:tt)+) => {
+        $crate::log!($logger, $crate::log_level::LogLevel::Warning, $($arg)+)
+    };
+}
+
+/// Logs a formatted message with the log level "Error", see [`log!`](crate::log!).
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::error;
+///
+/// let mut logger = Logger::new_default(String::from("example"));
+///
+/// error!(logger, "Couldn't open {}", "config.toml");
+/// ```
+#[macro_export]
+macro_rules! error {
+    ($logger:expr, $($arg:tt)+) => {
+        $crate::log!($logger, $crate::log_level::LogLevel::Error, $($arg)+)
+    };
+}
+
+/// Logs a formatted message with the log level "Critical", see [`log!`](crate::log!).
+///
+/// # Example
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::critical;
+///
+/// let mut logger = Logger::new_default(String::from("example"));
+///
+/// critical!(logger, "Out of memory after {} allocations", 1024);
+/// ```
+#[macro_export]
+macro_rules! critical {
+    ($logger:expr, $($arg:tt)+) => {
+        $crate::log!($logger, $crate::log_level::LogLevel::Critical, $($arg)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::fmt;
+
+    use crate::log_level::LogLevel;
+    use crate::Logger;
+
+    /// Counts how often it's formatted.
+    struct Counted<'a>(&'a Cell<u32>);
+
+    impl fmt::Display for Counted<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            self.0.set(self.0.get() + 1);
+            write!(f, "counted")
+        }
+    }
+
+    // The messages of this test are only compiled in if the static maximum level is "Info" or more verbose.
+    #[cfg(not(any(
+        feature = "max_level_off",
+        feature = "max_level_critical",
+        feature = "max_level_error",
+        feature = "max_level_warning"
+    )))]
+    #[test]
+    fn macros_should_keep_format_specs_and_skip_disabled_levels() {
+        let path = std::env::temp_dir().join("ultimate_logger_macros.log");
+        let _ = std::fs::remove_file(&path);
+        let mut logger = Logger::new_to_file(
+            String::from("test"),
+            LogLevel::Info,
+            path.to_string_lossy().into_owned(),
+            false,
+        );
+        let formatted = Cell::new(0);
+        let x = 42.5;
+
+        assert!(info!(logger, "{:>8.2}", x));
+        assert!(!debug!(logger, "{}", Counted(&formatted)));
+        assert!(error!(&mut logger, "{}", Counted(&formatted)));
+
+        assert_eq!(formatted.get(), 1);
+        logger.flush().unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("[info]    42.50"));
+        assert!(lines[1].ends_with("[error] counted"));
+    }
+
+    #[test]
+    fn macros_below_static_max_level_should_not_format() {
+        let mut logger = Logger::new(String::from("test"), LogLevel::Trace);
+        logger.disable();
+        let formatted = Cell::new(0);
+
+        trace!(logger, "{}", Counted(&formatted));
+
+        // The logger is off, so the message is only formatted if the call is compiled in.
+        assert_eq!(formatted.get(), 0);
+        assert_eq!(
+            logger.suppressed_count(),
+            LogLevel::Trace.is_statically_enabled() as u64
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::time::SystemTime;
+
+use opentelemetry::logs::{AnyValue, LogRecord, Logger, Severity};
+
+use crate::log_level::LogLevel;
+
+type Emit = Box<dyn Fn(LogLevel, &str, Option<&str>, &[(&str, &str)], &str) + Send>;
+
+/// Forwards messages to an OpenTelemetry logger, whose type is erased so the sink doesn't need a type parameter.
+pub(crate) struct OtelSink {
+    emit: Emit,
+}
+
+fn severity(level: LogLevel) -> Severity {
+    match level {
+        LogLevel::Trace => Severity::Trace,
+        LogLevel::Debug => Severity::Debug,
+        LogLevel::Info => Severity::Info,
+        LogLevel::Warning => Severity::Warn,
+        LogLevel::Error => Severity::Error,
+        LogLevel::Critical => Severity::Fatal,
+    }
+}
+
+impl OtelSink {
+    pub(crate) fn new<L: Logger + Send + 'static>(otel_logger: L) -> Self {
+        Self {
+            emit: Box::new(move |level, name, trace_id, fields, message| {
+                if !otel_logger.event_enabled(severity(level), name, None) {
+                    return;
+                }
+
+                let mut record = otel_logger.create_log_record();
+                let now = SystemTime::now();
+                record.set_timestamp(now);
+                record.set_observed_timestamp(now);
+                record.set_severity_number(severity(level));
+                record.set_severity_text(level.as_str());
+                record.set_target(name.to_string());
+                record.set_body(AnyValue::from(message.to_string()));
+
+                if let Some(trace_id) = trace_id {
+                    record.add_attribute("trace_id", trace_id.to_string());
+                }
+
+                for (key, value) in fields {
+                    record.add_attribute(key.to_string(), value.to_string());
+                }
+
+                otel_logger.emit(record);
+            }),
+        }
+    }
+
+    pub(crate) fn emit(
+        &self,
+        level: LogLevel,
+        name: &str,
+        trace_id: Option<&str>,
+        fields: &[(&str, &str)],
+        message: &str,
+    ) {
+        (self.emit)(level, name, trace_id, fields, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use std::sync::{Arc, Mutex};
+
+    use opentelemetry::Key;
+
+    #[derive(Default, Debug, Clone, PartialEq)]
+    pub(crate) struct TestRecord {
+        pub(crate) severity: Option<Severity>,
+        pub(crate) severity_text: Option<&'static str>,
+        pub(crate) target: Option<String>,
+        pub(crate) body: Option<AnyValue>,
+        pub(crate) attributes: Vec<(String, AnyValue)>,
+    }
+
+    impl LogRecord for TestRecord {
+        fn set_event_name(&mut self, _name: &'static str) {}
+
+        fn set_target<T>(&mut self, target: T)
+        where
+            T: Into<Cow<'static, str>>,
+        {
+            self.target = Some(target.into().into_owned());
+        }
+
+        fn set_timestamp(&mut self, _timestamp: SystemTime) {}
+
+        fn set_observed_timestamp(&mut self, _timestamp: SystemTime) {}
+
+        fn set_severity_text(&mut self, text: &'static str) {
+            self.severity_text = Some(text);
+        }
+
+        fn set_severity_number(&mut self, number: Severity) {
+            self.severity = Some(number);
+        }
+
+        fn set_body(&mut self, body: AnyValue) {
+            self.body = Some(body);
+        }
+
+        fn add_attributes<I, K, V>(&mut self, attributes: I)
+        where
+            I: IntoIterator<Item = (K, V)>,
+            K: Into<Key>,
+            V: Into<AnyValue>,
+        {
+            for (key, value) in attributes {
+                self.add_attribute(key, value);
+            }
+        }
+
+        fn add_attribute<K, V>(&mut self, key: K, value: V)
+        where
+            K: Into<Key>,
+            V: Into<AnyValue>,
+        {
+            self.attributes
+                .push((key.into().as_str().to_string(), value.into()));
+        }
+    }
+
+    #[derive(Default, Clone)]
+    pub(crate) struct TestLogger {
+        pub(crate) records: Arc<Mutex<Vec<TestRecord>>>,
+    }
+
+    impl Logger for TestLogger {
+        type LogRecord = TestRecord;
+
+        fn create_log_record(&self) -> Self::LogRecord {
+            TestRecord::default()
+        }
+
+        fn emit(&self, record: Self::LogRecord) {
+            self.records.lock().unwrap().push(record);
+        }
+
+        fn event_enabled(&self, level: Severity, _target: &str, _name: Option<&str>) -> bool {
+            level >= Severity::Debug
+        }
+    }
+
+    #[test]
+    fn emit_should_map_level_and_fields() {
+        let otel_logger = TestLogger::default();
+        let sink = OtelSink::new(otel_logger.clone());
+
+        sink.emit(
+            LogLevel::Critical,
+            "test",
+            Some("abc"),
+            &[("user_id", "42")],
+            "message",
+        );
+        sink.emit(LogLevel::Trace, "test", None, &[], "disabled");
+
+        let records = otel_logger.records.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].severity, Some(Severity::Fatal));
+        assert_eq!(records[0].severity_text, Some("critical"));
+        assert_eq!(records[0].target.as_deref(), Some("test"));
+        assert_eq!(
+            records[0].body,
+            Some(AnyValue::from(String::from("message")))
+        );
+        assert_eq!(
+            records[0].attributes,
+            vec![
+                (
+                    String::from("trace_id"),
+                    AnyValue::from(String::from("abc"))
+                ),
+                (String::from("user_id"), AnyValue::from(String::from("42")))
+            ]
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+/// Caps the number of bytes that may be written per second.
+pub(crate) struct ByteRateLimiter {
+    max_bytes_per_sec: Option<u64>,
+    window_start: Instant,
+    bytes_in_window: u64,
+    suppressed_bytes: u64,
+}
+
+impl ByteRateLimiter {
+    pub(crate) fn new(max_bytes_per_sec: Option<u64>) -> Self {
+        Self {
+            max_bytes_per_sec,
+            window_start: Instant::now(),
+            bytes_in_window: 0,
+            suppressed_bytes: 0,
+        }
+    }
+
+    pub(crate) fn set_max_bytes_per_sec(&mut self, max_bytes_per_sec: Option<u64>) {
+        self.max_bytes_per_sec = max_bytes_per_sec;
+        self.window_start = Instant::now();
+        self.bytes_in_window = 0;
+    }
+
+    pub(crate) fn max_bytes_per_sec(&self) -> Option<u64> {
+        self.max_bytes_per_sec
+    }
+
+    pub(crate) fn suppressed_bytes(&self) -> u64 {
+        self.suppressed_bytes
+    }
+
+    /// Returns `true` if `len` bytes may be written now, and accounts for them.
+    /// Returns `false` and counts the bytes as suppressed otherwise.
+    pub(crate) fn allow(&mut self, len: usize) -> bool {
+        self.allow_at(len, Instant::now())
+    }
+
+    fn allow_at(&mut self, len: usize, now: Instant) -> bool {
+        let max_bytes_per_sec = match self.max_bytes_per_sec {
+            Some(max_bytes_per_sec) => max_bytes_per_sec,
+            None => return true,
+        };
+
+        if now.duration_since(self.window_start) >= Duration::from_secs(1) {
+            self.window_start = now;
+            self.bytes_in_window = 0;
+        }
+
+        let len = len as u64;
+
+        if self.bytes_in_window + len > max_bytes_per_sec {
+            self.suppressed_bytes += len;
+            return false;
+        }
+
+        self.bytes_in_window += len;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_should_allow_everything() {
+        let mut limiter = ByteRateLimiter::new(None);
+
+        assert!(limiter.allow(usize::MAX / 2));
+        assert_eq!(limiter.suppressed_bytes(), 0);
+    }
+
+    #[test]
+    fn should_suppress_bytes_above_limit_within_window() {
+        let mut limiter = ByteRateLimiter::new(Some(10));
+        let now = limiter.window_start;
+
+        assert!(limiter.allow_at(6, now));
+        assert!(!limiter.allow_at(6, now));
+        assert!(limiter.allow_at(4, now));
+        assert_eq!(limiter.suppressed_bytes(), 6);
+    }
+
+    #[test]
+    fn should_allow_again_in_next_window() {
+        let mut limiter = ByteRateLimiter::new(Some(10));
+        let now = limiter.window_start;
+
+        assert!(limiter.allow_at(10, now));
+        assert!(!limiter.allow_at(1, now));
+        assert!(limiter.allow_at(10, now + Duration::from_secs(1)));
+        assert_eq!(limiter.suppressed_bytes(), 1);
+    }
+}
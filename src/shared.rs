@@ -0,0 +1,190 @@
+//! A logger that can be shared between threads
+
+use std::io;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::log_level::LogLevel;
+use crate::Logger;
+
+/// A [`Logger`] behind a mutex, with logging methods that take `&self`,
+/// so it can be shared between threads in an `Arc`.
+/// Every message is written while the lock is held, so lines of different threads are never interleaved.
+///
+/// If a thread panicked while holding the lock, the logger keeps working for the other threads.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::log_level::LogLevel;
+/// use ultimate_logger::shared::SharedLogger;
+///
+/// let logger = Arc::new(SharedLogger::new(Logger::new(String::from("example"), LogLevel::Info)));
+///
+/// let workers: Vec<_> = (0..4)
+///     .map(|i| {
+///         let logger = Arc::clone(&logger);
+///         thread::spawn(move || logger.info(&format!("Hello from worker {}", i)))
+///     })
+///     .collect();
+///
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+/// ```
+///
+/// This will log the following messages, in any order:
+/// [2020-12-31 23:59:59.999] [example] [info] Hello from worker 0
+/// [2020-12-31 23:59:59.999] [example] [info] Hello from worker 1
+/// [2020-12-31 23:59:59.999] [example] [info] Hello from worker 2
+/// [2020-12-31 23:59:59.999] [example] [info] Hello from worker 3
+pub struct SharedLogger {
+    logger: Mutex<Logger>,
+}
+
+impl SharedLogger {
+    /// Wraps a logger to share it between threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `logger` - The logger to share.
+    pub fn new(logger: Logger) -> Self {
+        Self {
+            logger: Mutex::new(logger),
+        }
+    }
+
+    /// Locks the logger, to change its settings or to log several messages without other threads in between.
+    /// Messages of other threads wait until the guard is dropped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    /// use ultimate_logger::shared::SharedLogger;
+    ///
+    /// let logger = SharedLogger::new(Logger::new(String::from("example"), LogLevel::Info));
+    ///
+    /// logger.lock().set_min_level(LogLevel::Debug);
+    /// ```
+    pub fn lock(&self) -> MutexGuard<'_, Logger> {
+        match self.logger.lock() {
+            Ok(logger) => logger,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Returns the logger, for example to drop it at a specific moment.
+    pub fn into_inner(self) -> Logger {
+        match self.logger.into_inner() {
+            Ok(logger) => logger,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Logs a message with the given log level, see `Logger::log`.
+    /// Returns `true` if the message was logged.
+    ///
+    /// # Arguments
+    ///
+    /// * `level` - The log level of the message.
+    /// * `message` - The message to log.
+    pub fn log(&self, level: LogLevel, message: &str) -> bool {
+        self.lock().log(level, message)
+    }
+
+    /// Logs a message with the log level "Trace", see `Logger::trace`.
+    pub fn trace(&self, message: &str) -> bool {
+        self.log(LogLevel::Trace, message)
+    }
+
+    /// Logs a message with the log level "Debug", see `Logger::debug`.
+    pub fn debug(&self, message: &str) -> bool {
+        self.log(LogLevel::Debug, message)
+    }
+
+    /// Logs a message with the log level "Info", see `Logger::info`.
+    pub fn info(&self, message: &str) -> bool {
+        self.log(LogLevel::Info, message)
+    }
+
+    /// Logs a message with the log level "Warning", see `Logger::warning`.
+    pub fn warning(&self, message: &str) -> bool {
+        self.log(LogLevel::Warning, message)
+    }
+
+    /// Logs a message with the log level "Error", see `Logger::error`.
+    pub fn error(&self, message: &str) -> bool {
+        self.log(LogLevel::Error, message)
+    }
+
+    /// Logs a message with the log level "Critical", see `Logger::critical`.
+    pub fn critical(&self, message: &str) -> bool {
+        self.log(LogLevel::Critical, message)
+    }
+
+    /// Writes the buffered messages to the files and writers of the logger, see `Logger::flush`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the first error that occurred while flushing.
+    pub fn flush(&self) -> io::Result<()> {
+        self.lock().flush()
+    }
+}
+
+impl From<Logger> for SharedLogger {
+    fn from(logger: Logger) -> Self {
+        Self::new(logger)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn concurrent_messages_should_be_complete_lines() {
+        let path = std::env::temp_dir().join("ultimate_logger_shared.log");
+        let _ = std::fs::remove_file(&path);
+        let logger = Arc::new(SharedLogger::new(Logger::new_to_file(
+            String::from("test"),
+            LogLevel::Trace,
+            path.to_string_lossy().into_owned(),
+            false,
+        )));
+
+        let workers: Vec<_> = (0..8)
+            .map(|thread| {
+                let logger = Arc::clone(&logger);
+                thread::spawn(move || {
+                    for i in 0..100 {
+                        logger.info(&format!("thread {} message {}", thread, i));
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        logger.flush().unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 800);
+        assert!(contents.ends_with('\n'));
+        for line in lines {
+            assert!(line.starts_with('['));
+            assert!(line.contains("] [test] [info] thread "));
+        }
+        for thread in 0..8 {
+            assert!(contents.contains(&format!("thread {} message 99\n", thread)));
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Sinks are the destinations a logger writes its messages to
+
+#[cfg(all(windows, feature = "eventlog"))]
+use crate::event_log::EventLog;
+use crate::log_file::SharedLogFile;
+use crate::log_level::LogLevel;
+#[cfg(feature = "otel")]
+use crate::otel::OtelSink;
+#[cfg(feature = "sqlite")]
+use crate::sqlite::SqliteTable;
+
+/// Identifies a sink of a logger.
+/// The id of a sink never changes, and isn't reused for other sinks of the same logger.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct SinkId(pub(crate) u64);
+
+/// The kind of destination of a sink.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum SinkKind {
+    /// The console.
+    Console,
+    /// A file.
+    File,
+    /// A writer, see `Logger::new_to_writer`.
+    Writer,
+    /// The Windows Event Log.
+    EventLog,
+    /// A SQLite table.
+    Sqlite,
+    /// An OpenTelemetry logger.
+    OpenTelemetry,
+}
+
+/// What a logger does with a write to a pipe (a FIFO) that is larger than the number of bytes
+/// the operating system writes atomically (`PIPE_BUF`), to use with `Logger::set_pipe_atomic_policy`.
+/// Such a write may interleave with writes of other processes to the same pipe.
+/// Writes to regular files are never affected.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PipeAtomicPolicy {
+    /// Write the message anyway, accepting that it may be split and interleaved. This is the default.
+    Split,
+    /// Discard the message, and count it (see `Logger::discarded_pipe_writes`), so every line in the pipe is intact.
+    Discard,
+}
+
+impl Default for PipeAtomicPolicy {
+    fn default() -> Self {
+        PipeAtomicPolicy::Split
+    }
+}
+
+/// What a logger does when a file it opens for a file sink turns out not to be a regular file,
+/// but a pipe, socket or device, to use with `Logger::set_special_file_policy`.
+/// Writes to such files may block indefinitely, for example when nobody reads from a pipe.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SpecialFilePolicy {
+    /// Write to the file like to a regular file. This is the default.
+    Allow,
+    /// Write to the file, but print a warning to stderr.
+    Warn,
+    /// Don't add the sink, and return an error instead.
+    Reject,
+}
+
+impl Default for SpecialFilePolicy {
+    fn default() -> Self {
+        SpecialFilePolicy::Allow
+    }
+}
+
+/// The format a logger writes its file and writer sinks in, to use with `Logger::set_output_format`.
+/// The console gets text, except in the JSON format.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum OutputFormat {
+    /// Lines of text, like `[2020-05-01 12:00:00.000] [example] [info] message`. This is the default.
+    Text,
+    /// The compact binary format of the [`binary`](crate::binary) module, which is much cheaper to write than text.
+    /// Read it back with `binary::decode_binary_log`.
+    /// The timestamp format, the compact date mode, line numbers and scopes don't apply to it.
+    Binary,
+    /// One JSON object per line (JSON Lines), with the keys `timestamp` (RFC 3339 with the UTC offset, or ending in `Z` if the logger uses UTC),
+    /// `logger`, `level` and `message`, and if they have a value `source`, `trace_id`, `scopes` and `payload`.
+    /// The console gets the same lines, without colors.
+    /// The timestamp format, the compact date mode, line numbers and the legend don't apply to it.
+    ///
+    /// ```
+    /// use ultimate_logger::Logger;
+    /// use ultimate_logger::log_level::LogLevel;
+    /// use ultimate_logger::sink::OutputFormat;
+    ///
+    /// let mut logger = Logger::new_default(String::from("example"));
+    /// logger.set_output_format(OutputFormat::Json);
+    ///
+    /// logger.info("Said \"hi\"\ntwice");
+    /// ```
+    ///
+    /// This will log the following line:
+    ///
+    /// ```text
+    /// {"timestamp":"2020-12-31T23:59:59.999+01:00","logger":"example","level":"info","message":"Said \"hi\"\ntwice"}
+    /// ```
+    Json,
+    /// Both text and JSON: every message is written as its lines of text, followed by its JSON line.
+    /// JSON lines can be told apart from lines of text because they start with `{`.
+    /// The text lines and the JSON lines can have their own minimum log level, see `Logger::set_dual_min_levels`,
+    /// for example to keep every message for ingestion but only warnings in the text that is tailed.
+    /// The console gets text. Line numbers count the JSON lines too.
+    Dual,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Text
+    }
+}
+
+/// What a logger does when a file sink is added for a file that another file sink of the logger already writes to,
+/// to use with `Logger::set_duplicate_sink_policy`.
+/// Two paths refer to the same file if they resolve to the same canonical path,
+/// so relative paths, `..` and symbolic links are taken into account.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DuplicateSinkPolicy {
+    /// Add the sink anyway, so every message is written to the file twice.
+    Allow,
+    /// Add the sink anyway, but print a warning to stderr. This is the default.
+    Warn,
+    /// Don't add a new sink, but return the id of the existing sink,
+    /// and lower its minimum log level if the new sink would accept more messages.
+    Merge,
+}
+
+impl Default for DuplicateSinkPolicy {
+    fn default() -> Self {
+        DuplicateSinkPolicy::Warn
+    }
+}
+
+/// Information about a sink of a logger, as returned by `Logger::sinks`.
+///
+/// # Examples
+///
+/// ```
+/// use ultimate_logger::Logger;
+/// use ultimate_logger::log_level::LogLevel;
+/// use ultimate_logger::sink::SinkKind;
+///
+/// let logger = Logger::new(String::from("example"), LogLevel::Info);
+/// let sinks = logger.sinks();
+///
+/// assert_eq!(sinks.len(), 1);
+/// assert_eq!(sinks[0].kind, SinkKind::Console);
+/// assert_eq!(sinks[0].path, None);
+/// assert_eq!(sinks[0].min_level, LogLevel::Info);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SinkInfo {
+    /// The id of the sink.
+    pub id: SinkId,
+    /// The kind of the sink.
+    pub kind: SinkKind,
+    /// The path of the file if the sink writes to a file, the source name if it writes to the Windows Event Log,
+    /// or the name of the table if it writes to a SQLite table.
+    pub path: Option<String>,
+    /// The minimum log level of messages written to the sink.
+    /// This takes the minimum log level of the logger into account.
+    pub min_level: LogLevel,
+}
+
+pub(crate) enum SinkTarget {
+    Console,
+    File(SharedLogFile),
+    Writer(SharedLogFile),
+    #[cfg(all(windows, feature = "eventlog"))]
+    EventLog(EventLog),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqliteTable),
+    #[cfg(feature = "otel")]
+    OpenTelemetry(OtelSink),
+}
+
+impl SinkTarget {
+    /// Returns whether the target can be described in a `config::SinkConfig`.
+    /// Other targets, like writers, are kept as they are when the logger is reconfigured.
+    pub(crate) fn is_configurable(&self) -> bool {
+        matches!(self, SinkTarget::Console | SinkTarget::File(_))
+    }
+}
+
+pub(crate) struct Sink {
+    pub(crate) id: SinkId,
+    pub(crate) target: SinkTarget,
+    pub(crate) min_level: Option<LogLevel>,
+}
+
+impl Sink {
+    pub(crate) fn accepts(&self, level: LogLevel) -> bool {
+        match self.min_level {
+            Some(min_level) => level >= min_level,
+            None => true,
+        }
+    }
+
+    pub(crate) fn info(&self, logger_min_level: LogLevel) -> SinkInfo {
+        let (kind, path) = match &self.target {
+            SinkTarget::Console => (SinkKind::Console, None),
+            SinkTarget::File(log_file) => {
+                (SinkKind::File, Some(log_file.lock().path().to_string()))
+            }
+            SinkTarget::Writer(_) => (SinkKind::Writer, None),
+            #[cfg(all(windows, feature = "eventlog"))]
+            SinkTarget::EventLog(event_log) => (
+                SinkKind::EventLog,
+                Some(event_log.source_name().to_string()),
+            ),
+            #[cfg(feature = "sqlite")]
+            SinkTarget::Sqlite(sqlite_table) => {
+                (SinkKind::Sqlite, Some(sqlite_table.table().to_string()))
+            }
+            #[cfg(feature = "otel")]
+            SinkTarget::OpenTelemetry(_) => (SinkKind::OpenTelemetry, None),
+        };
+
+        SinkInfo {
+            id: self.id,
+            kind,
+            path,
+            min_level: match self.min_level {
+                Some(min_level) => min_level.max(logger_min_level),
+                None => logger_min_level,
+            },
+        }
+    }
+}
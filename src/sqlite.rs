@@ -0,0 +1,187 @@
+use std::io;
+
+use rusqlite::{params, Connection};
+
+use crate::log_level::LogLevel;
+
+/// The number of rows that are inserted at once, in a single transaction.
+const BATCH_SIZE: usize = 64;
+
+struct Row {
+    timestamp: String,
+    logger: String,
+    level: LogLevel,
+    trace_id: Option<String>,
+    message: String,
+    fields: Option<String>,
+}
+
+pub(crate) struct SqliteTable {
+    connection: Connection,
+    table: String,
+    pending: Vec<Row>,
+}
+
+fn to_io_error(e: rusqlite::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, e)
+}
+
+/// Table names are put in the SQL statements as they are, so only plain identifiers are allowed.
+fn is_valid_table_name(table: &str) -> bool {
+    let mut chars = table.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl SqliteTable {
+    pub(crate) fn new(connection: Connection, table: &str) -> io::Result<Self> {
+        if !is_valid_table_name(table) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid SQLite table name: {:?}", table),
+            ));
+        }
+
+        connection
+            .execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    id INTEGER PRIMARY KEY,
+                    timestamp TEXT NOT NULL,
+                    logger TEXT NOT NULL,
+                    level TEXT NOT NULL,
+                    trace_id TEXT,
+                    message TEXT NOT NULL,
+                    fields TEXT
+                )",
+                table
+            ))
+            .map_err(to_io_error)?;
+
+        Ok(Self {
+            connection,
+            table: table.to_string(),
+            pending: Vec::new(),
+        })
+    }
+
+    pub(crate) fn table(&self) -> &str {
+        &self.table
+    }
+
+    pub(crate) fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        timestamp: &str,
+        logger: &str,
+        level: LogLevel,
+        trace_id: Option<&str>,
+        message: &str,
+        fields: Option<&str>,
+    ) -> io::Result<()> {
+        self.pending.push(Row {
+            timestamp: timestamp.to_string(),
+            logger: logger.to_string(),
+            level,
+            trace_id: trace_id.map(str::to_string),
+            message: message.to_string(),
+            fields: fields.map(str::to_string),
+        });
+
+        if self.pending.len() >= BATCH_SIZE {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let transaction = self.connection.transaction().map_err(to_io_error)?;
+
+        {
+            let mut statement = transaction
+                .prepare_cached(&format!(
+                    "INSERT INTO {} (timestamp, logger, level, trace_id, message, fields) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    self.table
+                ))
+                .map_err(to_io_error)?;
+
+            for row in &self.pending {
+                statement
+                    .execute(params![
+                        row.timestamp,
+                        row.logger,
+                        row.level.as_str(),
+                        row.trace_id,
+                        row.message,
+                        row.fields
+                    ])
+                    .map_err(to_io_error)?;
+            }
+        }
+
+        transaction.commit().map_err(to_io_error)?;
+        self.pending.clear();
+
+        Ok(())
+    }
+}
+
+impl Drop for SqliteTable {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            eprintln!(
+                "Error writing to the SQLite table: {}\nTable was: {}",
+                e, self.table
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_table_name() {
+        assert!(is_valid_table_name("logs"));
+        assert!(is_valid_table_name("_app_logs_2"));
+        assert!(!is_valid_table_name(""));
+        assert!(!is_valid_table_name("2logs"));
+        assert!(!is_valid_table_name("logs; DROP TABLE users"));
+    }
+
+    #[test]
+    fn insert_should_batch_rows() {
+        let mut table = SqliteTable::new(Connection::open_in_memory().unwrap(), "logs").unwrap();
+        let count = |table: &SqliteTable| -> i64 {
+            table
+                .connection()
+                .query_row("SELECT COUNT(*) FROM logs", [], |row| row.get(0))
+                .unwrap()
+        };
+
+        for _ in 0..BATCH_SIZE - 1 {
+            table
+                .insert("time", "test", LogLevel::Info, None, "message", None)
+                .unwrap();
+        }
+        assert_eq!(count(&table), 0);
+
+        table
+            .insert("time", "test", LogLevel::Info, None, "message", None)
+            .unwrap();
+        assert_eq!(count(&table), BATCH_SIZE as i64);
+    }
+}
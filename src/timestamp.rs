@@ -0,0 +1,113 @@
+//! Timestamp presets
+
+use chrono::format::{Item, StrftimeItems};
+
+/// Returns `true` if the chrono format string has no invalid specifiers, so formatting with it can't fail.
+pub(crate) fn is_valid_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+}
+
+/// A named timestamp format, to use with `Logger::set_timestamp_preset`.
+///
+/// # Examples
+///
+/// ```
+/// use ultimate_logger::timestamp::TimestampPreset;
+///
+/// assert_eq!(TimestampPreset::Default.format_str(), "%F %T%.3f");
+/// assert_eq!(TimestampPreset::Rfc3339.format_str(), "%Y-%m-%dT%H:%M:%S%.3f%:z");
+/// assert!(TimestampPreset::HttpDate.uses_utc());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TimestampPreset {
+    /// The default format in local time, for example `2020-05-01 12:00:00.000`.
+    Default,
+    /// RFC 3339 in local time with the UTC offset, for example `2020-05-01T12:00:00.000+02:00`.
+    Rfc3339,
+    /// ISO 8601 in UTC, for example `2020-05-01T10:00:00.000Z`.
+    Iso8601,
+    /// The format of the HTTP `Date` header, always in UTC, for example `Fri, 01 May 2020 10:00:00 GMT`.
+    HttpDate,
+    /// Seconds since the Unix epoch with milliseconds, for example `1588327200.000`.
+    Unix,
+}
+
+impl TimestampPreset {
+    /// Returns the chrono format string of the preset.
+    pub fn format_str(self) -> &'static str {
+        match self {
+            TimestampPreset::Default => "%F %T%.3f",
+            TimestampPreset::Rfc3339 => "%Y-%m-%dT%H:%M:%S%.3f%:z",
+            TimestampPreset::Iso8601 => "%Y-%m-%dT%H:%M:%S%.3fZ",
+            TimestampPreset::HttpDate => "%a, %d %b %Y %H:%M:%S GMT",
+            TimestampPreset::Unix => "%s%.3f",
+        }
+    }
+
+    /// Returns `true` if the preset formats the time in UTC instead of local time.
+    pub fn uses_utc(self) -> bool {
+        match self {
+            TimestampPreset::Default | TimestampPreset::Rfc3339 => false,
+            TimestampPreset::Iso8601 | TimestampPreset::HttpDate | TimestampPreset::Unix => true,
+        }
+    }
+}
+
+/// How the timestamps are shown, to use with `Logger::set_timestamp_style`.
+///
+/// # Examples
+///
+/// ```
+/// use ultimate_logger::timestamp::TimestampStyle;
+///
+/// assert_eq!(TimestampStyle::default(), TimestampStyle::Wall);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TimestampStyle {
+    /// The wall clock time, in the timestamp format of the logger, for example `2020-05-01 12:00:00.000`.
+    Wall,
+    /// The seconds since the logger was created, with milliseconds, for example `+0.123s`.
+    Relative,
+}
+
+impl Default for TimestampStyle {
+    fn default() -> Self {
+        TimestampStyle::Wall
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_format_str() {
+        let time = Utc.with_ymd_and_hms(2020, 5, 1, 10, 0, 0).unwrap();
+
+        assert_eq!(
+            time.format(TimestampPreset::Default.format_str())
+                .to_string(),
+            "2020-05-01 10:00:00.000"
+        );
+        assert_eq!(
+            time.format(TimestampPreset::Rfc3339.format_str())
+                .to_string(),
+            "2020-05-01T10:00:00.000+00:00"
+        );
+        assert_eq!(
+            time.format(TimestampPreset::Iso8601.format_str())
+                .to_string(),
+            "2020-05-01T10:00:00.000Z"
+        );
+        assert_eq!(
+            time.format(TimestampPreset::HttpDate.format_str())
+                .to_string(),
+            "Fri, 01 May 2020 10:00:00 GMT"
+        );
+        assert_eq!(
+            time.format(TimestampPreset::Unix.format_str()).to_string(),
+            "1588327200.000"
+        );
+    }
+}